@@ -0,0 +1,29 @@
+#ifndef SWAGGER_CONVERT_H
+#define SWAGGER_CONVERT_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/*
+ * Converts a NUL-terminated Swagger 2.0 JSON document into an OpenAPI 3.0 JSON document.
+ *
+ * Returns a newly allocated string which must be released with swagger_convert_free(),
+ * or NULL on failure, in which case swagger_convert_last_error() describes the error.
+ */
+char *swagger_convert_json(const char *swagger);
+
+/*
+ * Returns the error of the last failed swagger_convert_json() call on the calling thread,
+ * or NULL. The string is owned by the library and valid until the next conversion call.
+ */
+const char *swagger_convert_last_error(void);
+
+/* Releases a string returned by swagger_convert_json(). */
+void swagger_convert_free(char *openapi);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* SWAGGER_CONVERT_H */
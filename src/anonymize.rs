@@ -0,0 +1,283 @@
+//! Anonymization of documents before they are shared, see
+//! [`ConvertOptions::anonymize`](crate::ConvertOptions::anonymize).
+//!
+//! Free texts, contact details, hosts and servers are replaced with placeholders and examples
+//! and defaults with values of the same shape, e.g. `{"name": "Rex", "age": 3}` becomes
+//! `{"name": "string", "age": 0}`. Defaults of enums become their first value. Paths, names
+//! and schemas, including their enum values, stay untouched, so the document converts the
+//! same way.
+
+use serde_json::{json, Map, Value};
+
+use crate::{spec::Swagger, walk};
+
+const TEXT: &str = "redacted";
+const HOST: &str = "example.com";
+const URL: &str = "https://example.com";
+
+/// Replaces the descriptions, examples, contact details, hosts and servers of `document`
+/// with placeholders.
+pub(crate) fn anonymize(document: &mut Value) {
+    if let Value::Object(root) = document {
+        if root.contains_key("host") {
+            root.insert("host".to_string(), json!(HOST));
+        }
+        if let Some(Value::Array(hosts)) = root.get_mut("x-hosts") {
+            for (i, host) in hosts.iter_mut().enumerate() {
+                let name = json!(format!("host{}.{HOST}", i + 1));
+                match host {
+                    Value::Object(environment) => {
+                        replace(environment, "host", name);
+                        replace(environment, "description", json!(TEXT));
+                    }
+                    host => *host = name,
+                }
+            }
+        }
+        if let Some(Value::Object(variables)) = root.get_mut("x-server-variables") {
+            variables.values_mut().for_each(server_variable);
+        }
+        if let Some(Value::Object(info)) = root.get_mut("info") {
+            info_object(info);
+        }
+    }
+    walk::enter_objects(document, &mut String::new(), &mut |object, _| {
+        keywords(object)
+    });
+}
+
+/// Document standing in for an anonymized one which failed to parse, so nothing of the
+/// original is converted.
+pub(crate) fn placeholder() -> Swagger {
+    serde_json::from_value(json!({
+        "swagger": "2.0",
+        "info": {"title": TEXT, "version": TEXT},
+        "paths": {}
+    }))
+    .expect("placeholder document is valid")
+}
+
+fn info_object(info: &mut Map<String, Value>) {
+    replace(info, "title", json!(TEXT));
+    replace(info, "termsOfService", json!(URL));
+    if let Some(Value::Object(contact)) = info.get_mut("contact") {
+        replace(contact, "name", json!(TEXT));
+        replace(contact, "url", json!(URL));
+        replace(contact, "email", json!(format!("{TEXT}@{HOST}")));
+    }
+    if let Some(Value::Object(license)) = info.get_mut("license") {
+        replace(license, "url", json!(URL));
+    }
+}
+
+/// Replaces the member `key` of `object` if it is present.
+fn replace(object: &mut Map<String, Value>, key: &str, value: Value) {
+    if let Some(member) = object.get_mut(key) {
+        *member = value;
+    }
+}
+
+/// Anonymizes the keywords of `object`.
+fn keywords(object: &mut Map<String, Value>) {
+    if let Some(default) = object.get("default") {
+        let enum_value = object
+            .get("enum")
+            .and_then(Value::as_array)
+            .and_then(|values| values.first());
+        let default = match enum_value {
+            Some(value) => value.clone(),
+            None => {
+                let mut default = default.clone();
+                shape(&mut default);
+                default
+            }
+        };
+        object.insert("default".to_string(), default);
+    }
+    for (key, value) in object.iter_mut() {
+        match (key.as_str(), value) {
+            ("description" | "summary", Value::String(text)) => *text = TEXT.to_string(),
+            ("example" | "x-example", example) => shape(example),
+            ("examples", Value::Object(examples)) => examples.values_mut().for_each(shape),
+            ("scopes", Value::Object(scopes)) => scopes.values_mut().for_each(|s| *s = json!(TEXT)),
+            ("externalDocs", Value::Object(docs)) => {
+                replace(docs, "description", json!(TEXT));
+                replace(docs, "url", json!(URL));
+            }
+            ("x-servers", Value::Array(servers)) => servers.iter_mut().for_each(server),
+            ("x-tagGroups", Value::Array(groups)) => {
+                for group in groups.iter_mut().filter_map(Value::as_object_mut) {
+                    replace(group, "description", json!(TEXT));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Replaces the URL and the description of a server of `x-servers`.
+fn server(server: &mut Value) {
+    let Value::Object(server) = server else {
+        return;
+    };
+    replace(server, "url", json!(URL));
+    replace(server, "description", json!(TEXT));
+    // the placeholders of the URL are gone with it
+    server.remove("variables");
+}
+
+/// Replaces the values of a server variable, which may name hosts.
+fn server_variable(variable: &mut Value) {
+    let Value::Object(variable) = variable else {
+        return;
+    };
+    replace(variable, "default", json!(TEXT));
+    replace(variable, "description", json!(TEXT));
+    variable.remove("enum");
+}
+
+/// Replaces the scalars within `value` with placeholders of the same type.
+fn shape(value: &mut Value) {
+    match value {
+        Value::String(s) => *s = "string".to_string(),
+        Value::Number(n) => *n = 0.into(),
+        Value::Bool(b) => *b = false,
+        Value::Array(items) => items.iter_mut().for_each(shape),
+        Value::Object(members) => members.values_mut().for_each(shape),
+        Value::Null => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::ConvertOptions;
+
+    #[test]
+    fn anonymize_document() {
+        let swagger = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {
+                "title": "Internal billing",
+                "version": "1.0",
+                "contact": {"name": "Jane", "email": "jane@corp.internal"}
+            },
+            "host": "billing.corp.internal",
+            "schemes": ["https"],
+            "paths": {
+                "/invoices": {
+                    "get": {
+                        "summary": "List the invoices of a customer",
+                        "responses": {"200": {"description": "The invoices"}}
+                    }
+                }
+            },
+            "definitions": {
+                "Invoice": {
+                    "type": "object",
+                    "description": "A billed order",
+                    "properties": {
+                        "description": {
+                            "type": "string",
+                            "description": "Free text",
+                            "default": "Office supplies"
+                        },
+                        "currency": {"type": "string", "enum": ["EUR", "USD"], "default": "USD"}
+                    },
+                    "example": {"description": "Six chairs", "total": 129.5}
+                }
+            }
+        }))
+        .unwrap();
+        let options = ConvertOptions {
+            anonymize: true,
+            ..Default::default()
+        };
+
+        let document = crate::convert_with(swagger, &options).to_value();
+
+        assert_eq!(document["info"]["title"], "redacted");
+        assert_eq!(document["info"]["contact"]["email"], "redacted@example.com");
+        assert_eq!(
+            document["servers"],
+            json!([{"url": "https://example.com/"}])
+        );
+        assert_eq!(document["paths"]["/invoices"]["get"]["summary"], "redacted");
+        assert_eq!(
+            document["components"]["schemas"]["Invoice"],
+            json!({
+                "type": "object",
+                "description": "redacted",
+                "properties": {
+                    "description": {
+                        "type": "string",
+                        "description": "redacted",
+                        "default": "string"
+                    },
+                    "currency": {"type": "string", "enum": ["EUR", "USD"], "default": "EUR"}
+                },
+                "example": {"description": "string", "total": 0}
+            })
+        );
+    }
+
+    #[test]
+    fn anonymize_servers() {
+        let swagger: Swagger = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {"title": "Billing", "version": "1.0"},
+            "basePath": "/{version}",
+            "schemes": ["https"],
+            "x-hosts": [
+                "billing.corp.internal",
+                {"host": "staging.corp.internal", "description": "Staging at corp"}
+            ],
+            "x-server-variables": {
+                "version": {"default": "v1", "enum": ["v1", "v2.corp.internal"]}
+            },
+            "x-tagGroups": [
+                {"name": "Billing", "description": "Owned by corp", "tags": ["invoices"]}
+            ],
+            "paths": {
+                "/invoices": {
+                    "get": {
+                        "x-servers": [{
+                            "url": "https://{region}.cache.corp.internal",
+                            "description": "Cache at corp",
+                            "variables": {"region": {"default": "eu"}}
+                        }],
+                        "responses": {"200": {"description": "The invoices"}}
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        let options = ConvertOptions {
+            anonymize: true,
+            ..Default::default()
+        };
+
+        let document = crate::convert_with(swagger, &options).to_value();
+
+        assert!(!document.to_string().contains("corp"));
+        assert_eq!(
+            document["servers"],
+            json!([
+                {
+                    "url": "https://host1.example.com/{version}",
+                    "variables": {"version": {"default": "redacted"}}
+                },
+                {
+                    "url": "https://host2.example.com/{version}",
+                    "description": "redacted",
+                    "variables": {"version": {"default": "redacted"}}
+                }
+            ])
+        );
+        assert_eq!(
+            document["paths"]["/invoices"]["get"]["servers"],
+            json!([{"url": "https://example.com", "description": "redacted"}])
+        );
+    }
+}
@@ -0,0 +1,87 @@
+//! Asynchronous entry points for services converting documents on a tokio runtime.
+//!
+//! Reading the source and writing the result are asynchronous. The conversion itself is CPU
+//! bound, it runs on the blocking pool of the runtime so it does not stall other tasks.
+//!
+//! The conversion only follows local `$ref`s, there are no remote references to resolve
+//! asynchronously.
+
+use std::panic;
+
+use tokio::{
+    io::{AsyncRead, AsyncReadExt},
+    task,
+};
+
+use crate::{convert_str, Conversion, ConvertOptions, Error};
+
+/// Reads a Swagger 2.0 JSON document from `reader` and converts it like [`convert_str`].
+pub async fn convert_async_reader<R: AsyncRead + Unpin>(
+    mut reader: R,
+    options: &ConvertOptions,
+) -> Result<Conversion, Error> {
+    let mut source = String::new();
+    reader
+        .read_to_string(&mut source)
+        .await
+        .map_err(Error::Read)?;
+    convert_blocking(source, options).await
+}
+
+/// Fetches a Swagger 2.0 JSON document from `url` and converts it like [`convert_str`].
+///
+/// Responses with an error status fail with [`Error::Fetch`].
+pub async fn convert_url(url: &str, options: &ConvertOptions) -> Result<Conversion, Error> {
+    let source = reqwest::get(url)
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(Error::Fetch)?
+        .text()
+        .await
+        .map_err(Error::Fetch)?;
+    convert_blocking(source, options).await
+}
+
+/// Converts `source` like [`convert_str`] on the blocking pool, resuming its panics.
+async fn convert_blocking(source: String, options: &ConvertOptions) -> Result<Conversion, Error> {
+    let options = options.clone();
+    match task::spawn_blocking(move || convert_str(&source, &options)).await {
+        Ok(conversion) => conversion,
+        Err(err) => panic::resume_unwind(err.into_panic()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::output::{OutputOptions, Syntax};
+
+    #[tokio::test]
+    async fn convert_and_write_async() {
+        let source = json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "paths": {}
+        })
+        .to_string();
+        let options = ConvertOptions {
+            output: OutputOptions {
+                syntax: Syntax::CompactJson,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let conversion = convert_async_reader(source.as_bytes(), &options)
+            .await
+            .unwrap();
+        let mut output = Vec::new();
+        conversion.to_async_writer(&mut output).await.unwrap();
+
+        assert!(!output.contains(&b'\n'));
+        let document: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(document["info"]["title"], "Pets");
+    }
+}
@@ -1,14 +1,30 @@
 use std::{
     fs::File,
-    io::{BufReader, BufWriter},
+    io::{BufReader, BufWriter, ErrorKind, Write},
+    path::Path,
 };
 
 use anyhow::{anyhow, Result};
-use clap::{Arg, Command};
-use spec::Swagger;
-use utoipa::openapi::OpenApi;
+use clap::{value_parser, Arg, ArgAction, ArgMatches, Command};
+use utoipa::openapi::{
+    info::{ContactBuilder, LicenseBuilder},
+    server::ServerVariable,
+    ExternalDocs,
+};
 
-use swagger_convert::spec;
+use swagger_convert::{
+    compare::{self, Bump},
+    diagnostics, export,
+    output::{Layout, Order, OutputOptions, Syntax, Target},
+    prune::PruneOptions,
+    sanitize::{HtmlHandling, SanitizeOptions},
+    sink::Sink,
+    spec::{
+        BasePath, Casing, ExtensionRegistry, OperationIdTemplate, RefNamespace, RequiredSecurity,
+        SecurityTarget,
+    },
+    ConvertOptions,
+};
 
 fn main() {
     let mut cmd = Command::new("swagger-convert")
@@ -18,13 +34,420 @@ fn main() {
                 .help("Path to swagger 2.0 spec")
                 .value_hint(clap::ValueHint::FilePath),
         )
+        .arg(out_arg())
         .arg(
-            Arg::new("out")
-                .short('o')
-                .long("out")
-                .default_value("./openapi.json")
-                .help("Output OpenAPI file path")
-                .value_hint(clap::ValueHint::FilePath),
+            Arg::new("format")
+                .long("format")
+                .value_parser([
+                    "openapi",
+                    "postman",
+                    "insomnia",
+                    "markdown",
+                    "html",
+                    "json-schema",
+                ])
+                .default_value("openapi")
+                .help("Format of the output file, json-schema writes a directory of schemas"),
+        )
+        .arg(
+            Arg::new("draft")
+                .long("draft")
+                .value_parser(["4", "7", "2020-12"])
+                .default_value("7")
+                .help("JSON Schema draft of the json-schema format"),
+        )
+        .arg(
+            Arg::new("target")
+                .long("target")
+                .value_parser(["3.0", "3.1"])
+                .default_value("3.0")
+                .help("OpenAPI version of the output file"),
+        )
+        .arg(
+            Arg::new("order")
+                .long("order")
+                .value_parser(["alphabetical", "source", "tag"])
+                .default_value("alphabetical")
+                .help("Order of paths and schemas in the output file"),
+        )
+        .arg(
+            Arg::new("dialect")
+                .long("dialect")
+                .value_name("URI")
+                .help("jsonSchemaDialect of OpenAPI 3.1 output"),
+        )
+        .arg(
+            Arg::new("schema-dialect")
+                .long("schema-dialect")
+                .action(ArgAction::SetTrue)
+                .help("Add the dialect as $schema to component schemas of OpenAPI 3.1 output"),
+        )
+        .arg(
+            Arg::new("split")
+                .long("split")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("format")
+                .help("Write component schemas into files of their own next to the OpenAPI file"),
+        )
+        .arg(
+            Arg::new("schemas-dir")
+                .long("schemas-dir")
+                .default_value("schemas")
+                .help("Directory of the schema files with --split, relative to the OpenAPI file"),
+        )
+        .arg(
+            Arg::new("stamp")
+                .long("stamp")
+                .action(ArgAction::SetTrue)
+                .help("Record the converter version, input hash and time as x-converted-from"),
+        )
+        .arg(
+            Arg::new("slim")
+                .long("slim")
+                .action(ArgAction::SetTrue)
+                .help("Drop descriptions, examples and external docs from the OpenAPI file"),
+        )
+        .arg(
+            Arg::new("minify")
+                .long("minify")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("yaml")
+                .help("Write JSON output without indentation"),
+        )
+        .arg(
+            Arg::new("yaml")
+                .long("yaml")
+                .action(ArgAction::SetTrue)
+                .help("Write the OpenAPI file and other JSON output as YAML"),
+        )
+        .arg(
+            Arg::new("html")
+                .long("html")
+                .value_parser(["keep", "strip", "markdown"])
+                .default_value("keep")
+                .help("How HTML markup in descriptions is handled"),
+        )
+        .arg(
+            Arg::new("normalize-line-endings")
+                .long("normalize-line-endings")
+                .action(ArgAction::SetTrue)
+                .help("Convert Windows line endings in descriptions to \\n"),
+        )
+        .arg(
+            Arg::new("max-description-length")
+                .long("max-description-length")
+                .value_parser(value_parser!(usize))
+                .help("Truncate descriptions to at most this many characters"),
+        )
+        .arg(
+            Arg::new("stream")
+                .long("stream")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Convert while reading, for very large specs (diagnostics lack line numbers)",
+                ),
+        )
+        .arg(
+            Arg::new("cache-schemas")
+                .long("cache-schemas")
+                .action(ArgAction::SetTrue)
+                .help("Convert identical inline schemas only once"),
+        )
+        .arg(
+            Arg::new("partial")
+                .long("partial")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("stream")
+                .help("Skip paths and definitions which fail to parse instead of failing"),
+        )
+        .arg(
+            Arg::new("examples")
+                .long("examples")
+                .action(ArgAction::SetTrue)
+                .help("Generate examples for schemas and media types without one"),
+        )
+        .arg(
+            Arg::new("diagnostics-format")
+                .long("diagnostics-format")
+                .value_parser(["text", "github"])
+                .default_value("text")
+                .help("Print diagnostics as text or as GitHub Actions annotations"),
+        )
+        .arg(
+            Arg::new("baseline")
+                .long("baseline")
+                .value_name("FILE")
+                .value_hint(clap::ValueHint::FilePath)
+                .help("Only report findings missing from FILE, which is created with the current findings if it does not exist"),
+        )
+        .arg(
+            Arg::new("ref-prefix")
+                .long("ref-prefix")
+                .value_name("PREFIX")
+                .help("Prefix the names of components and the references to them"),
+        )
+        .arg(
+            Arg::new("ref-document")
+                .long("ref-document")
+                .value_name("FILE")
+                .help("Point references at the components of another document"),
+        )
+        .arg(
+            Arg::new("exclude-deprecated")
+                .long("exclude-deprecated")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("stream")
+                .help("Drop deprecated operations and schema properties"),
+        )
+        .arg(
+            Arg::new("normalize-formats")
+                .long("normalize-formats")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("stream")
+                .help("Normalize date-time and UUID examples, report values not matching formats"),
+        )
+        .arg(
+            Arg::new("validate-defaults")
+                .long("validate-defaults")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("stream")
+                .help("Report defaults not matching their schema, coerce them with --lenient"),
+        )
+        .arg(
+            Arg::new("validate-examples")
+                .long("validate-examples")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("stream")
+                .help("Report examples which do not match their schema"),
+        )
+        .arg(
+            Arg::new("anonymize")
+                .long("anonymize")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("stream")
+                .help("Replace descriptions, examples, contact details and host with placeholders"),
+        )
+        .arg(
+            Arg::new("grpc-gateway")
+                .long("grpc-gateway")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("stream")
+                .help("Handle the quirks of documents generated for grpc-gateway"),
+        )
+        .arg(
+            Arg::new("go-swagger")
+                .long("go-swagger")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("stream")
+                .help("Map the extensions of documents generated by go-swagger"),
+        )
+        .arg(
+            Arg::new("dotnet")
+                .long("dotnet")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("stream")
+                .help("Normalize the conventions of documents generated by Swashbuckle or NSwag"),
+        )
+        .arg(
+            Arg::new("operation-id-template")
+                .long("operation-id-template")
+                .value_name("TEMPLATE")
+                .value_parser(|template: &str| template.parse::<OperationIdTemplate>())
+                .help("Rewrite operationIds, e.g. \"{tag:snake}_{method}_{path:snake}\""),
+        )
+        .arg(
+            Arg::new("require-security")
+                .long("require-security")
+                .value_name("SCHEME[:SCOPE,...]")
+                .action(ArgAction::Append)
+                .value_parser(|value: &str| value.parse::<RequiredSecurity>())
+                .help("Add a security requirement to the document"),
+        )
+        .arg(
+            Arg::new("require-security-on")
+                .long("require-security-on")
+                .value_parser(["document", "operations"])
+                .default_value("document")
+                .help("Add required security to the document or to operations without any"),
+        )
+        .arg(
+            Arg::new("license-name")
+                .long("license-name")
+                .value_name("NAME")
+                .help("Set the license of the API, replacing the one of the input"),
+        )
+        .arg(
+            Arg::new("license-url")
+                .long("license-url")
+                .value_name("URL")
+                .requires("license-name")
+                .help("URL of the license set with --license-name"),
+        )
+        .arg(
+            Arg::new("contact-name")
+                .long("contact-name")
+                .value_name("NAME")
+                .help("Name of the contact of the API, replacing the contact of the input"),
+        )
+        .arg(
+            Arg::new("contact-email")
+                .long("contact-email")
+                .value_name("EMAIL")
+                .help("Email of the contact of the API, replacing the contact of the input"),
+        )
+        .arg(
+            Arg::new("contact-url")
+                .long("contact-url")
+                .value_name("URL")
+                .help("URL of the contact of the API, replacing the contact of the input"),
+        )
+        .arg(
+            Arg::new("terms-of-service")
+                .long("terms-of-service")
+                .value_name("URL")
+                .help("Set the terms of service of the API, replacing the ones of the input"),
+        )
+        .arg(
+            Arg::new("external-docs")
+                .long("external-docs")
+                .value_name("URL")
+                .help("Set the external docs of the API, replacing the ones of the input"),
+        )
+        .arg(
+            Arg::new("tag-external-docs")
+                .long("tag-external-docs")
+                .value_name("TAG=URL")
+                .action(ArgAction::Append)
+                .value_parser(|value: &str| {
+                    value
+                        .split_once('=')
+                        .map(|(tag, url)| (tag.to_string(), url.to_string()))
+                        .ok_or_else(|| format!("expected TAG=URL, got `{value}`"))
+                })
+                .help("Set the external docs of a tag, declaring the tag if the input does not"),
+        )
+        .arg(
+            Arg::new("host")
+                .long("host")
+                .value_name("HOST")
+                .help("Generate the servers with this host instead of the host of the input"),
+        )
+        .arg(
+            Arg::new("base-path")
+                .long("base-path")
+                .value_name("PATH")
+                .conflicts_with("base-path-prefix")
+                .help("Generate the servers with this base path instead of the basePath"),
+        )
+        .arg(
+            Arg::new("base-path-prefix")
+                .long("base-path-prefix")
+                .value_name("PATH")
+                .help("Prepend this path to the basePath of the generated servers"),
+        )
+        .arg(
+            Arg::new("server-variable")
+                .long("server-variable")
+                .value_name("NAME=DEFAULT[,VALUE...]")
+                .action(ArgAction::Append)
+                .value_parser(|value: &str| {
+                    let (name, values) = value
+                        .split_once('=')
+                        .ok_or_else(|| format!("expected NAME=DEFAULT[,VALUE...], got `{value}`"))?;
+                    let values: Vec<String> = values.split(',').map(str::to_string).collect();
+                    let mut variable = ServerVariable::default();
+                    variable.default_value = values[0].clone();
+                    if values.len() > 1 {
+                        variable.enum_values = Some(values);
+                    }
+                    Ok::<_, String>((name.to_string(), variable))
+                })
+                .help("Declare a placeholder of a templated host or basePath, the default first"),
+        )
+        .arg(
+            Arg::new("strip-schema-prefix")
+                .long("strip-schema-prefix")
+                .value_name("PREFIX")
+                .action(ArgAction::Append)
+                .help("Remove this prefix from definition names, e.g. io.company.model."),
+        )
+        .arg(
+            Arg::new("prune")
+                .long("prune")
+                .value_name("COMPONENTS")
+                .value_parser(["security-schemes", "responses"])
+                .action(ArgAction::Append)
+                .help("Drop unused components of this kind, which are reported otherwise"),
+        )
+        .arg(
+            Arg::new("schema-name-case")
+                .long("schema-name-case")
+                .value_parser(|value: &str| value.parse::<Casing>())
+                .help("Casing of definition names: camel, pascal, snake, kebab, lower or upper"),
+        )
+        .arg(
+            Arg::new("keep-extension")
+                .long("keep-extension")
+                .value_name("NAME")
+                .action(ArgAction::Append)
+                .help("Drop every vendor extension but the ones given"),
+        )
+        .arg(
+            Arg::new("lenient")
+                .long("lenient")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Keep status code ranges like 2XX and coerce enum values and defaults \
+                     (with --validate-defaults) of the wrong type",
+                ),
+        )
+        .args_conflicts_with_subcommands(true)
+        .subcommand(
+            Command::new("compat")
+                .about("Classify the changes between two versions of a spec as breaking or not")
+                .arg(
+                    document_arg()
+                        .id("old")
+                        .help("Path to the previous version of the spec"),
+                )
+                .arg(document_arg().id("new").help("Path to the new version of the spec"))
+                .arg(
+                    Arg::new("format")
+                        .long("format")
+                        .value_parser(["text", "json"])
+                        .default_value("text")
+                        .help("Print the changes as text or as JSON"),
+                )
+                .arg(
+                    Arg::new("check-version")
+                        .long("check-version")
+                        .action(ArgAction::SetTrue)
+                        .help("Fail unless info.version was bumped as the changes require"),
+                ),
+        )
+        .subcommand(
+            Command::new("score")
+                .about("Score how completely a spec is documented and list the gaps")
+                .arg(document_arg())
+                .arg(
+                    Arg::new("format")
+                        .long("format")
+                        .value_parser(["text", "json"])
+                        .default_value("text")
+                        .help("Print the report as text or as JSON"),
+                ),
+        )
+        .subcommand(
+            Command::new("normalize")
+                .about("Rewrite a spec into a canonical form for comparing and hashing")
+                .arg(document_arg())
+                .arg(out_arg()),
+        )
+        .subcommand(
+            Command::new("flatten")
+                .about("Merge allOf compositions into plain schemas")
+                .arg(document_arg())
+                .arg(out_arg()),
         );
 
     let help = cmd.render_help();
@@ -34,26 +457,446 @@ fn main() {
     }
 }
 
+fn document_arg() -> Arg {
+    Arg::new("document")
+        .required(true)
+        .help("Path to a swagger 2.0 or openapi 3.x spec")
+        .value_hint(clap::ValueHint::FilePath)
+}
+
+fn out_arg() -> Arg {
+    Arg::new("out")
+        .short('o')
+        .long("out")
+        .default_value("./openapi.json")
+        .help("Output OpenAPI file path")
+        .value_hint(clap::ValueHint::FilePath)
+}
+
 fn parse_args(cmd: Command) -> Result<()> {
     let matches = cmd.try_get_matches()?;
+    match matches.subcommand() {
+        Some(("compat", matches)) => return compat(matches),
+        Some(("flatten", matches)) => return flatten(matches),
+        Some(("normalize", matches)) => return normalize(matches),
+        Some(("score", matches)) => return score(matches),
+        _ => {}
+    }
 
     let swagger_path = matches
         .get_one::<String>("swagger")
         .ok_or_else(|| anyhow!("missing swagger path"))?;
     let openapi_path = matches.get_one::<String>("out").unwrap();
 
-    let file = File::open(swagger_path)?;
-    let mut buf = BufReader::new(file);
-    let swagger: Swagger = serde_json::from_reader(&mut buf)?;
-    let openapi: OpenApi = swagger.into();
+    let options = convert_options(&matches);
+    let (mut conversion, source) = if matches.get_flag("stream") {
+        let reader = BufReader::new(File::open(swagger_path)?);
+        let conversion =
+            swagger_convert::stream::convert_reader(reader, &options).map_err(|err| {
+                let source = std::fs::read_to_string(swagger_path).ok();
+                with_snippet(err, swagger_path, source.as_deref())
+            })?;
+        (conversion, None)
+    } else {
+        let source = std::fs::read_to_string(swagger_path)?;
+        let conversion = swagger_convert::convert_str(&source, &options)
+            .map_err(|err| with_snippet(err, swagger_path, Some(&source)))?;
+        (conversion, Some(source))
+    };
+    if let Some(baseline_path) = matches.get_one::<String>("baseline") {
+        match File::open(baseline_path) {
+            Ok(file) => {
+                let baseline: diagnostics::Baseline =
+                    serde_json::from_reader(BufReader::new(file))?;
+                conversion.diagnostics =
+                    baseline.filter(std::mem::take(&mut conversion.diagnostics));
+            }
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                let baseline = diagnostics::Baseline::new(&conversion.diagnostics);
+                let out_file = File::create(baseline_path)?;
+                serde_json::to_writer_pretty(BufWriter::new(out_file), &baseline)?;
+                println!(
+                    "Recorded {} findings in baseline {baseline_path:?}",
+                    baseline.findings.len()
+                );
+                conversion.diagnostics.clear();
+            }
+            Err(err) => return Err(err.into()),
+        }
+    }
+    let github = matches
+        .get_one::<String>("diagnostics-format")
+        .map(String::as_str)
+        == Some("github");
+    for diagnostic in &conversion.diagnostics {
+        let location = source
+            .as_deref()
+            .and_then(|source| diagnostics::locate(source, &diagnostic.pointer));
+        if github {
+            println!("{}", diagnostic.github_annotation(swagger_path, location));
+            continue;
+        }
+        match location {
+            Some(location) => eprintln!(
+                "{swagger_path}:{}:{}: {diagnostic}",
+                location.line, location.column
+            ),
+            None => eprintln!("{swagger_path}: {diagnostic}"),
+        }
+    }
+
+    if matches.get_one::<String>("format").map(String::as_str) == Some("json-schema") {
+        let draft = match matches.get_one::<String>("draft").map(String::as_str) {
+            Some("4") => export::Draft::Draft4,
+            Some("2020-12") => export::Draft::Draft2020_12,
+            _ => export::Draft::Draft7,
+        };
+        println!("Writing JSON Schemas to {openapi_path:?}");
+        std::fs::create_dir_all(openapi_path)?;
+        for (file_name, schema) in export::json_schemas(&conversion.openapi, draft) {
+            let path = Path::new(openapi_path).join(file_name);
+            let out_file = File::options().create_new(true).write(true).open(path)?;
+            Sink::new(BufWriter::new(out_file), options.output.syntax).write(&schema)?;
+        }
+        return Ok(());
+    }
+
+    if matches.get_flag("split") {
+        let out = Path::new(openapi_path);
+        let layout = Layout {
+            document: out
+                .file_name()
+                .ok_or_else(|| anyhow!("missing output file name"))?
+                .to_string_lossy()
+                .into_owned(),
+            schemas: matches.get_one::<String>("schemas-dir").unwrap().clone(),
+        };
+        let dir = out.parent().unwrap_or(Path::new(""));
+        println!("Writing OpenAPI files to {dir:?}");
+        for (path, document) in conversion.to_files(&layout) {
+            let path = dir.join(path);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let out_file = File::options().create_new(true).write(true).open(path)?;
+            Sink::new(BufWriter::new(out_file), options.output.syntax).write(&document)?;
+        }
+        return Ok(());
+    }
 
-    println!("Writing OpenAPI file to {openapi_path:?}");
     let out_file = File::options()
         .create_new(true)
         .write(true)
         .open(openapi_path)?;
     let mut buf = BufWriter::new(out_file);
-    serde_json::to_writer_pretty(&mut buf, &openapi)?;
+    match matches.get_one::<String>("format").map(String::as_str) {
+        Some("postman") => {
+            println!("Writing Postman collection to {openapi_path:?}");
+            let collection = export::postman_collection(&conversion.openapi);
+            Sink::new(&mut buf, options.output.syntax).write(&collection)?;
+        }
+        Some("insomnia") => {
+            println!("Writing Insomnia export to {openapi_path:?}");
+            let export = export::insomnia_export(&conversion.openapi);
+            Sink::new(&mut buf, options.output.syntax).write(&export)?;
+        }
+        Some("markdown") => {
+            println!("Writing Markdown documentation to {openapi_path:?}");
+            buf.write_all(export::markdown(&conversion.openapi).as_bytes())?;
+        }
+        Some("html") => {
+            println!("Writing HTML documentation to {openapi_path:?}");
+            buf.write_all(export::html(&conversion.openapi).as_bytes())?;
+        }
+        _ => {
+            println!("Writing OpenAPI file to {openapi_path:?}");
+            conversion.to_writer(&mut buf)?;
+        }
+    }
 
     Ok(())
 }
+
+/// Reads the document of a subcommand as OpenAPI 3.0 document, see
+/// [`swagger_convert::compare::load`].
+fn read_document(matches: &ArgMatches, id: &str) -> Result<(String, serde_json::Value)> {
+    let path = matches.get_one::<String>(id).unwrap();
+    let source = std::fs::read_to_string(path)?;
+    let document = swagger_convert::compare::load(&source, &ConvertOptions::default())
+        .map_err(|err| with_snippet(err, path, Some(&source)))?;
+    Ok((path.clone(), document))
+}
+
+/// Reads the document of a subcommand which rewrites it as OpenAPI 3.x document, keeping
+/// OpenAPI 3.1 documents in 3.1.
+fn read_rewritten(matches: &ArgMatches, id: &str) -> Result<(String, serde_json::Value)> {
+    let path = matches.get_one::<String>(id).unwrap();
+    let source = std::fs::read_to_string(path)?;
+    let document = swagger_convert::ir::load(&source, &ConvertOptions::default())
+        .map_err(|err| with_snippet(err, path, Some(&source)))?;
+    Ok((path.clone(), document.to_openapi(document.source)))
+}
+
+fn write_document(matches: &ArgMatches, document: &serde_json::Value) -> Result<()> {
+    let path = matches.get_one::<String>("out").unwrap();
+    println!("Writing OpenAPI file to {path:?}");
+    let out_file = File::options().create_new(true).write(true).open(path)?;
+    Sink::new(BufWriter::new(out_file), Syntax::Json).write(document)?;
+    Ok(())
+}
+
+/// Prints the changes between the documents and the version bump they call for. Fails if
+/// any change is breaking or, with `--check-version`, if the version was not bumped enough.
+fn compat(matches: &ArgMatches) -> Result<()> {
+    let (_, old) = read_document(matches, "old")?;
+    let (new_path, new) = read_document(matches, "new")?;
+    let (changes, diagnostics) = diagnostics::collect(|| compare::compat(&old, &new));
+    for diagnostic in diagnostics {
+        eprintln!("{new_path}: {diagnostic}");
+    }
+    let bump = compare::suggest_bump(&changes);
+
+    let check_version = matches.get_flag("check-version");
+    let passed = if check_version {
+        let version = |document: &serde_json::Value| {
+            document["info"]["version"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string()
+        };
+        let (old_version, new_version) = (version(&old), version(&new));
+        match compare::version_bump(&old_version, &new_version) {
+            Some(actual) => actual >= bump,
+            // nothing to release, the version may stay
+            None => bump == Bump::Patch && old_version == new_version,
+        }
+    } else {
+        bump != Bump::Major
+    };
+
+    if matches.get_one::<String>("format").map(String::as_str) == Some("json") {
+        let report = serde_json::json!({"bump": bump, "passed": passed, "changes": changes});
+        Sink::new(std::io::stdout().lock(), Syntax::Json).write(&report)?;
+        println!();
+    } else {
+        for change in &changes {
+            println!("{change}");
+        }
+        println!("Suggested version bump: {bump}");
+        if check_version && !passed {
+            eprintln!("error: info.version was not bumped for a {bump} release");
+        }
+    }
+    if !passed {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn score(matches: &ArgMatches) -> Result<()> {
+    let (path, document) = read_document(matches, "document")?;
+    let report = swagger_convert::coverage::score(&document);
+    if matches.get_one::<String>("format").map(String::as_str) == Some("json") {
+        let mut value = serde_json::to_value(&report)?;
+        value["score"] = serde_json::json!(report.score());
+        Sink::new(std::io::stdout().lock(), Syntax::Json).write(&value)?;
+        println!();
+        return Ok(());
+    }
+    for gap in &report.gaps {
+        eprintln!("{path}: {gap}");
+    }
+    for (name, coverage) in report.coverages() {
+        println!(
+            "{name}: {:.0}% ({}/{})",
+            coverage.percent(),
+            coverage.documented,
+            coverage.total
+        );
+    }
+    println!("score: {:.0}%", report.score());
+    Ok(())
+}
+
+fn flatten(matches: &ArgMatches) -> Result<()> {
+    let (path, mut document) = read_rewritten(matches, "document")?;
+    for diagnostic in swagger_convert::flatten::flatten(&mut document) {
+        eprintln!("{path}: {diagnostic}");
+    }
+    write_document(matches, &document)
+}
+
+fn normalize(matches: &ArgMatches) -> Result<()> {
+    let (path, mut document) = read_rewritten(matches, "document")?;
+    for diagnostic in swagger_convert::normalize::normalize(&mut document) {
+        eprintln!("{path}: {diagnostic}");
+    }
+    write_document(matches, &document)
+}
+
+/// Shows the position of a parse error within `source` below its message, if it has one.
+fn with_snippet(err: swagger_convert::Error, path: &str, source: Option<&str>) -> anyhow::Error {
+    if let (swagger_convert::Error::Parse(parse), Some(source)) = (&err, source) {
+        if parse.line() > 0 {
+            let location = diagnostics::Location {
+                line: parse.line(),
+                column: parse.column(),
+            };
+            let snippet = diagnostics::snippet(source, location, 2);
+            return anyhow!(
+                "{err}\n --> {path}:{}:{}\n{snippet}",
+                parse.line(),
+                parse.column()
+            );
+        }
+    }
+    err.into()
+}
+
+fn convert_options(matches: &ArgMatches) -> ConvertOptions {
+    let html = match matches.get_one::<String>("html").map(String::as_str) {
+        Some("strip") => HtmlHandling::Strip,
+        Some("markdown") => HtmlHandling::CommonMark,
+        _ => HtmlHandling::Keep,
+    };
+
+    let target = match matches.get_one::<String>("target").map(String::as_str) {
+        Some("3.1") => Target::V3_1,
+        _ => Target::V3_0,
+    };
+
+    let order = match matches.get_one::<String>("order").map(String::as_str) {
+        Some("source") => Order::Source,
+        Some("tag") => Order::Tag,
+        _ => Order::Alphabetical,
+    };
+
+    let mut extensions = ExtensionRegistry::default();
+    if let Some(names) = matches.get_many::<String>("keep-extension") {
+        extensions.keep_only(names.cloned());
+    }
+
+    ConvertOptions {
+        extensions,
+        sanitize: SanitizeOptions {
+            html,
+            normalize_line_endings: matches.get_flag("normalize-line-endings"),
+            max_length: matches.get_one::<usize>("max-description-length").copied(),
+        },
+        cache_schemas: matches.get_flag("cache-schemas"),
+        examples: matches.get_flag("examples"),
+        partial: matches.get_flag("partial"),
+        lenient: matches.get_flag("lenient"),
+        exclude_deprecated: matches.get_flag("exclude-deprecated"),
+        normalize_formats: matches.get_flag("normalize-formats"),
+        validate_defaults: matches.get_flag("validate-defaults"),
+        validate_examples: matches.get_flag("validate-examples"),
+        anonymize: matches.get_flag("anonymize"),
+        grpc_gateway: matches.get_flag("grpc-gateway"),
+        go_swagger: matches.get_flag("go-swagger"),
+        dotnet: matches.get_flag("dotnet"),
+        required_security: matches
+            .get_many::<RequiredSecurity>("require-security")
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect(),
+        required_security_target: match matches
+            .get_one::<String>("require-security-on")
+            .map(String::as_str)
+        {
+            Some("operations") => SecurityTarget::Operations,
+            _ => SecurityTarget::Document,
+        },
+        operation_ids: matches
+            .get_one::<OperationIdTemplate>("operation-id-template")
+            .cloned(),
+        license: matches.get_one::<String>("license-name").map(|name| {
+            LicenseBuilder::new()
+                .name(name)
+                .url(matches.get_one::<String>("license-url"))
+                .build()
+        }),
+        contact: ["contact-name", "contact-email", "contact-url"]
+            .iter()
+            .any(|id| matches.contains_id(id))
+            .then(|| {
+                ContactBuilder::new()
+                    .name(matches.get_one::<String>("contact-name"))
+                    .email(matches.get_one::<String>("contact-email"))
+                    .url(matches.get_one::<String>("contact-url"))
+                    .build()
+            }),
+        terms_of_service: matches.get_one::<String>("terms-of-service").cloned(),
+        external_docs: matches
+            .get_one::<String>("external-docs")
+            .map(ExternalDocs::new),
+        tag_external_docs: matches
+            .get_many::<(String, String)>("tag-external-docs")
+            .into_iter()
+            .flatten()
+            .map(|(tag, url)| (tag.clone(), ExternalDocs::new(url)))
+            .collect(),
+        host: matches.get_one::<String>("host").cloned(),
+        base_path: match (
+            matches.get_one::<String>("base-path"),
+            matches.get_one::<String>("base-path-prefix"),
+        ) {
+            (Some(path), _) => Some(BasePath::Replace(path.clone())),
+            (None, Some(prefix)) => Some(BasePath::Prefix(prefix.clone())),
+            (None, None) => None,
+        },
+        server_variables: matches
+            .get_many::<(String, ServerVariable)>("server-variable")
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect(),
+        refs: RefNamespace {
+            document: matches
+                .get_one::<String>("ref-document")
+                .cloned()
+                .unwrap_or_default(),
+            name_prefix: matches
+                .get_one::<String>("ref-prefix")
+                .cloned()
+                .unwrap_or_default(),
+            strip_prefixes: matches
+                .get_many::<String>("strip-schema-prefix")
+                .into_iter()
+                .flatten()
+                .cloned()
+                .collect(),
+            name_case: matches.get_one::<Casing>("schema-name-case").copied(),
+        },
+        prune: {
+            let prune = |kind: &str| {
+                matches
+                    .get_many::<String>("prune")
+                    .into_iter()
+                    .flatten()
+                    .any(|k| k == kind)
+            };
+            PruneOptions {
+                security_schemes: prune("security-schemes"),
+                responses: prune("responses"),
+            }
+        },
+        output: OutputOptions {
+            target,
+            order,
+            syntax: if matches.get_flag("yaml") {
+                Syntax::Yaml
+            } else if matches.get_flag("minify") {
+                Syntax::CompactJson
+            } else {
+                Syntax::Json
+            },
+            dialect: matches.get_one::<String>("dialect").cloned(),
+            schema_dialect: matches.get_flag("schema-dialect"),
+            stamp: matches.get_flag("stamp"),
+            slim: matches.get_flag("slim"),
+        },
+        ..Default::default()
+    }
+}
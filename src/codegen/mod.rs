@@ -0,0 +1,110 @@
+//! Generation of Rust source code for utoipa code-first services from Swagger documents.
+//!
+//! The generated code is a scaffold: it compiles against `serde` and `utoipa` and mirrors
+//! the document, but names and types are derived mechanically and are worth a review.
+
+use utoipa::openapi::SchemaFormat;
+
+mod path;
+mod schema;
+
+pub use path::*;
+pub use schema::*;
+
+const KEYWORDS: [&str; 38] = [
+    "as", "async", "await", "box", "break", "const", "continue", "crate", "dyn", "else", "enum",
+    "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+    "mut", "pub", "ref", "return", "self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while",
+];
+
+/// Words of an identifier, split at non alphanumeric characters and lower to upper case
+/// boundaries, e.g. `petStore-item` becomes `pet`, `Store`, `item`.
+pub(crate) fn words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut previous_lower = false;
+    for c in name.chars() {
+        if !c.is_alphanumeric() {
+            words.extend((!current.is_empty()).then(|| std::mem::take(&mut current)));
+            previous_lower = false;
+            continue;
+        }
+        if c.is_uppercase() && previous_lower {
+            words.push(std::mem::take(&mut current));
+        }
+        previous_lower = c.is_lowercase() || c.is_ascii_digit();
+        current.push(c);
+    }
+    words.extend((!current.is_empty()).then_some(current));
+    words
+}
+
+/// `UpperCamelCase` identifier for types and enum variants.
+pub(crate) fn pascal_case(name: &str) -> String {
+    let ident: String = words(name)
+        .iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            let first = chars.next().map(|c| c.to_uppercase().to_string());
+            first.unwrap_or_default() + &chars.as_str().to_lowercase()
+        })
+        .collect();
+    match ident.chars().next() {
+        None => "Unnamed".to_string(),
+        Some(c) if c.is_ascii_digit() => format!("V{ident}"),
+        // `Self` is a keyword which cannot be a raw identifier
+        Some(_) if ident == "Self" => "Self_".to_string(),
+        Some(_) => ident,
+    }
+}
+
+/// `snake_case` identifier for fields, functions and modules.
+pub(crate) fn snake_case(name: &str) -> String {
+    let ident = words(name)
+        .iter()
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("_");
+    match ident.chars().next() {
+        None => "unnamed".to_string(),
+        Some(c) if c.is_ascii_digit() => format!("_{ident}"),
+        // keywords which cannot be raw identifiers
+        Some(_) if matches!(ident.as_str(), "crate" | "self" | "super") => format!("{ident}_"),
+        Some(_) if KEYWORDS.contains(&ident.as_str()) => format!("r#{ident}"),
+        Some(_) => ident,
+    }
+}
+
+/// Doc comment lines for `text` at `indent`.
+pub(crate) fn doc_comment(text: Option<&str>, indent: &str) -> String {
+    text.map(|text| {
+        text.trim()
+            .lines()
+            .map(|line| {
+                format!(
+                    "{indent}///{}{}\n",
+                    if line.is_empty() { "" } else { " " },
+                    line
+                )
+            })
+            .collect()
+    })
+    .unwrap_or_default()
+}
+
+/// Name of the definition a local `$ref` points to.
+pub(crate) fn ref_name(location: &str) -> &str {
+    location.rsplit('/').next().unwrap_or(location)
+}
+
+/// Name of a format as written in the document, e.g. `int32`.
+pub(crate) fn format_name(format: &SchemaFormat) -> String {
+    match format {
+        SchemaFormat::Custom(format) => format.clone(),
+        known => serde_json::to_value(known)
+            .ok()
+            .and_then(|value| value.as_str().map(str::to_string))
+            .unwrap_or_default(),
+    }
+}
@@ -0,0 +1,262 @@
+use utoipa::openapi::{self, SchemaType};
+
+use crate::spec::{
+    method_name, Operation, Parameter, ParameterGeneric, ParameterIn, Paths, RefOr, Response,
+    Schema,
+};
+
+use super::{doc_comment, format_name, pascal_case, ref_name, snake_case};
+
+/// Generates a handler stub annotated with `#[utoipa::path(...)]` for every operation.
+///
+/// Schemas are referenced by the names [`rust_schemas`](super::rust_schemas) generates for
+/// them, inline schemas are referenced as `serde_json::Value`. The handler bodies are left
+/// for the web framework of the service.
+pub fn rust_paths(paths: &Paths) -> String {
+    let mut handlers = Vec::new();
+    for (path, path_item) in &paths.paths {
+        for (method, operation) in &path_item.operations {
+            let mut parameters: Vec<&Parameter> = operation.parameters.iter().flatten().collect();
+            for parameter in path_item.parameters.iter().flatten() {
+                let location = parameter.parameter_in.location();
+                if !parameters
+                    .iter()
+                    .any(|p| p.name == parameter.name && p.parameter_in.location() == location)
+                {
+                    parameters.push(parameter);
+                }
+            }
+            handlers.push(handler(path, method_name(method), operation, &parameters));
+        }
+    }
+    handlers.join("\n")
+}
+
+fn handler(path: &str, method: &str, operation: &Operation, parameters: &[&Parameter]) -> String {
+    let mut out = String::new();
+    let docs = [
+        operation.summary.as_deref(),
+        operation.description.as_deref(),
+    ];
+    for (i, doc) in docs.into_iter().flatten().enumerate() {
+        if i > 0 {
+            out.push_str("///\n");
+        }
+        out.push_str(&doc_comment(Some(doc), ""));
+    }
+
+    out.push_str(&format!(
+        "#[utoipa::path(\n    {method},\n    path = {path:?},\n"
+    ));
+    if let Some(tag) = operation.tags.iter().flatten().next() {
+        out.push_str(&format!("    tag = {tag:?},\n"));
+    }
+    if let Some(operation_id) = &operation.operation_id {
+        out.push_str(&format!("    operation_id = {operation_id:?},\n"));
+    }
+
+    let params: Vec<String> = parameters.iter().filter_map(|p| param(p)).collect();
+    if !params.is_empty() {
+        out.push_str(&format!("    params(\n{}    ),\n", params.concat()));
+    }
+    if let Some(request_body) = request_body(parameters) {
+        out.push_str(&format!("    {request_body},\n"));
+    }
+
+    let mut responses: Vec<(String, &RefOr<Response>)> = operation
+        .responses
+        .responses
+        .iter()
+        .map(|(status, response)| (status.clone(), response))
+        .collect();
+    if let Some(default) = &operation.responses.default {
+        responses.push(("default".to_string(), default));
+    }
+    if !responses.is_empty() {
+        out.push_str("    responses(\n");
+        for (status, response) in responses {
+            let status = match status.parse::<u16>() {
+                Ok(code) => code.to_string(),
+                Err(_) => format!("{status:?}"),
+            };
+            out.push_str(&format!("        {},\n", response_tuple(&status, response)));
+        }
+        out.push_str("    ),\n");
+    }
+
+    if let Some(security) = &operation.security {
+        let requirements: Vec<String> = security
+            .iter()
+            .filter_map(|requirement| serde_json::to_value(requirement).ok())
+            .filter_map(|requirement| requirement.as_object().cloned())
+            .map(|requirement| {
+                let schemes: Vec<String> = requirement
+                    .iter()
+                    .map(|(name, scopes)| format!("{name:?} = {scopes}"))
+                    .collect();
+                format!("({})", schemes.join(", "))
+            })
+            .collect();
+        out.push_str(&format!("    security({}),\n", requirements.join(", ")));
+    }
+    out.push_str(")]\n");
+
+    if matches!(operation.deprecated, Some(openapi::Deprecated::True)) {
+        out.push_str("#[deprecated]\n");
+    }
+    let name = operation
+        .operation_id
+        .as_deref()
+        .map(snake_case)
+        .unwrap_or_else(|| snake_case(&format!("{method} {path}")));
+    out.push_str(&format!("pub async fn {name}() {{\n    todo!()\n}}\n"));
+    out
+}
+
+/// Tuple of a query, header or path parameter within `params(...)`.
+fn param(parameter: &Parameter) -> Option<String> {
+    let (location, generic) = match &parameter.parameter_in {
+        ParameterIn::Query(generic) => ("Query", generic),
+        ParameterIn::Header(generic) => ("Header", generic),
+        ParameterIn::Path(generic) => ("Path", generic),
+        ParameterIn::FormData(_) | ParameterIn::Body(_) => return None,
+    };
+
+    let mut rust_type = parameter_type(generic);
+    if !parameter.required && location != "Path" {
+        rust_type = format!("Option<{rust_type}>");
+    }
+    let mut tuple = format!("        ({:?} = {rust_type}, {location}", parameter.name);
+    if let Some(description) = &parameter.description {
+        tuple.push_str(&format!(", description = {description:?}"));
+    }
+    tuple.push_str("),\n");
+    Some(tuple)
+}
+
+/// `request_body` argument of a body parameter or the form data parameters.
+fn request_body(parameters: &[&Parameter]) -> Option<String> {
+    let body = parameters.iter().find_map(|p| match &p.parameter_in {
+        ParameterIn::Body(body) => Some(body),
+        _ => None,
+    });
+    if let Some(body) = body {
+        return Some(format!("request_body = {}", body_type(&body.schema)));
+    }
+
+    let form_data = parameters
+        .iter()
+        .any(|p| matches!(p.parameter_in, ParameterIn::FormData(_)));
+    form_data.then(|| {
+        "request_body(content = serde_json::Value, content_type = \"application/x-www-form-urlencoded\")"
+            .to_string()
+    })
+}
+
+fn response_tuple(status: &str, response: &RefOr<Response>) -> String {
+    let response = match response {
+        RefOr::Ref(reference) => {
+            let name = pascal_case(ref_name(&reference.ref_location));
+            return format!("(status = {status}, response = {name})");
+        }
+        RefOr::T(response) => response,
+    };
+
+    let mut tuple = format!(
+        "(status = {status}, description = {:?}",
+        response.description
+    );
+    if let Some(schema) = &response.schema {
+        tuple.push_str(&format!(", body = {}", body_type(schema)));
+    }
+    tuple.push(')');
+    tuple
+}
+
+/// Type of a request or response body, in the `[Item]` notation of utoipa for arrays.
+fn body_type(schema: &RefOr<Schema>) -> String {
+    match schema {
+        RefOr::Ref(reference) => pascal_case(ref_name(&reference.ref_location)),
+        RefOr::T(Schema::Array(array)) => format!("[{}]", body_type(&array.items)),
+        RefOr::T(Schema::Object(object)) => {
+            let format = object.format.as_ref().map(format_name);
+            primitive_type(&object.schema_type, format.as_deref())
+        }
+        RefOr::T(Schema::AllOf(_)) => "serde_json::Value".to_string(),
+    }
+}
+
+fn parameter_type(parameter: &ParameterGeneric) -> String {
+    if let (SchemaType::Array, Some(items)) = (&parameter.schema_type, &parameter.items) {
+        return format!("Vec<{}>", parameter_type(items));
+    }
+    let format = parameter.format.as_ref().map(format_name);
+    primitive_type(&parameter.schema_type, format.as_deref())
+}
+
+fn primitive_type(schema_type: &SchemaType, format: Option<&str>) -> String {
+    let rust_type = match (schema_type, format) {
+        (SchemaType::String, _) => "String",
+        (SchemaType::Integer, Some("int32")) => "i32",
+        (SchemaType::Integer, _) => "i64",
+        (SchemaType::Number, Some("float")) => "f32",
+        (SchemaType::Number, _) => "f64",
+        (SchemaType::Boolean, _) => "bool",
+        _ => "serde_json::Value",
+    };
+    rust_type.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn generate_path_attributes() {
+        let paths: Paths = serde_json::from_value(json!({
+            "/pets/{id}": {
+                "parameters": [{"name": "id", "in": "path", "required": true, "type": "integer", "format": "int64"}],
+                "put": {
+                    "tags": ["pets"],
+                    "summary": "Update a pet",
+                    "operationId": "updatePet",
+                    "parameters": [
+                        {"name": "dryRun", "in": "query", "type": "boolean", "description": "Only validate"},
+                        {"name": "body", "in": "body", "required": true, "schema": {"$ref": "#/definitions/Pet"}}
+                    ],
+                    "responses": {
+                        "200": {"description": "updated", "schema": {"type": "array", "items": {"$ref": "#/definitions/Pet"}}},
+                        "404": {"$ref": "#/responses/NotFound"}
+                    }
+                }
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            rust_paths(&paths),
+            r#"/// Update a pet
+#[utoipa::path(
+    put,
+    path = "/pets/{id}",
+    tag = "pets",
+    operation_id = "updatePet",
+    params(
+        ("dryRun" = Option<bool>, Query, description = "Only validate"),
+        ("id" = i64, Path),
+    ),
+    request_body = Pet,
+    responses(
+        (status = 200, description = "updated", body = [Pet]),
+        (status = 404, response = NotFound),
+    ),
+)]
+pub async fn update_pet() {
+    todo!()
+}
+"#
+        );
+    }
+}
@@ -0,0 +1,386 @@
+use std::collections::BTreeSet;
+
+use utoipa::openapi::SchemaType;
+
+use crate::spec::{AdditionalProperties, AllOf, Definitions, Object, RefOr, Schema};
+
+use super::{doc_comment, format_name, pascal_case, ref_name, snake_case};
+
+const DERIVE: &str = "#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]";
+
+/// Generates Rust types deriving `utoipa::ToSchema` for every definition.
+///
+/// Objects become structs and string enums become enums, inline objects and enums of
+/// properties get their own type named after the property. Optional and `x-nullable`
+/// properties are wrapped in `Option`, `allOf` compositions flatten their parts.
+pub fn rust_schemas(definitions: &Definitions) -> String {
+    let mut generator = SchemaGenerator {
+        items: Vec::new(),
+        names: definitions
+            .defintions
+            .keys()
+            .map(|name| pascal_case(name))
+            .collect(),
+    };
+
+    for (name, schema) in &definitions.defintions {
+        let type_name = pascal_case(name);
+        match schema {
+            RefOr::Ref(reference) => generator.items.push(format!(
+                "pub type {type_name} = {};\n",
+                pascal_case(ref_name(&reference.ref_location))
+            )),
+            RefOr::T(schema) => generator.item(type_name, schema),
+        }
+    }
+
+    generator.items.join("\n")
+}
+
+struct SchemaGenerator {
+    items: Vec<String>,
+    /// Type names in use, generated names must not clash with them.
+    names: BTreeSet<String>,
+}
+
+impl SchemaGenerator {
+    /// Generates the item `type_name` for a definition.
+    fn item(&mut self, type_name: String, schema: &Schema) {
+        // nested items are generated while generating this one but placed after it
+        let index = self.items.len();
+        self.items.push(String::new());
+        self.items[index] = match schema {
+            Schema::Object(object) if string_enum(object).is_some() => {
+                enumeration(&type_name, object)
+            }
+            Schema::Object(object) if is_struct(object) => self.structure(&type_name, object),
+            Schema::AllOf(all_of) => self.all_of(&type_name, all_of),
+            _ => {
+                let rust_type = self.rust_type(&type_name, schema);
+                let doc = doc_comment(description(schema), "");
+                format!("{doc}pub type {type_name} = {rust_type};\n")
+            }
+        };
+    }
+
+    /// Generates a new item for an inline schema, named after its location.
+    fn nested(&mut self, name: &str, schema: &Schema) -> String {
+        let mut type_name = name.to_string();
+        let mut i = 2;
+        while self.names.contains(&type_name) {
+            type_name = format!("{name}{i}");
+            i += 1;
+        }
+        self.names.insert(type_name.clone());
+        self.item(type_name.clone(), schema);
+        type_name
+    }
+
+    fn structure(&mut self, type_name: &str, object: &Object) -> String {
+        let mut fields = String::new();
+        let mut used = BTreeSet::new();
+        for (property, schema) in &object.properties {
+            let field = unique(snake_case(property), &mut used);
+            let nested_name = format!("{type_name}{}", pascal_case(property));
+            let mut rust_type = self.rust_ref_type(&nested_name, schema);
+            let inline = match schema {
+                RefOr::T(inline) => Some(inline),
+                RefOr::Ref(_) => None,
+            };
+            let nullable = inline.is_some_and(extensions_nullable);
+            let optional = nullable || !object.required.contains(property);
+
+            fields.push_str(&doc_comment(inline.and_then(description), "    "));
+            let format = inline.and_then(schema_format);
+            fields.push_str(&field_attributes(property, &field, optional, format));
+            if rust_type == type_name {
+                // a struct containing itself would be infinitely large
+                rust_type = format!("Box<{rust_type}>");
+            }
+            if optional {
+                rust_type = format!("Option<{rust_type}>");
+            }
+            fields.push_str(&format!("    pub {field}: {rust_type},\n"));
+        }
+
+        let doc = doc_comment(object.description.as_deref(), "");
+        format!("{doc}{DERIVE}\npub struct {type_name} {{\n{fields}}}\n")
+    }
+
+    fn all_of(&mut self, type_name: &str, all_of: &AllOf) -> String {
+        let mut fields = String::new();
+        let mut used = BTreeSet::new();
+        for (i, item) in all_of.items.iter().enumerate() {
+            let nested_name = format!("{type_name}Part{}", i + 1);
+            let rust_type = self.rust_ref_type(&nested_name, item);
+            let field = match item {
+                RefOr::Ref(reference) => snake_case(ref_name(&reference.ref_location)),
+                RefOr::T(_) => format!("part{}", i + 1),
+            };
+            let field = unique(field, &mut used);
+            fields.push_str(&format!(
+                "    #[serde(flatten)]\n    pub {field}: {rust_type},\n"
+            ));
+        }
+
+        let doc = doc_comment(all_of.description.as_deref(), "");
+        format!("{doc}{DERIVE}\npub struct {type_name} {{\n{fields}}}\n")
+    }
+
+    fn rust_ref_type(&mut self, name: &str, schema: &RefOr<Schema>) -> String {
+        match schema {
+            RefOr::Ref(reference) => pascal_case(ref_name(&reference.ref_location)),
+            RefOr::T(schema) => self.rust_type(name, schema),
+        }
+    }
+
+    /// Rust type of a schema, generating items for inline objects and enums named `name`.
+    fn rust_type(&mut self, name: &str, schema: &Schema) -> String {
+        let object = match schema {
+            Schema::Array(array) => {
+                let items = self.rust_ref_type(&format!("{name}Item"), &array.items);
+                return format!("Vec<{items}>");
+            }
+            Schema::AllOf(_) => return self.nested(name, schema),
+            Schema::Object(object) => object,
+        };
+        if string_enum(object).is_some() || is_struct(object) {
+            return self.nested(name, schema);
+        }
+
+        let format = schema_format(schema);
+        match object.schema_type {
+            SchemaType::String => "String".to_string(),
+            SchemaType::Integer => match format.as_deref() {
+                Some("int32") => "i32".to_string(),
+                _ => "i64".to_string(),
+            },
+            SchemaType::Number => match format.as_deref() {
+                Some("float") => "f32".to_string(),
+                _ => "f64".to_string(),
+            },
+            SchemaType::Boolean => "bool".to_string(),
+            _ => match object.additional_properties.as_deref() {
+                Some(AdditionalProperties::RefOr(values)) => {
+                    let values = self.rust_ref_type(&format!("{name}Value"), values);
+                    format!("std::collections::HashMap<String, {values}>")
+                }
+                _ => "serde_json::Value".to_string(),
+            },
+        }
+    }
+}
+
+fn enumeration(type_name: &str, object: &Object) -> String {
+    let mut variants = String::new();
+    let mut used = BTreeSet::new();
+    for value in string_enum(object).unwrap_or_default() {
+        let variant = unique(pascal_case(value), &mut used);
+        if variant != value {
+            variants.push_str(&format!("    #[serde(rename = {value:?})]\n"));
+        }
+        variants.push_str(&format!("    {variant},\n"));
+    }
+
+    let doc = doc_comment(object.description.as_deref(), "");
+    format!("{doc}{DERIVE}\npub enum {type_name} {{\n{variants}}}\n")
+}
+
+/// `ident`, suffixed with underscores until it differs from the identifiers already `used`
+/// in the same item, e.g. for the properties `petId` and `pet_id`.
+fn unique(mut ident: String, used: &mut BTreeSet<String>) -> String {
+    while !used.insert(ident.clone()) {
+        ident.push('_');
+    }
+    ident
+}
+
+/// Values of an enum of strings.
+fn string_enum(object: &Object) -> Option<Vec<&str>> {
+    if !matches!(object.schema_type, SchemaType::String) {
+        return None;
+    }
+    object
+        .enum_values
+        .as_ref()?
+        .iter()
+        .map(|value| value.as_str())
+        .collect()
+}
+
+/// Objects with properties become structs, others are free-form or maps.
+fn is_struct(object: &Object) -> bool {
+    matches!(object.schema_type, SchemaType::Object) && !object.properties.is_empty()
+}
+
+fn extensions_nullable(schema: &Schema) -> bool {
+    match schema {
+        Schema::Array(array) => array.extensions.nullable(),
+        Schema::Object(object) => object.extensions.nullable(),
+        Schema::AllOf(all_of) => all_of.extensions.nullable(),
+    }
+}
+
+fn description(schema: &Schema) -> Option<&str> {
+    match schema {
+        Schema::Array(array) => array.description.as_deref(),
+        Schema::Object(object) => object.description.as_deref(),
+        Schema::AllOf(all_of) => all_of.description.as_deref(),
+    }
+}
+
+fn schema_format(schema: &Schema) -> Option<String> {
+    match schema {
+        Schema::Object(object) => object.format.as_ref().map(format_name),
+        _ => None,
+    }
+}
+
+/// Serde and utoipa attributes of a struct field.
+fn field_attributes(property: &str, field: &str, optional: bool, format: Option<String>) -> String {
+    let mut attributes = String::new();
+    if field.trim_start_matches("r#") != property {
+        attributes.push_str(&format!("    #[serde(rename = {property:?})]\n"));
+    }
+    if optional {
+        attributes.push_str("    #[serde(skip_serializing_if = \"Option::is_none\")]\n");
+    }
+    let known_format = match format.as_deref() {
+        Some("date") => Some("Date"),
+        Some("date-time") => Some("DateTime"),
+        Some("byte") => Some("Byte"),
+        Some("binary") => Some("Binary"),
+        Some("password") => Some("Password"),
+        _ => None,
+    };
+    if let Some(known_format) = known_format {
+        attributes.push_str(&format!("    #[schema(format = {known_format})]\n"));
+    }
+    attributes
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn generate_structs_and_enums() {
+        let definitions: Definitions = serde_json::from_value(json!({
+            "Pet": {
+                "type": "object",
+                "description": "A pet.",
+                "required": ["name", "status"],
+                "properties": {
+                    "name": {"type": "string"},
+                    "birthDate": {"type": "string", "format": "date"},
+                    "status": {"type": "string", "enum": ["available", "sold"]},
+                    "tags": {"type": "array", "items": {"$ref": "#/definitions/Tag"}},
+                    "owner": {"type": "integer", "format": "int32", "x-nullable": true}
+                }
+            },
+            "Tag": {"type": "string"}
+        }))
+        .unwrap();
+
+        assert_eq!(
+            rust_schemas(&definitions),
+            r#"/// A pet.
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct Pet {
+    #[serde(rename = "birthDate")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(format = Date)]
+    pub birth_date: Option<String>,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<i32>,
+    pub status: PetStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<Tag>>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub enum PetStatus {
+    #[serde(rename = "available")]
+    Available,
+    #[serde(rename = "sold")]
+    Sold,
+}
+
+pub type Tag = String;
+"#
+        );
+    }
+
+    #[test]
+    fn box_recursive_fields() {
+        let definitions: Definitions = serde_json::from_value(json!({
+            "Node": {
+                "type": "object",
+                "required": ["value"],
+                "properties": {
+                    "value": {"type": "string"},
+                    "parent": {"$ref": "#/definitions/Node"},
+                    "children": {"type": "array", "items": {"$ref": "#/definitions/Node"}}
+                }
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            rust_schemas(&definitions),
+            r#"#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct Node {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub children: Option<Vec<Node>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent: Option<Box<Node>>,
+    pub value: String,
+}
+"#
+        );
+    }
+
+    #[test]
+    fn unique_identifiers() {
+        let definitions: Definitions = serde_json::from_value(json!({
+            "Owner": {
+                "type": "object",
+                "required": ["petId", "pet_id", "self", "type"],
+                "properties": {
+                    "petId": {"type": "integer"},
+                    "pet_id": {"type": "string"},
+                    "self": {"type": "string"},
+                    "type": {"type": "string"}
+                }
+            },
+            "Relation": {"type": "string", "enum": ["self", "other"]}
+        }))
+        .unwrap();
+
+        assert_eq!(
+            rust_schemas(&definitions),
+            r#"#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct Owner {
+    #[serde(rename = "petId")]
+    pub pet_id: i64,
+    #[serde(rename = "pet_id")]
+    pub pet_id_: String,
+    #[serde(rename = "self")]
+    pub self_: String,
+    pub r#type: String,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub enum Relation {
+    #[serde(rename = "self")]
+    Self_,
+    #[serde(rename = "other")]
+    Other,
+}
+"#
+        );
+    }
+}
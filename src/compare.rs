@@ -0,0 +1,658 @@
+//! Comparison of API documents regardless of the version they are written in.
+//!
+//! Documents are loaded through the [`crate::ir`] first, so a legacy Swagger document can be
+//! compared with the OpenAPI 3.x document succeeding it. Comparisons work on the serialized
+//! documents, like the [`crate::export`]s.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    cycle::{self, RefStack},
+    diagnostics::{self, escape},
+    ir, ConvertOptions, Error,
+};
+
+const METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "kebab-case")]
+pub enum Impact {
+    NonBreaking,
+    Breaking,
+}
+
+impl fmt::Display for Impact {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Impact::NonBreaking => f.write_str("non-breaking"),
+            Impact::Breaking => f.write_str("breaking"),
+        }
+    }
+}
+
+/// Part of a semantic version to increment.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Bump {
+    Patch,
+    Minor,
+    Major,
+}
+
+impl fmt::Display for Bump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Bump::Patch => f.write_str("patch"),
+            Bump::Minor => f.write_str("minor"),
+            Bump::Major => f.write_str("major"),
+        }
+    }
+}
+
+/// A difference between two versions of a document.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub impact: Impact,
+    /// JSON pointer of the changed element in the new document, or in the old one if it was
+    /// removed.
+    pub pointer: String,
+    pub message: String,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}: {}", self.impact, self.pointer, self.message)
+    }
+}
+
+/// Reads any document [`ir::load`] does as an OpenAPI 3.0 document.
+///
+/// Every version is written as OpenAPI 3.0, so the schemas of documents of different versions
+/// are written in the same dialect, e.g. `nullable` rather than a `null` type. Swagger
+/// documents are converted with `options`.
+pub fn load(source: &str, options: &ConvertOptions) -> Result<Value, Error> {
+    Ok(ir::load(source, options)?.to_openapi(ir::Version::OpenApi3_0))
+}
+
+/// Classifies the differences between the OpenAPI documents `old` and `new`, as returned by
+/// [`load`], by whether they break clients written against `old`.
+///
+/// Removed operations, parameters, responses, media types, properties and enum values are
+/// breaking, as are changed types and parameters, properties or request bodies which are
+/// required now. Additions are not. Schemas are compared regardless of whether they are
+/// sent or received, so e.g. a property which is required now is breaking in responses too.
+///
+/// References nested deeper than [`ConvertOptions::max_ref_depth`] are not compared, which is
+/// reported as diagnostic, see [`diagnostics::collect`].
+pub fn compat(old: &Value, new: &Value) -> Vec<Change> {
+    let mut compat = Compat {
+        old,
+        new,
+        changes: Vec::new(),
+        refs: RefStack::new(cycle::max_depth()),
+    };
+    compat.paths();
+    if let (Some(Value::Object(old)), Some(Value::Object(new))) = (
+        old.pointer("/components/schemas"),
+        new.pointer("/components/schemas"),
+    ) {
+        for (name, schema) in old {
+            let pointer = format!("/components/schemas/{}", escape(name));
+            match new.get(name) {
+                Some(new) => compat.schema(schema, new, &pointer),
+                None => compat.report(Impact::Breaking, &pointer, "schema removed"),
+            }
+        }
+    }
+    compat.changes
+}
+
+/// The version bump `changes` call for: major for breaking changes, minor for other changes
+/// and patch if the documents differ in nothing [`compat`] compares.
+pub fn suggest_bump(changes: &[Change]) -> Bump {
+    match changes.iter().map(|change| change.impact).max() {
+        Some(Impact::Breaking) => Bump::Major,
+        Some(Impact::NonBreaking) => Bump::Minor,
+        None => Bump::Patch,
+    }
+}
+
+/// The part incremented from version `old` to `new`, or `None` if `new` is not greater or
+/// either is no semantic version. A leading `v` and pre-release and build suffixes are
+/// ignored, missing parts count as `0`.
+pub fn version_bump(old: &str, new: &str) -> Option<Bump> {
+    let ([old_major, old_minor, old_patch], [major, minor, patch]) = (semver(old)?, semver(new)?);
+    if major != old_major {
+        (major > old_major).then_some(Bump::Major)
+    } else if minor != old_minor {
+        (minor > old_minor).then_some(Bump::Minor)
+    } else {
+        (patch > old_patch).then_some(Bump::Patch)
+    }
+}
+
+fn semver(version: &str) -> Option<[u64; 3]> {
+    let version = version.trim().trim_start_matches('v');
+    let core = version.split(|c| c == '-' || c == '+').next()?;
+    let mut parts = [0; 3];
+    for (i, part) in core.split('.').enumerate() {
+        *parts.get_mut(i)? = part.parse().ok()?;
+    }
+    Some(parts)
+}
+
+struct Compat<'a> {
+    old: &'a Value,
+    new: &'a Value,
+    changes: Vec<Change>,
+    /// References followed to reach the schemas being compared.
+    refs: RefStack,
+}
+
+impl Compat<'_> {
+    fn report(&mut self, impact: Impact, pointer: &str, message: impl Into<String>) {
+        self.changes.push(Change {
+            impact,
+            pointer: pointer.to_string(),
+            message: message.into(),
+        });
+    }
+
+    fn paths(&mut self) {
+        let empty = serde_json::Map::new();
+        let old_paths = self.old["paths"].as_object().unwrap_or(&empty);
+        let new_paths = self.new["paths"].as_object().unwrap_or(&empty);
+        for (path, old_item) in old_paths {
+            for method in METHODS {
+                let Some(old_operation) = old_item.get(method) else {
+                    continue;
+                };
+                let pointer = format!("/paths/{}/{method}", escape(path));
+                let operation = format!("{} {path}", method.to_uppercase());
+                match new_paths.get(path).and_then(|item| item.get(method)) {
+                    Some(new_operation) => {
+                        let new_item = &new_paths[path];
+                        self.parameters(old_item, old_operation, new_item, new_operation, &pointer);
+                        self.request_body(old_operation, new_operation, &pointer);
+                        self.responses(old_operation, new_operation, &pointer);
+                    }
+                    None => self.report(
+                        Impact::Breaking,
+                        &pointer,
+                        format!("operation `{operation}` removed"),
+                    ),
+                }
+            }
+        }
+        for (path, new_item) in new_paths {
+            for method in METHODS {
+                let added = new_item.get(method).is_some()
+                    && old_paths
+                        .get(path)
+                        .and_then(|item| item.get(method))
+                        .is_none();
+                if added {
+                    let pointer = format!("/paths/{}/{method}", escape(path));
+                    let operation = format!("{} {path}", method.to_uppercase());
+                    self.report(
+                        Impact::NonBreaking,
+                        &pointer,
+                        format!("operation `{operation}` added"),
+                    );
+                }
+            }
+        }
+    }
+
+    fn parameters(
+        &mut self,
+        old_item: &Value,
+        old_operation: &Value,
+        new_item: &Value,
+        new_operation: &Value,
+        pointer: &str,
+    ) {
+        let old = parameters(self.old, old_item, old_operation);
+        let new = parameters(self.new, new_item, new_operation);
+        for (key, parameter) in &old {
+            let (location, name) = key;
+            match new.iter().find(|(k, _)| k == key) {
+                Some((_, new_parameter)) => {
+                    if is_required(new_parameter) && !is_required(parameter) {
+                        self.report(
+                            Impact::Breaking,
+                            pointer,
+                            format!("{location} parameter `{name}` is required now"),
+                        );
+                    }
+                    if let (Some(old_schema), Some(new_schema)) =
+                        (parameter.get("schema"), new_parameter.get("schema"))
+                    {
+                        self.schema(old_schema, new_schema, pointer);
+                    }
+                }
+                None => self.report(
+                    Impact::Breaking,
+                    pointer,
+                    format!("{location} parameter `{name}` removed"),
+                ),
+            }
+        }
+        for (key, parameter) in &new {
+            if old.iter().any(|(k, _)| k == key) {
+                continue;
+            }
+            let (location, name) = key;
+            if is_required(parameter) {
+                let message = format!("required {location} parameter `{name}` added");
+                self.report(Impact::Breaking, pointer, message);
+            } else {
+                let message = format!("{location} parameter `{name}` added");
+                self.report(Impact::NonBreaking, pointer, message);
+            }
+        }
+    }
+
+    fn request_body(&mut self, old_operation: &Value, new_operation: &Value, pointer: &str) {
+        let old = old_operation
+            .get("requestBody")
+            .map(|body| resolve(self.old, body));
+        let new = new_operation
+            .get("requestBody")
+            .map(|body| resolve(self.new, body));
+        let pointer = format!("{pointer}/requestBody");
+        match (old, new) {
+            (Some(old), Some(new)) => {
+                if is_required(new) && !is_required(old) {
+                    self.report(Impact::Breaking, &pointer, "request body is required now");
+                }
+                self.content(old, new, &pointer);
+            }
+            (Some(_), None) => self.report(Impact::Breaking, &pointer, "request body removed"),
+            (None, Some(new)) if is_required(new) => {
+                self.report(Impact::Breaking, &pointer, "required request body added")
+            }
+            (None, Some(_)) => self.report(Impact::NonBreaking, &pointer, "request body added"),
+            (None, None) => {}
+        }
+    }
+
+    fn responses(&mut self, old_operation: &Value, new_operation: &Value, pointer: &str) {
+        let empty = serde_json::Map::new();
+        let old = old_operation["responses"].as_object().unwrap_or(&empty);
+        let new = new_operation["responses"].as_object().unwrap_or(&empty);
+        for (status, response) in old {
+            let pointer = format!("{pointer}/responses/{}", escape(status));
+            match new.get(status) {
+                Some(new_response) => {
+                    let old = resolve(self.old, response);
+                    let new = resolve(self.new, new_response);
+                    self.content(old, new, &pointer);
+                }
+                None => self.report(
+                    Impact::Breaking,
+                    &pointer,
+                    format!("response `{status}` removed"),
+                ),
+            }
+        }
+        for status in new.keys().filter(|status| !old.contains_key(*status)) {
+            let pointer = format!("{pointer}/responses/{}", escape(status));
+            self.report(
+                Impact::NonBreaking,
+                &pointer,
+                format!("response `{status}` added"),
+            );
+        }
+    }
+
+    /// Compares the media types of a request body or response.
+    fn content(&mut self, old: &Value, new: &Value, pointer: &str) {
+        let empty = serde_json::Map::new();
+        let old = old["content"].as_object().unwrap_or(&empty);
+        let new = new["content"].as_object().unwrap_or(&empty);
+        for (media_type, old_media_type) in old {
+            let pointer = format!("{pointer}/content/{}", escape(media_type));
+            match new.get(media_type) {
+                Some(new_media_type) => {
+                    if let (Some(old_schema), Some(new_schema)) =
+                        (old_media_type.get("schema"), new_media_type.get("schema"))
+                    {
+                        self.schema(old_schema, new_schema, &format!("{pointer}/schema"));
+                    }
+                }
+                None => self.report(
+                    Impact::Breaking,
+                    &pointer,
+                    format!("media type `{media_type}` removed"),
+                ),
+            }
+        }
+        for media_type in new
+            .keys()
+            .filter(|media_type| !old.contains_key(*media_type))
+        {
+            let pointer = format!("{pointer}/content/{}", escape(media_type));
+            self.report(
+                Impact::NonBreaking,
+                &pointer,
+                format!("media type `{media_type}` added"),
+            );
+        }
+    }
+
+    fn schema(&mut self, old: &Value, new: &Value, pointer: &str) {
+        let (old_ref, new_ref) = (old.get("$ref"), new.get("$ref"));
+        if old_ref.is_some() && old_ref == new_ref {
+            // component schemas are compared on their own
+            return;
+        }
+        if let Some(location) = old_ref.or(new_ref) {
+            let location = location.as_str().unwrap_or_default();
+            if diagnostics::at(pointer, || self.refs.follow(location)) {
+                self.schema(resolve(self.old, old), resolve(self.new, new), pointer);
+                self.refs.leave();
+            }
+            return;
+        }
+
+        if old.get("type") != new.get("type") {
+            let message = format!(
+                "type changed from {} to {}",
+                old.get("type").unwrap_or(&Value::Null),
+                new.get("type").unwrap_or(&Value::Null)
+            );
+            self.report(Impact::Breaking, pointer, message);
+        }
+
+        match (old.get("enum"), new.get("enum")) {
+            (Some(Value::Array(old_values)), Some(Value::Array(new_values))) => {
+                for value in old_values.iter().filter(|v| !new_values.contains(v)) {
+                    self.report(
+                        Impact::Breaking,
+                        pointer,
+                        format!("enum value {value} removed"),
+                    );
+                }
+                for value in new_values.iter().filter(|v| !old_values.contains(v)) {
+                    let message = format!("enum value {value} added");
+                    self.report(Impact::NonBreaking, pointer, message);
+                }
+            }
+            (None, Some(_)) => {
+                self.report(Impact::Breaking, pointer, "values restricted to an enum")
+            }
+            (Some(_), None) => self.report(Impact::NonBreaking, pointer, "enum removed"),
+            _ => {}
+        }
+
+        let (old_required, new_required) = (required(old), required(new));
+        for name in new_required.iter().filter(|n| !old_required.contains(*n)) {
+            let message = format!("property `{name}` is required now");
+            self.report(Impact::Breaking, pointer, message);
+        }
+
+        let empty = serde_json::Map::new();
+        let old_properties = old["properties"].as_object().unwrap_or(&empty);
+        let new_properties = new["properties"].as_object().unwrap_or(&empty);
+        for (name, property) in old_properties {
+            let pointer = format!("{pointer}/properties/{}", escape(name));
+            match new_properties.get(name) {
+                Some(new_property) => self.schema(property, new_property, &pointer),
+                None => self.report(
+                    Impact::Breaking,
+                    &pointer,
+                    format!("property `{name}` removed"),
+                ),
+            }
+        }
+        for name in new_properties
+            .keys()
+            .filter(|n| !old_properties.contains_key(*n))
+        {
+            let pointer = format!("{pointer}/properties/{}", escape(name));
+            self.report(
+                Impact::NonBreaking,
+                &pointer,
+                format!("property `{name}` added"),
+            );
+        }
+
+        if let (Some(old_items), Some(new_items)) = (old.get("items"), new.get("items")) {
+            self.schema(old_items, new_items, &format!("{pointer}/items"));
+        }
+    }
+}
+
+/// `value`, or the element of `document` it references.
+fn resolve<'a>(document: &'a Value, value: &'a Value) -> &'a Value {
+    value
+        .get("$ref")
+        .and_then(Value::as_str)
+        .and_then(|location| location.strip_prefix('#'))
+        .and_then(|pointer| document.pointer(pointer))
+        .unwrap_or(value)
+}
+
+/// Names of the required properties of `schema`.
+fn required(schema: &Value) -> Vec<&str> {
+    schema["required"]
+        .as_array()
+        .map(|names| names.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default()
+}
+
+fn is_required(value: &Value) -> bool {
+    value.get("required") == Some(&Value::Bool(true))
+}
+
+/// The parameters of `operation` by location and name, including those of its path `item`.
+fn parameters<'a>(
+    document: &'a Value,
+    item: &'a Value,
+    operation: &'a Value,
+) -> Vec<((&'a str, &'a str), &'a Value)> {
+    let mut parameters: Vec<((&str, &str), &Value)> = Vec::new();
+    let declared = [item, operation]
+        .into_iter()
+        .filter_map(|holder| holder["parameters"].as_array())
+        .flatten();
+    for parameter in declared {
+        let parameter = resolve(document, parameter);
+        let (Some(location), Some(name)) = (parameter["in"].as_str(), parameter["name"].as_str())
+        else {
+            continue;
+        };
+        // operation parameters override those of the path item
+        parameters.retain(|(key, _)| *key != (location, name));
+        parameters.push(((location, name), parameter));
+    }
+    parameters
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn load_mixed_versions() {
+        let swagger = json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "paths": {"/pets": {"get": {"responses": {"204": {"description": "none"}}}}}
+        });
+        let openapi = json!({
+            "openapi": "3.0.3",
+            "info": {"title": "Pets", "version": "2.0"},
+            "paths": {"/pets": {"get": {"responses": {"204": {"description": "none"}}}}}
+        });
+        let options = ConvertOptions::default();
+
+        let legacy = load(&swagger.to_string(), &options).unwrap();
+        let successor = load(&openapi.to_string(), &options).unwrap();
+
+        assert_eq!(legacy["openapi"], "3.0.3");
+        assert_eq!(
+            legacy["paths"]["/pets"]["get"]["responses"]["204"]["description"],
+            successor["paths"]["/pets"]["get"]["responses"]["204"]["description"]
+        );
+        assert_eq!(successor["info"]["version"], "2.0");
+        assert!(matches!(
+            load(r#"{"swagger": "1.2"}"#, &options),
+            Err(Error::UnsupportedVersion(version)) if version == "1.2"
+        ));
+    }
+
+    #[test]
+    fn compare_swagger_with_openapi_3_1() {
+        let swagger = json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "paths": {},
+            "definitions": {
+                "Pet": {
+                    "type": "object",
+                    "properties": {
+                        "age": {"type": "integer", "minimum": 0, "exclusiveMinimum": true},
+                        "name": {"type": "string", "x-nullable": true}
+                    }
+                }
+            }
+        });
+        let openapi = json!({
+            "openapi": "3.1.0",
+            "info": {"title": "Pets", "version": "2.0"},
+            "paths": {},
+            "components": {
+                "schemas": {
+                    "Pet": {
+                        "type": "object",
+                        "properties": {
+                            "age": {"type": "integer", "exclusiveMinimum": 0},
+                            "name": {"type": ["string", "null"]}
+                        }
+                    }
+                }
+            }
+        });
+        let options = ConvertOptions::default();
+
+        let legacy = load(&swagger.to_string(), &options).unwrap();
+        let successor = load(&openapi.to_string(), &options).unwrap();
+
+        assert_eq!(successor["openapi"], legacy["openapi"]);
+        assert_eq!(
+            successor["components"]["schemas"]["Pet"]["properties"]["name"],
+            json!({"type": "string", "nullable": true})
+        );
+        assert!(compat(&legacy, &successor).is_empty());
+    }
+
+    #[test]
+    fn classify_changes() {
+        let old = json!({
+            "openapi": "3.0.3",
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "parameters": [
+                            {"name": "limit", "in": "query", "schema": {"type": "integer"}}
+                        ],
+                        "responses": {
+                            "200": {
+                                "description": "ok",
+                                "content": {
+                                    "application/json": {
+                                        "schema": {"$ref": "#/components/schemas/Pet"}
+                                    }
+                                }
+                            }
+                        }
+                    },
+                    "delete": {"responses": {"204": {"description": "gone"}}}
+                }
+            },
+            "components": {
+                "schemas": {
+                    "Pet": {
+                        "type": "object",
+                        "properties": {
+                            "kind": {"type": "string", "enum": ["cat", "dog", "bird"]},
+                            "name": {"type": "string"}
+                        }
+                    }
+                }
+            }
+        });
+        let mut new = old.clone();
+        new["paths"]["/pets"]
+            .as_object_mut()
+            .unwrap()
+            .remove("delete");
+        new["paths"]["/pets"]["get"]["parameters"][0]["description"] = json!("Page size");
+        new["components"]["schemas"]["Pet"]["properties"]["kind"]["enum"] = json!(["cat", "dog"]);
+        new["components"]["schemas"]["Pet"]["properties"]["age"] = json!({"type": "integer"});
+        new["components"]["schemas"]["Pet"]["required"] = json!(["name"]);
+
+        let changes = compat(&old, &new);
+
+        assert_eq!(
+            changes
+                .iter()
+                .map(|c| (c.impact, c.pointer.as_str(), c.message.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    Impact::Breaking,
+                    "/paths/~1pets/delete",
+                    "operation `DELETE /pets` removed"
+                ),
+                (
+                    Impact::Breaking,
+                    "/components/schemas/Pet",
+                    "property `name` is required now"
+                ),
+                (
+                    Impact::Breaking,
+                    "/components/schemas/Pet/properties/kind",
+                    "enum value \"bird\" removed"
+                ),
+                (
+                    Impact::NonBreaking,
+                    "/components/schemas/Pet/properties/age",
+                    "property `age` added"
+                ),
+            ]
+        );
+        assert!(compat(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn bump_versions() {
+        let change = |impact| Change {
+            impact,
+            pointer: String::new(),
+            message: String::new(),
+        };
+        assert_eq!(suggest_bump(&[]), Bump::Patch);
+        assert_eq!(
+            suggest_bump(&[change(Impact::NonBreaking), change(Impact::Breaking)]),
+            Bump::Major
+        );
+        assert_eq!(suggest_bump(&[change(Impact::NonBreaking)]), Bump::Minor);
+
+        assert_eq!(version_bump("1.4.2", "v2.0.0"), Some(Bump::Major));
+        assert_eq!(version_bump("1.4", "1.5.0-rc.1"), Some(Bump::Minor));
+        assert_eq!(version_bump("1.4.2", "1.4.3"), Some(Bump::Patch));
+        assert_eq!(version_bump("1.4.2", "1.4.2"), None);
+        assert_eq!(version_bump("2.0.0", "1.9.0"), None);
+        assert_eq!(version_bump("2024-01", "2024-02"), None);
+    }
+}
@@ -0,0 +1,210 @@
+//! Documentation completeness of OpenAPI documents, to track the quality of the
+//! documentation of an API, e.g. while migrating it.
+//!
+//! The [`Report`] tells how many operations have a summary and a description, how many
+//! parameters have a description and how many component schemas have an example, and lists
+//! the elements lacking them.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::diagnostics::{escape, Diagnostic, Severity};
+
+const METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// Number of elements having some documentation out of the elements which could.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct Coverage {
+    pub documented: usize,
+    pub total: usize,
+}
+
+impl Coverage {
+    /// Share of documented elements in percent, 100 if there are no elements.
+    pub fn percent(&self) -> f64 {
+        if self.total == 0 {
+            return 100.0;
+        }
+        self.documented as f64 * 100.0 / self.total as f64
+    }
+
+    fn count(&mut self, documented: bool) {
+        self.total += 1;
+        if documented {
+            self.documented += 1;
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Report {
+    pub operation_summaries: Coverage,
+    pub operation_descriptions: Coverage,
+    pub parameter_descriptions: Coverage,
+    pub schema_examples: Coverage,
+    /// The undocumented elements, as warnings at their pointer.
+    pub gaps: Vec<Diagnostic>,
+}
+
+impl Report {
+    /// The coverages by name, in the order they are reported.
+    pub fn coverages(&self) -> [(&'static str, Coverage); 4] {
+        [
+            ("operation summaries", self.operation_summaries),
+            ("operation descriptions", self.operation_descriptions),
+            ("parameter descriptions", self.parameter_descriptions),
+            ("schema examples", self.schema_examples),
+        ]
+    }
+
+    /// Mean percentage of the coverages.
+    pub fn score(&self) -> f64 {
+        let coverages = self.coverages();
+        let sum: f64 = coverages
+            .iter()
+            .map(|(_, coverage)| coverage.percent())
+            .sum();
+        sum / coverages.len() as f64
+    }
+
+    fn gap(&mut self, pointer: String, message: impl Into<String>) {
+        self.gaps.push(Diagnostic {
+            severity: Severity::Warning,
+            pointer,
+            message: message.into(),
+        });
+    }
+}
+
+/// Scores the documentation of the serialized OpenAPI `document`.
+pub fn score(document: &Value) -> Report {
+    let mut report = Report::default();
+    for (path, item) in document["paths"].as_object().into_iter().flatten() {
+        let pointer = format!("/paths/{}", escape(path));
+        parameters(&mut report, item, &pointer);
+        for method in METHODS {
+            let Some(operation) = item.get(method) else {
+                continue;
+            };
+            let pointer = format!("{pointer}/{method}");
+            let has_summary = has_text(operation, "summary");
+            report.operation_summaries.count(has_summary);
+            if !has_summary {
+                report.gap(pointer.clone(), "operation has no summary");
+            }
+            let has_description = has_text(operation, "description");
+            report.operation_descriptions.count(has_description);
+            if !has_description {
+                report.gap(pointer.clone(), "operation has no description");
+            }
+            parameters(&mut report, operation, &pointer);
+        }
+    }
+    let shared = &document["components"]["parameters"];
+    for (name, parameter) in shared.as_object().into_iter().flatten() {
+        let pointer = format!("/components/parameters/{}", escape(name));
+        parameter_description(&mut report, parameter, pointer);
+    }
+
+    let schemas = &document["components"]["schemas"];
+    for (name, schema) in schemas.as_object().into_iter().flatten() {
+        let has_example = schema.get("example").is_some() || schema.get("examples").is_some();
+        report.schema_examples.count(has_example);
+        if !has_example {
+            let pointer = format!("/components/schemas/{}", escape(name));
+            report.gap(pointer, "schema has no example");
+        }
+    }
+    report
+}
+
+/// Counts the parameters declared by a path item or operation, references are counted
+/// where they point.
+fn parameters(report: &mut Report, holder: &Value, pointer: &str) {
+    for (i, parameter) in holder["parameters"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .enumerate()
+    {
+        if parameter.get("$ref").is_none() {
+            parameter_description(report, parameter, format!("{pointer}/parameters/{i}"));
+        }
+    }
+}
+
+fn parameter_description(report: &mut Report, parameter: &Value, pointer: String) {
+    let has_description = has_text(parameter, "description");
+    report.parameter_descriptions.count(has_description);
+    if !has_description {
+        let name = parameter["name"].as_str().unwrap_or_default();
+        report.gap(pointer, format!("parameter `{name}` has no description"));
+    }
+}
+
+fn has_text(value: &Value, key: &str) -> bool {
+    value[key]
+        .as_str()
+        .is_some_and(|text| !text.trim().is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn score_documentation() {
+        let document = json!({
+            "openapi": "3.0.3",
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "summary": "List pets",
+                        "description": "Lists the pets, most recent first.",
+                        "parameters": [
+                            {"name": "limit", "in": "query", "description": "Page size"},
+                            {"$ref": "#/components/parameters/Offset"}
+                        ],
+                        "responses": {}
+                    },
+                    "post": {"summary": "Add a pet", "description": " ", "responses": {}}
+                }
+            },
+            "components": {
+                "parameters": {"Offset": {"name": "offset", "in": "query"}},
+                "schemas": {
+                    "Pet": {"type": "object", "example": {"name": "Rex"}},
+                    "Error": {"type": "object"}
+                }
+            }
+        });
+
+        let report = score(&document);
+
+        assert_eq!(
+            report.coverages().map(|(_, coverage)| coverage.percent()),
+            [100.0, 50.0, 50.0, 50.0]
+        );
+        assert_eq!(report.score(), 62.5);
+        assert_eq!(
+            report
+                .gaps
+                .iter()
+                .map(|d| (d.pointer.as_str(), d.message.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("/paths/~1pets/post", "operation has no description"),
+                (
+                    "/components/parameters/Offset",
+                    "parameter `offset` has no description"
+                ),
+                ("/components/schemas/Error", "schema has no example"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,159 @@
+//! Tracking of the `$ref`s followed by recursive passes over a document.
+//!
+//! Self-referential schemas such as a `Node` with `children` of type `Node` are common, so
+//! every pass which follows references has to stop at cycles. [`RefStack`] does so and
+//! additionally limits how deep references are nested, see [`max_depth`].
+
+use std::fmt;
+
+use crate::{diagnostics, options};
+
+/// Nesting of `$ref`s followed unless [`ConvertOptions::max_ref_depth`] says otherwise.
+///
+/// [`ConvertOptions::max_ref_depth`]: crate::ConvertOptions::max_ref_depth
+pub(crate) const DEFAULT_MAX_DEPTH: usize = 32;
+
+/// Nesting of `$ref`s followed by passes of the current conversion, or the default outside
+/// of one.
+pub(crate) fn max_depth() -> usize {
+    options::current()
+        .max_ref_depth
+        .unwrap_or(DEFAULT_MAX_DEPTH)
+}
+
+/// Why a `$ref` is not followed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Recursion {
+    /// The reference is already being followed, the chain starts and ends with it.
+    Cycle(Vec<String>),
+    /// Following the reference would exceed the depth limit.
+    TooDeep(usize),
+}
+
+impl fmt::Display for Recursion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Recursion::Cycle(chain) => write!(f, "circular reference {}", chain.join(" -> ")),
+            Recursion::TooDeep(max_depth) => {
+                write!(f, "references are nested deeper than {max_depth}")
+            }
+        }
+    }
+}
+
+/// The chain of `$ref`s a recursive pass is currently following.
+pub(crate) struct RefStack {
+    refs: Vec<String>,
+    max_depth: usize,
+    /// Whether a reference was not followed for being nested too deep.
+    truncated: bool,
+}
+
+impl RefStack {
+    pub fn new(max_depth: usize) -> Self {
+        Self {
+            refs: Vec::new(),
+            max_depth,
+            truncated: false,
+        }
+    }
+
+    /// Like [`Self::enter`], but only tells whether `location` is followed. The first
+    /// reference not followed for being nested too deep is reported as warning. Cycles are
+    /// not, recursive schemas are common and passes simply stop expanding them.
+    pub fn follow(&mut self, location: &str) -> bool {
+        match self.enter(location) {
+            Ok(()) => true,
+            Err(recursion @ Recursion::TooDeep(_)) => {
+                if !self.truncated {
+                    self.truncated = true;
+                    diagnostics::warn(format!("did not follow `{location}`, {recursion}"));
+                }
+                false
+            }
+            Err(Recursion::Cycle(_)) => false,
+        }
+    }
+
+    /// Number of references currently followed.
+    pub fn depth(&self) -> usize {
+        self.refs.len()
+    }
+
+    /// Whether [`Self::follow`] did not follow a reference for being nested too deep.
+    pub fn truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Whether `location` is being followed already.
+    pub fn contains(&self, location: &str) -> bool {
+        self.refs.iter().any(|r| r == location)
+    }
+
+    /// Starts following `location`, which has to be matched by a call to [`Self::leave`]
+    /// once done, unless an error is returned.
+    pub fn enter(&mut self, location: &str) -> Result<(), Recursion> {
+        if let Some(start) = self.refs.iter().position(|r| r == location) {
+            let mut chain: Vec<String> = self.refs[start..].to_vec();
+            chain.push(location.to_string());
+            return Err(Recursion::Cycle(chain));
+        }
+        if self.refs.len() >= self.max_depth {
+            return Err(Recursion::TooDeep(self.max_depth));
+        }
+        self.refs.push(location.to_string());
+        Ok(())
+    }
+
+    pub fn leave(&mut self) {
+        self.refs.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::ConvertOptions;
+
+    #[test]
+    fn detect_cycles_and_depth() {
+        let mut refs = RefStack::new(3);
+        refs.enter("#/A").unwrap();
+        refs.enter("#/B").unwrap();
+
+        let cycle = refs.enter("#/A").unwrap_err();
+        assert_eq!(cycle.to_string(), "circular reference #/A -> #/B -> #/A");
+
+        refs.enter("#/C").unwrap();
+        assert_eq!(refs.enter("#/D"), Err(Recursion::TooDeep(3)));
+
+        refs.leave();
+        refs.enter("#/D").unwrap();
+    }
+
+    #[test]
+    fn follow_reports_truncation_once() {
+        let options = ConvertOptions {
+            max_ref_depth: Some(1),
+            ..Default::default()
+        };
+        let (followed, reported) = diagnostics::collect(|| {
+            options::scope(Arc::new(options), || {
+                let mut refs = RefStack::new(max_depth());
+                let followed = [refs.follow("#/A"), refs.follow("#/A"), refs.follow("#/B")];
+                diagnostics::at("/definitions/C", || refs.follow("#/C"));
+                followed
+            })
+        });
+
+        assert_eq!(followed, [true, false, false]);
+        assert_eq!(reported.len(), 1);
+        assert_eq!(reported[0].pointer, "");
+        assert_eq!(
+            reported[0].message,
+            "did not follow `#/B`, references are nested deeper than 1"
+        );
+    }
+}
@@ -0,0 +1,130 @@
+//! Removal of deprecated operations and schema properties, see
+//! [`ConvertOptions::exclude_deprecated`](crate::ConvertOptions::exclude_deprecated).
+
+use serde_json::{Map, Value};
+
+use crate::{diagnostics, walk};
+
+const METHODS: [&str; 7] = ["get", "put", "post", "delete", "options", "head", "patch"];
+
+/// Drops the operations and schema properties of `document` marked `deprecated` or
+/// `x-deprecated`, reporting each of them. Path items left without operations are dropped
+/// as well.
+pub(crate) fn exclude(document: &mut Value) {
+    if let Some(Value::Object(paths)) = document.get_mut("paths") {
+        paths.retain(|path, path_item| {
+            let Value::Object(path_item) = path_item else {
+                return true;
+            };
+            let pointer = format!("/paths/{}", diagnostics::escape(path));
+            let operations = operation_count(path_item);
+            path_item.retain(|method, operation| {
+                let drop = METHODS.contains(&method.as_str()) && deprecated(operation);
+                if drop {
+                    diagnostics::at(&format!("{pointer}/{method}"), || {
+                        diagnostics::warn("dropped deprecated operation")
+                    });
+                }
+                !drop
+            });
+            operations == 0 || operation_count(path_item) > 0
+        });
+    }
+    walk::enter_objects(document, &mut String::new(), &mut properties);
+}
+
+fn operation_count(path_item: &Map<String, Value>) -> usize {
+    METHODS
+        .iter()
+        .filter(|method| path_item.contains_key(**method))
+        .count()
+}
+
+fn deprecated(value: &Value) -> bool {
+    value.get("deprecated") == Some(&Value::Bool(true))
+        || value.get("x-deprecated") == Some(&Value::Bool(true))
+}
+
+/// Drops the deprecated properties of the schema `object` at `pointer`.
+fn properties(object: &mut Map<String, Value>, pointer: &str) {
+    let mut dropped = Vec::new();
+    if let Some(Value::Object(properties)) = object.get_mut("properties") {
+        properties.retain(|name, schema| {
+            let drop = deprecated(schema);
+            if drop {
+                dropped.push(name.clone());
+            }
+            !drop
+        });
+    }
+    for name in &dropped {
+        let property = format!("{pointer}/properties/{}", diagnostics::escape(name));
+        diagnostics::at(&property, || {
+            diagnostics::warn("dropped deprecated property")
+        });
+    }
+    if let Some(Value::Array(required)) = object.get_mut("required") {
+        required.retain(|name| !dropped.iter().any(|d| name == d.as_str()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::ConvertOptions;
+
+    #[test]
+    fn exclude_deprecated() {
+        let swagger = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "paths": {
+                "/pets": {
+                    "get": {"responses": {"200": {"description": "ok"}}},
+                    "delete": {"deprecated": true, "responses": {"204": {"description": "gone"}}}
+                },
+                "/legacy": {"get": {"x-deprecated": true, "responses": {"200": {"description": "ok"}}}}
+            },
+            "definitions": {
+                "Pet": {
+                    "type": "object",
+                    "required": ["name", "nick"],
+                    "properties": {
+                        "name": {"type": "string"},
+                        "nick": {"type": "string", "x-deprecated": true}
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        let options = ConvertOptions {
+            exclude_deprecated: true,
+            ..Default::default()
+        };
+
+        let conversion = crate::convert_with(swagger, &options);
+
+        let document = conversion.to_value();
+        let paths = document["paths"].as_object().unwrap();
+        assert_eq!(paths.keys().collect::<Vec<_>>(), vec!["/pets"]);
+        assert!(paths["/pets"].get("delete").is_none());
+        assert_eq!(
+            document["components"]["schemas"]["Pet"]["required"],
+            json!(["name"])
+        );
+        let pointers: Vec<&str> = conversion
+            .diagnostics
+            .iter()
+            .map(|d| d.pointer.as_str())
+            .collect();
+        assert_eq!(
+            pointers,
+            vec![
+                "/paths/~1legacy/get",
+                "/paths/~1pets/delete",
+                "/definitions/Pet/properties/nick"
+            ]
+        );
+    }
+}
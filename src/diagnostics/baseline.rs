@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+
+use super::Diagnostic;
+
+/// Findings accepted as known, so only new ones are reported.
+///
+/// Findings are matched by severity, pointer and message. Each recorded finding suppresses
+/// one occurrence, so a finding reported more often than recorded is reported again.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Baseline {
+    pub findings: Vec<Diagnostic>,
+}
+
+impl Baseline {
+    /// Records `diagnostics` as the known findings.
+    pub fn new(diagnostics: &[Diagnostic]) -> Self {
+        Self {
+            findings: diagnostics.to_vec(),
+        }
+    }
+
+    /// Drops the findings of `diagnostics` which are in the baseline.
+    pub fn filter(&self, diagnostics: Vec<Diagnostic>) -> Vec<Diagnostic> {
+        let mut known = self.findings.clone();
+        diagnostics
+            .into_iter()
+            .filter(
+                |diagnostic| match known.iter().position(|k| k == diagnostic) {
+                    Some(index) => {
+                        known.swap_remove(index);
+                        false
+                    }
+                    None => true,
+                },
+            )
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostics::Severity;
+
+    #[test]
+    fn suppress_known_findings() {
+        let finding = |pointer: &str| Diagnostic {
+            severity: Severity::Warning,
+            pointer: pointer.to_string(),
+            message: "dropped unknown path item member `gets`".to_string(),
+        };
+        let baseline = Baseline::new(&[finding("/paths/~1pets/gets")]);
+
+        let reported = baseline.filter(vec![
+            finding("/paths/~1pets/gets"),
+            finding("/paths/~1pets/gets"),
+            finding("/paths/~1owners/gets"),
+        ]);
+
+        assert_eq!(
+            reported,
+            vec![
+                finding("/paths/~1pets/gets"),
+                finding("/paths/~1owners/gets")
+            ]
+        );
+    }
+}
@@ -0,0 +1,263 @@
+use std::iter;
+
+use serde::Serialize;
+
+/// 1-based line and column of an element in a JSON source text.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Location {
+    /// Location of the byte `offset` within `source`.
+    pub fn from_offset(source: &str, offset: usize) -> Self {
+        let before = &source[..offset.min(source.len())];
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        Self {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+}
+
+/// Finds the location of the value addressed by the JSON `pointer` within the JSON `source`.
+///
+/// Returns `None` if the pointer does not resolve or the source is not valid JSON up to the
+/// addressed value.
+pub fn locate(source: &str, pointer: &str) -> Option<Location> {
+    let segments: Vec<String> = if pointer.is_empty() {
+        Vec::new()
+    } else {
+        pointer
+            .strip_prefix('/')?
+            .split('/')
+            .map(|s| s.replace("~1", "/").replace("~0", "~"))
+            .collect()
+    };
+
+    let mut scanner = Scanner {
+        bytes: source.as_bytes(),
+        pos: 0,
+    };
+    let offset = scanner.find(&segments)?;
+    Some(Location::from_offset(source, offset))
+}
+
+/// Renders the line of `source` at `location` and up to `context` lines before it, with a
+/// caret under the column, e.g. to show a parse error in place:
+///
+/// ```text
+///   |
+/// 2 |   "swagger": "2.0",
+/// 3 |   "paths": [}
+///   |             ^
+/// ```
+pub fn snippet(source: &str, location: Location, context: usize) -> String {
+    let lines: Vec<&str> = source.lines().chain(iter::once("")).collect();
+    let line = location.line.clamp(1, lines.len());
+    let first = line.saturating_sub(context).max(1);
+    let width = line.to_string().len();
+
+    let indent: String = lines[line - 1]
+        .chars()
+        .take(location.column.saturating_sub(1))
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+    iter::once(format!("{:width$} |", ""))
+        .chain((first..=line).map(|number| format!("{number:>width$} | {}", lines[number - 1])))
+        .chain(iter::once(format!("{:width$} | {indent}^", "")))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+struct Scanner<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Scanner<'_> {
+    fn find(&mut self, segments: &[String]) -> Option<usize> {
+        self.skip_whitespace();
+        let Some((segment, rest)) = segments.split_first() else {
+            return Some(self.pos);
+        };
+
+        match self.peek()? {
+            b'{' => {
+                self.pos += 1;
+                loop {
+                    self.skip_whitespace();
+                    if self.peek()? == b'}' {
+                        return None;
+                    }
+                    let key = self.string()?;
+                    self.skip_whitespace();
+                    self.expect(b':')?;
+                    if key == *segment {
+                        return self.find(rest);
+                    }
+                    self.skip_value()?;
+                    self.skip_whitespace();
+                    self.expect(b',')?;
+                }
+            }
+            b'[' => {
+                let index: usize = segment.parse().ok()?;
+                self.pos += 1;
+                let mut current = 0;
+                loop {
+                    self.skip_whitespace();
+                    if self.peek()? == b']' {
+                        return None;
+                    }
+                    if current == index {
+                        return self.find(rest);
+                    }
+                    self.skip_value()?;
+                    self.skip_whitespace();
+                    self.expect(b',')?;
+                    current += 1;
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn skip_value(&mut self) -> Option<()> {
+        self.skip_whitespace();
+        match self.peek()? {
+            b'"' => self.string().map(drop),
+            b'{' | b'[' => {
+                let mut depth = 0usize;
+                loop {
+                    match self.peek()? {
+                        b'"' => {
+                            self.string()?;
+                            continue;
+                        }
+                        b'{' | b'[' => depth += 1,
+                        b'}' | b']' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                self.pos += 1;
+                                return Some(());
+                            }
+                        }
+                        _ => {}
+                    }
+                    self.pos += 1;
+                }
+            }
+            _ => {
+                while let Some(b) = self.peek() {
+                    if matches!(b, b',' | b'}' | b']') || b.is_ascii_whitespace() {
+                        break;
+                    }
+                    self.pos += 1;
+                }
+                Some(())
+            }
+        }
+    }
+
+    fn string(&mut self) -> Option<String> {
+        self.expect(b'"')?;
+        let mut out = Vec::new();
+        loop {
+            match self.bump()? {
+                b'"' => return String::from_utf8(out).ok(),
+                b'\\' => match self.bump()? {
+                    b'n' => out.push(b'\n'),
+                    b't' => out.push(b'\t'),
+                    b'r' => out.push(b'\r'),
+                    b'b' => out.push(0x08),
+                    b'f' => out.push(0x0c),
+                    b'u' => {
+                        let hex = self.bytes.get(self.pos..self.pos + 4)?;
+                        self.pos += 4;
+                        let code = u32::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()?;
+                        let c = char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER);
+                        out.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+                    }
+                    escaped => out.push(escaped),
+                },
+                b => out.push(b),
+            }
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|b| b.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, expected: u8) -> Option<()> {
+        (self.bump()? == expected).then_some(())
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn bump(&mut self) -> Option<u8> {
+        let b = self.peek()?;
+        self.pos += 1;
+        Some(b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locate_pointer() {
+        let source = r#"{
+  "swagger": "2.0",
+  "paths": {
+    "/users/{id}": {
+      "get": {
+        "parameters": [
+          {"name": "a", "in": "query", "type": "string"},
+          {"name": "id", "in": "path", "type": "string"}
+        ]
+      }
+    }
+  }
+}"#;
+
+        assert_eq!(locate(source, ""), Some(Location { line: 1, column: 1 }));
+        assert_eq!(
+            locate(source, "/paths/~1users~1{id}/get"),
+            Some(Location {
+                line: 5,
+                column: 14
+            })
+        );
+        assert_eq!(
+            locate(source, "/paths/~1users~1{id}/get/parameters/1"),
+            Some(Location {
+                line: 8,
+                column: 11
+            })
+        );
+        assert_eq!(locate(source, "/paths/~1users/get"), None);
+    }
+
+    #[test]
+    fn snippet_with_caret() {
+        let source = "{\n  \"swagger\": \"2.0\",\n  \"paths\": [}\n";
+        let error = serde_json::from_str::<serde_json::Value>(source).unwrap_err();
+        let location = Location {
+            line: error.line(),
+            column: error.column(),
+        };
+
+        assert_eq!(
+            snippet(source, location, 1),
+            "  |\n2 |   \"swagger\": \"2.0\",\n3 |   \"paths\": [}\n  |             ^"
+        );
+    }
+}
@@ -0,0 +1,226 @@
+//! Non-fatal findings reported while converting a document.
+//!
+//! The `From` conversions in [`crate::spec`] cannot return anything besides the converted
+//! value, so findings are pushed into a thread local collector which is installed by
+//! [`collect`] for the duration of a conversion. Every diagnostic carries the JSON pointer
+//! of the input element it was reported for, see [`locate`] to map it back to a position.
+
+use std::{cell::RefCell, fmt, mem};
+
+use serde::{Deserialize, Serialize};
+
+mod baseline;
+mod location;
+
+pub use baseline::*;
+pub use location::*;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Warning => f.write_str("warning"),
+            Severity::Error => f.write_str("error"),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// JSON pointer of the element in the input document, empty for the document itself.
+    pub pointer: String,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.pointer.is_empty() {
+            write!(f, "{}: {}", self.severity, self.message)
+        } else {
+            write!(f, "{}: {}: {}", self.severity, self.pointer, self.message)
+        }
+    }
+}
+
+impl Diagnostic {
+    /// Formats the diagnostic as a GitHub Actions workflow command, which shows it as an
+    /// annotation of `file` at `location`.
+    pub fn github_annotation(&self, file: &str, location: Option<Location>) -> String {
+        let mut properties = format!("file={}", escape_property(file));
+        if let Some(location) = location {
+            properties.push_str(&format!(",line={},col={}", location.line, location.column));
+        }
+        if !self.pointer.is_empty() {
+            properties.push_str(&format!(",title={}", escape_property(&self.pointer)));
+        }
+        format!(
+            "::{} {properties}::{}",
+            self.severity,
+            escape_data(&self.message)
+        )
+    }
+}
+
+fn escape_data(data: &str) -> String {
+    data.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_property(property: &str) -> String {
+    escape_data(property)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+thread_local! {
+    static COLLECTOR: RefCell<Option<Vec<Diagnostic>>> = const { RefCell::new(None) };
+    static POINTER: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+/// Runs `f` and returns its result together with every diagnostic reported during the call.
+pub fn collect<T>(f: impl FnOnce() -> T) -> (T, Vec<Diagnostic>) {
+    let restore = Restore::Collector(COLLECTOR.with(|c| c.replace(Some(Vec::new()))));
+    let value = f();
+    let diagnostics = COLLECTOR.with(|c| c.take()).unwrap_or_default();
+    drop(restore);
+    (value, diagnostics)
+}
+
+/// Runs `f` with `segment` appended to the pointer of diagnostics reported during the call.
+pub(crate) fn scope<T>(segment: impl fmt::Display, f: impl FnOnce() -> T) -> T {
+    let _restore = Restore::Truncate(POINTER.with(|p| {
+        let mut pointer = p.borrow_mut();
+        let len = pointer.len();
+        pointer.push('/');
+        pointer.push_str(&escape(&segment.to_string()));
+        len
+    }));
+    f()
+}
+
+/// Runs `f` with the pointer of reported diagnostics set to `pointer`.
+pub(crate) fn at<T>(pointer: &str, f: impl FnOnce() -> T) -> T {
+    let _restore = Restore::Pointer(POINTER.with(|p| p.replace(pointer.to_string())));
+    f()
+}
+
+/// Undoes [`collect`], [`scope`] or [`at`] when dropped, so a panic does not leave the
+/// collector or pointer of the call behind.
+enum Restore {
+    Collector(Option<Vec<Diagnostic>>),
+    Pointer(String),
+    Truncate(usize),
+}
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        match self {
+            Restore::Collector(previous) => COLLECTOR.with(|c| *c.borrow_mut() = previous.take()),
+            Restore::Pointer(previous) => POINTER.with(|p| *p.borrow_mut() = mem::take(previous)),
+            Restore::Truncate(len) => POINTER.with(|p| p.borrow_mut().truncate(*len)),
+        }
+    }
+}
+
+/// JSON pointer of the element currently being converted.
+pub(crate) fn pointer() -> String {
+    POINTER.with(|p| p.borrow().clone())
+}
+
+/// Escapes a JSON pointer reference token.
+pub fn escape(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+pub(crate) fn report(severity: Severity, message: impl Into<String>) {
+    COLLECTOR.with(|c| {
+        if let Some(diagnostics) = c.borrow_mut().as_mut() {
+            diagnostics.push(Diagnostic {
+                severity,
+                pointer: pointer(),
+                message: message.into(),
+            });
+        }
+    });
+}
+
+/// Reports diagnostics collected elsewhere, e.g. on another thread.
+pub(crate) fn extend(reported: Vec<Diagnostic>) {
+    COLLECTOR.with(|c| {
+        if let Some(diagnostics) = c.borrow_mut().as_mut() {
+            diagnostics.extend(reported);
+        }
+    });
+}
+
+pub(crate) fn warn(message: impl Into<String>) {
+    report(Severity::Warning, message);
+}
+
+pub(crate) fn error(message: impl Into<String>) {
+    report(Severity::Error, message);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn github_annotation() {
+        let diagnostic = Diagnostic {
+            severity: Severity::Error,
+            pointer: "/paths/~1a,b".to_string(),
+            message: "dangling reference: 100%\nfix it".to_string(),
+        };
+
+        assert_eq!(
+            diagnostic.github_annotation("api.json", Some(Location { line: 3, column: 7 })),
+            "::error file=api.json,line=3,col=7,title=/paths/~1a%2Cb::dangling reference: 100%25%0Afix it"
+        );
+    }
+
+    #[test]
+    fn restore_after_panic() {
+        let ((), diagnostics) = collect(|| {
+            let result = std::panic::catch_unwind(|| {
+                collect(|| {
+                    at("/definitions", || {
+                        scope("Pet", || panic!("conversion failed"))
+                    })
+                })
+            });
+            assert!(result.is_err());
+            warn("after");
+        });
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].pointer, "");
+    }
+
+    #[test]
+    fn collect_nested() {
+        let ((), outer) = collect(|| {
+            warn("outer");
+            let ((), inner) = collect(|| scope("paths", || scope("/users", || warn("inner"))));
+            assert_eq!(inner.len(), 1);
+            assert_eq!(inner[0].pointer, "/paths/~1users");
+        });
+
+        assert_eq!(
+            outer,
+            vec![Diagnostic {
+                severity: Severity::Warning,
+                pointer: String::new(),
+                message: "outer".to_string()
+            }]
+        );
+    }
+}
@@ -0,0 +1,237 @@
+//! Verification of the `discriminator`s of definitions, which generated clients rely on to
+//! deserialize polymorphic payloads.
+//!
+//! The discriminator property must be declared and required by the schema naming it, and
+//! the definitions extending that schema through `allOf` must be told apart by distinct
+//! values. A definition's value is its `x-discriminator-value`, the single `enum` value it
+//! gives the property, or its name otherwise.
+
+use std::collections::BTreeMap;
+
+use serde_json::{Map, Value};
+
+use crate::{
+    cycle::{self, RefStack},
+    diagnostics,
+};
+
+const DEFINITIONS: &str = "#/definitions/";
+
+/// Reports the discriminators of the definitions of the serialized Swagger `document` which
+/// would break polymorphic deserialization.
+pub(crate) fn verify(document: &Value) {
+    let Some(Value::Object(definitions)) = document.get("definitions") else {
+        return;
+    };
+    for (name, schema) in definitions {
+        let Some(property) = schema.get("discriminator").and_then(Value::as_str) else {
+            continue;
+        };
+        let pointer = format!("/definitions/{}", diagnostics::escape(name));
+        diagnostics::at(&format!("{pointer}/discriminator"), || {
+            parent(schema, property, definitions)
+        });
+        children(name, property, definitions);
+    }
+}
+
+/// The members of a definition [`verify`] reads, so the discriminators of a document can be
+/// checked without keeping its definitions whole.
+pub(crate) fn outline(schema: &Value) -> Value {
+    let Value::Object(schema) = schema else {
+        return Value::Null;
+    };
+    let mut outline = Map::new();
+    for key in ["$ref", "discriminator", "x-discriminator-value", "required"] {
+        if let Some(value) = schema.get(key) {
+            outline.insert(key.to_string(), value.clone());
+        }
+    }
+    if let Some(Value::Array(members)) = schema.get("allOf") {
+        let members = members.iter().map(outline).collect();
+        outline.insert("allOf".to_string(), Value::Array(members));
+    }
+    if let Some(Value::Object(properties)) = schema.get("properties") {
+        let properties = properties
+            .iter()
+            .map(|(name, property)| {
+                let mut property_outline = Map::new();
+                if let Some(values) = property.get("enum") {
+                    property_outline.insert("enum".to_string(), values.clone());
+                }
+                (name.clone(), Value::Object(property_outline))
+            })
+            .collect();
+        outline.insert("properties".to_string(), Value::Object(properties));
+    }
+    Value::Object(outline)
+}
+
+/// Checks that the schema with the discriminator declares and requires `property`.
+fn parent(schema: &Value, property: &str, definitions: &Map<String, Value>) {
+    let mut declared = Declared::default();
+    declared.collect(schema, definitions, &mut RefStack::new(cycle::max_depth()));
+    if !declared.properties.contains_key(property) {
+        diagnostics::error(format!(
+            "discriminator property `{property}` is not declared by the schema"
+        ));
+    } else if !declared.required.iter().any(|name| name == property) {
+        diagnostics::warn(format!(
+            "discriminator property `{property}` is not required by the schema"
+        ));
+    }
+}
+
+/// Checks that the definitions extending `parent` have distinct discriminator values.
+fn children(parent: &str, property: &str, definitions: &Map<String, Value>) {
+    let location = format!("{DEFINITIONS}{parent}");
+    let mut values: BTreeMap<String, &str> = BTreeMap::new();
+    for (name, schema) in definitions {
+        let extends = schema["allOf"]
+            .as_array()
+            .is_some_and(|members| members.iter().any(|member| member["$ref"] == *location));
+        if !extends {
+            continue;
+        }
+        let value = discriminator_value(name, schema, property, definitions);
+        match values.get(&value) {
+            Some(other) => {
+                let pointer = format!("/definitions/{}", diagnostics::escape(name));
+                diagnostics::at(&pointer, || {
+                    diagnostics::error(format!(
+                        "discriminator value `{value}` of `{parent}` is already used by `{other}`"
+                    ))
+                });
+            }
+            None => {
+                values.insert(value, name);
+            }
+        }
+    }
+}
+
+fn discriminator_value(
+    name: &str,
+    schema: &Value,
+    property: &str,
+    definitions: &Map<String, Value>,
+) -> String {
+    if let Some(value) = schema.get("x-discriminator-value").and_then(Value::as_str) {
+        return value.to_string();
+    }
+    let mut declared = Declared::default();
+    declared.collect(schema, definitions, &mut RefStack::new(cycle::max_depth()));
+    match declared.properties.get(property).map(|p| &p["enum"]) {
+        Some(Value::Array(values)) if values.len() == 1 => match &values[0] {
+            Value::String(value) => value.clone(),
+            value => value.to_string(),
+        },
+        _ => name.to_string(),
+    }
+}
+
+/// Properties and required names of a schema, including those of its `allOf` members. The
+/// properties declared last win, so a definition refines the properties it extends.
+#[derive(Default)]
+struct Declared<'a> {
+    properties: BTreeMap<&'a str, &'a Value>,
+    required: Vec<&'a str>,
+}
+
+impl<'a> Declared<'a> {
+    fn collect(
+        &mut self,
+        schema: &'a Value,
+        definitions: &'a Map<String, Value>,
+        refs: &mut RefStack,
+    ) {
+        if let Some(location) = schema["$ref"].as_str() {
+            let target = location
+                .strip_prefix(DEFINITIONS)
+                .and_then(|name| definitions.get(name));
+            if let Some(target) = target {
+                if refs.follow(location) {
+                    self.collect(target, definitions, refs);
+                    refs.leave();
+                }
+                return;
+            }
+        }
+        for member in schema["allOf"].as_array().into_iter().flatten() {
+            self.collect(member, definitions, refs);
+        }
+        if let Some(properties) = schema["properties"].as_object() {
+            self.properties
+                .extend(properties.iter().map(|(name, p)| (name.as_str(), p)));
+        }
+        let required = schema["required"].as_array().into_iter().flatten();
+        self.required.extend(required.filter_map(Value::as_str));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    #[test]
+    fn verify_discriminators() {
+        let swagger = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "paths": {},
+            "definitions": {
+                "Pet": {
+                    "type": "object",
+                    "discriminator": "petType",
+                    "properties": {"petType": {"type": "string"}}
+                },
+                "Cat": {
+                    "allOf": [
+                        {"$ref": "#/definitions/Pet"},
+                        {"type": "object", "properties": {"lives": {"type": "integer"}}}
+                    ]
+                },
+                "Lion": {
+                    "x-discriminator-value": "Cat",
+                    "allOf": [{"$ref": "#/definitions/Pet"}]
+                },
+                "Dog": {
+                    "allOf": [
+                        {"$ref": "#/definitions/Pet"},
+                        {"properties": {"petType": {"type": "string", "enum": ["dog"]}}}
+                    ]
+                },
+                "Shape": {"type": "object", "discriminator": "kind"}
+            }
+        }))
+        .unwrap();
+
+        let conversion = crate::convert(swagger);
+
+        assert_eq!(
+            conversion.to_value()["components"]["schemas"]["Pet"]["discriminator"],
+            json!({"propertyName": "petType"})
+        );
+        assert_eq!(
+            conversion
+                .diagnostics
+                .iter()
+                .map(|d| (d.pointer.as_str(), d.message.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "/definitions/Pet/discriminator",
+                    "discriminator property `petType` is not required by the schema"
+                ),
+                (
+                    "/definitions/Lion",
+                    "discriminator value `Cat` of `Pet` is already used by `Cat`"
+                ),
+                (
+                    "/definitions/Shape/discriminator",
+                    "discriminator property `kind` is not declared by the schema"
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,350 @@
+//! Compatibility with the documents Swashbuckle and NSwag generate for .NET APIs, see
+//! [`ConvertOptions::dotnet`](crate::ConvertOptions::dotnet).
+//!
+//! Both name the operations of overloaded actions alike, which is resolved by appending the
+//! method to the repeated `operationId`s, and pointing the `x-links` naming them at the
+//! operation taking the parameters of the link. The names of enum members in NSwag's `x-enumNames`
+//! and Swashbuckle's `x-ms-enum` become `x-enum-varnames`, which most generators read.
+//! `x-nullable` needs no special treatment, it is converted anyway.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde_json::{json, Map, Value};
+
+use crate::{diagnostics, walk};
+
+const METHODS: [&str; 7] = ["get", "put", "post", "delete", "options", "head", "patch"];
+
+/// Extension naming the members of an enum, as read by the generators.
+const VARNAMES: &str = "x-enum-varnames";
+
+/// Rewrites the .NET conventions of `document` into their common form.
+pub(crate) fn adapt(document: &mut Value) {
+    operation_ids(document);
+    walk::enter_objects(document, &mut String::new(), &mut varnames);
+}
+
+/// Operation of the document, by path and method.
+struct Operation {
+    path: String,
+    method: &'static str,
+    operation_id: String,
+}
+
+/// Appends the method to the `operationId`s repeating an earlier one, and a number if that
+/// is taken as well, then points the `x-links` naming a repeated `operationId` at the right
+/// operation.
+fn operation_ids(document: &mut Value) {
+    let Some(Value::Object(paths)) = document.get_mut("paths") else {
+        return;
+    };
+    let mut operations = Vec::new();
+    for (path, path_item) in paths.iter() {
+        for method in METHODS {
+            if let Some(Value::String(operation_id)) =
+                path_item.pointer(&format!("/{method}/operationId"))
+            {
+                operations.push(Operation {
+                    path: path.clone(),
+                    method,
+                    operation_id: operation_id.clone(),
+                });
+            }
+        }
+    }
+    // renamed operations must not take an `operationId` given later in the document
+    let mut taken: BTreeSet<String> = operations
+        .iter()
+        .map(|operation| operation.operation_id.clone())
+        .collect();
+    let mut seen = BTreeSet::new();
+    let mut repeated: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for (i, operation) in operations.iter().enumerate() {
+        if seen.insert(operation.operation_id.clone()) {
+            continue;
+        }
+        let Operation {
+            path,
+            method,
+            operation_id,
+        } = operation;
+        let mut renamed = format!("{operation_id}_{method}");
+        let mut n = 2;
+        while taken.contains(&renamed) {
+            renamed = format!("{operation_id}_{method}{n}");
+            n += 1;
+        }
+        let pointer = format!("/paths/{}/{method}/operationId", diagnostics::escape(path));
+        diagnostics::at(&pointer, || {
+            diagnostics::warn(format!(
+                "renamed duplicate operationId `{operation_id}` to `{renamed}`"
+            ))
+        });
+        paths[path.as_str()][*method]["operationId"] = Value::String(renamed.clone());
+        taken.insert(renamed);
+        repeated.entry(operation_id.clone()).or_default().push(i);
+    }
+    if repeated.is_empty() {
+        return;
+    }
+    // the first operation of each repeated id keeps it
+    for (operation_id, renamed) in repeated.iter_mut() {
+        if let Some(first) = operations
+            .iter()
+            .position(|operation| operation.operation_id == *operation_id)
+        {
+            renamed.insert(0, first);
+        }
+    }
+    for operation in &mut operations {
+        let current = &paths[operation.path.as_str()][operation.method]["operationId"];
+        if let Value::String(current) = current {
+            operation.operation_id = current.clone();
+        }
+    }
+    links(document, &operations, &repeated);
+}
+
+/// Rewrites the `operationId` of the `x-links` naming a repeated one to the operation taking
+/// every parameter of the link. Links matching several or none of them keep the first and
+/// are reported.
+fn links(document: &mut Value, operations: &[Operation], repeated: &BTreeMap<String, Vec<usize>>) {
+    let parameters: Vec<BTreeSet<String>> = operations
+        .iter()
+        .map(|operation| parameter_names(document, operation))
+        .collect();
+
+    let mut responses = Vec::new();
+    if let Some(Value::Object(shared)) = document.get("responses") {
+        for name in shared.keys() {
+            responses.push(format!("/responses/{}", diagnostics::escape(name)));
+        }
+    }
+    for operation in operations {
+        let pointer = format!(
+            "/paths/{}/{}/responses",
+            diagnostics::escape(&operation.path),
+            operation.method
+        );
+        if let Some(Value::Object(own)) = document.pointer(&pointer) {
+            for status in own.keys() {
+                responses.push(format!("{pointer}/{}", diagnostics::escape(status)));
+            }
+        }
+    }
+
+    for response in responses {
+        let Some(Value::Object(links)) = document.pointer_mut(&format!("{response}/x-links"))
+        else {
+            continue;
+        };
+        for (name, link) in links.iter_mut() {
+            let Some(Value::String(operation_id)) = link.get("operationId") else {
+                continue;
+            };
+            let Some(candidates) = repeated.get(operation_id) else {
+                continue;
+            };
+            let keys: BTreeSet<&str> = link
+                .get("parameters")
+                .and_then(Value::as_object)
+                .into_iter()
+                .flat_map(|parameters| parameters.keys())
+                .map(|key| match key.split_once('.') {
+                    Some(("path" | "query" | "header" | "cookie", name)) => name,
+                    _ => key.as_str(),
+                })
+                .collect();
+            let matching: Vec<usize> = candidates
+                .iter()
+                .copied()
+                .filter(|i| keys.iter().all(|key| parameters[*i].contains(*key)))
+                .collect();
+            let target = match matching[..] {
+                [i] => &operations[i],
+                _ => {
+                    let first = &operations[candidates[0]];
+                    let pointer = format!("{response}/x-links/{}", diagnostics::escape(name));
+                    diagnostics::at(&pointer, || {
+                        diagnostics::warn(format!(
+                            "link names the duplicate operationId `{operation_id}`, which is kept \
+                             by {} `{}`",
+                            first.method, first.path
+                        ))
+                    });
+                    first
+                }
+            };
+            link["operationId"] = Value::String(target.operation_id.clone());
+        }
+    }
+}
+
+/// Names of the path and operation level parameters of `operation`.
+fn parameter_names(document: &Value, operation: &Operation) -> BTreeSet<String> {
+    let path_item = &document["paths"][operation.path.as_str()];
+    [
+        &path_item["parameters"],
+        &path_item[operation.method]["parameters"],
+    ]
+    .into_iter()
+    .filter_map(Value::as_array)
+    .flatten()
+    .filter_map(|parameter| parameter["name"].as_str())
+    .map(str::to_string)
+    .collect()
+}
+
+/// Adds `x-enum-varnames` to an enum with member names in `x-enumNames` or `x-ms-enum`,
+/// replacing `x-enumNames`. Names which do not match the enum values are reported.
+fn varnames(object: &mut Map<String, Value>, pointer: &str) {
+    let Some(Value::Array(values)) = object.get("enum").cloned() else {
+        return;
+    };
+    if object.contains_key(VARNAMES) {
+        return;
+    }
+    let names = match object.remove("x-enumNames") {
+        Some(Value::Array(names)) if names.len() == values.len() => Some(Value::Array(names)),
+        Some(_) => {
+            diagnostics::at(&format!("{pointer}/x-enumNames"), || {
+                diagnostics::warn("dropped `x-enumNames`, which does not name every enum value")
+            });
+            None
+        }
+        None => ms_enum_names(object.get("x-ms-enum"), &values),
+    };
+    if let Some(names) = names {
+        object.insert(VARNAMES.to_string(), names);
+    }
+}
+
+/// Names of the enum `values` in the `values` of an `x-ms-enum`, if it names every value.
+fn ms_enum_names(ms_enum: Option<&Value>, values: &[Value]) -> Option<Value> {
+    let members = ms_enum?.get("values")?.as_array()?;
+    let names = values
+        .iter()
+        .map(|value| {
+            members
+                .iter()
+                .find(|member| member.get("value") == Some(value))
+                .and_then(|member| member.get("name").cloned())
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(json!(names))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::ConvertOptions;
+
+    #[test]
+    fn adapt_dotnet() {
+        let swagger = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "v1"},
+            "paths": {
+                "/api/Pets": {
+                    "get": {"operationId": "Pets_Get", "responses": {}},
+                    "post": {
+                        "operationId": "Pets_Post",
+                        "responses": {
+                            "201": {
+                                "description": "Created",
+                                "x-links": {
+                                    "GetPet": {
+                                        "operationId": "Pets_Get",
+                                        "parameters": {"path.id": "$response.body#/id"}
+                                    }
+                                }
+                            }
+                        }
+                    }
+                },
+                "/api/Pets/{id}": {
+                    "get": {
+                        "operationId": "Pets_Get",
+                        "parameters": [
+                            {"name": "id", "in": "path", "required": true, "type": "integer"}
+                        ],
+                        "responses": {}
+                    }
+                },
+                "/api/Pets/{id}/owner": {
+                    "parameters": [
+                        {"name": "id", "in": "path", "required": true, "type": "integer"}
+                    ],
+                    "get": {"operationId": "Pets_Get_get", "responses": {}}
+                }
+            },
+            "definitions": {
+                "Kind": {
+                    "type": "integer",
+                    "enum": [0, 1],
+                    "x-enumNames": ["Cat", "Dog"]
+                },
+                "Size": {
+                    "type": "string",
+                    "enum": ["S", "L"],
+                    "x-ms-enum": {
+                        "name": "Size",
+                        "values": [
+                            {"value": "S", "name": "Small"},
+                            {"value": "L", "name": "Large"}
+                        ]
+                    }
+                },
+                "Color": {"type": "string", "enum": ["red"], "x-enumNames": ["Red", "Blue"]}
+            }
+        }))
+        .unwrap();
+        let options = ConvertOptions {
+            dotnet: true,
+            ..Default::default()
+        };
+
+        let conversion = crate::convert_with(swagger, &options);
+
+        let document = conversion.to_value();
+        let paths = &document["paths"];
+        assert_eq!(
+            paths["/api/Pets/{id}"]["get"]["operationId"],
+            "Pets_Get_get2"
+        );
+        assert_eq!(
+            paths["/api/Pets/{id}/owner"]["get"]["operationId"],
+            "Pets_Get_get"
+        );
+        assert_eq!(
+            paths["/api/Pets"]["post"]["responses"]["201"]["links"]["GetPet"]["operationId"],
+            "Pets_Get_get2"
+        );
+        let schemas = &document["components"]["schemas"];
+        assert_eq!(schemas["Kind"]["x-enum-varnames"], json!(["Cat", "Dog"]));
+        assert!(schemas["Kind"].get("x-enumNames").is_none());
+        assert_eq!(
+            schemas["Size"]["x-enum-varnames"],
+            json!(["Small", "Large"])
+        );
+        assert!(schemas["Color"].get("x-enum-varnames").is_none());
+        assert_eq!(
+            conversion
+                .diagnostics
+                .iter()
+                .map(|d| (d.pointer.as_str(), d.message.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "/paths/~1api~1Pets~1{id}/get/operationId",
+                    "renamed duplicate operationId `Pets_Get` to `Pets_Get_get2`"
+                ),
+                (
+                    "/definitions/Color/x-enumNames",
+                    "dropped `x-enumNames`, which does not name every enum value"
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,21 @@
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("failed to parse swagger document: {0}")]
+    Parse(serde_json::Error),
+    #[error("failed to serialize openapi document: {0}")]
+    Serialize(serde_json::Error),
+    #[cfg(feature = "yaml")]
+    #[error("failed to serialize openapi document: {0}")]
+    SerializeYaml(serde_yaml::Error),
+    #[error("unsupported document version `{0}`, expected swagger 2.0 or openapi 3.x")]
+    UnsupportedVersion(String),
+    #[error("`{0}` is not supported by streaming conversion")]
+    Unsupported(&'static str),
+    #[error("failed to read swagger document: {0}")]
+    Read(std::io::Error),
+    #[cfg(feature = "tokio")]
+    #[error("failed to fetch swagger document: {0}")]
+    Fetch(reqwest::Error),
+    #[error("failed to write document: {0}")]
+    Write(std::io::Error),
+}
@@ -0,0 +1,51 @@
+use utoipa::openapi::OpenApi;
+
+const TEMPLATE: &str = include_str!("template.html");
+
+/// Renders a converted document as a single self-contained HTML page.
+///
+/// The page embeds the document and a small script rendering its operations and schemas, so
+/// it can be opened straight from disk without any external assets.
+pub fn html(openapi: &OpenApi) -> String {
+    let spec = serde_json::to_string(openapi).unwrap_or_default();
+    TEMPLATE
+        .replace("{{title}}", &escape_html(&openapi.info.title))
+        .replace("{{spec}}", &escape_script(&spec))
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Keeps the embedded JSON from ending its `<script>` element. `<` only occurs within JSON
+/// strings, where `\/` and `\u0021` are valid escapes.
+fn escape_script(json: &str) -> String {
+    json.replace("</", "<\\/").replace("<!--", "<\\u0021--")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::spec::Swagger;
+
+    #[test]
+    fn embed_spec() {
+        let swagger: Swagger = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {"title": "<Pets>", "version": "1.0", "description": "</script><b>"},
+            "paths": {}
+        }))
+        .unwrap();
+
+        let html = html(&crate::convert(swagger).openapi);
+
+        assert!(html.contains("<title>&lt;Pets&gt;</title>"));
+        assert!(html.contains(r#""description":"<\/script><b>""#));
+        assert_eq!(html.matches("</script>").count(), 2);
+    }
+}
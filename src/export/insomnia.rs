@@ -0,0 +1,196 @@
+use serde_json::{json, Map, Value};
+use utoipa::openapi::OpenApi;
+
+use super::{plain, Document, Operation};
+
+const WORKSPACE_ID: &str = "wrk_1";
+
+/// Exports a converted document as an Insomnia v4 export.
+///
+/// The export holds a workspace with a request group per first tag and a request per
+/// operation. The server URL is exposed as the `base_url` variable of the base environment
+/// and path parameters as environment variables of the same name.
+pub fn insomnia_export(openapi: &OpenApi) -> Value {
+    let document = Document::new(openapi);
+
+    let mut workspace = json!({
+        "_id": WORKSPACE_ID,
+        "_type": "workspace",
+        "parentId": null,
+        "name": document.str("/info/title").unwrap_or_default(),
+    });
+    if let Some(description) = document.str("/info/description") {
+        workspace["description"] = json!(description);
+    }
+
+    let operations = document.operations();
+    let mut environment = Map::new();
+    environment.insert(
+        "base_url".to_string(),
+        json!(document.base_url().trim_end_matches('/')),
+    );
+    for operation in &operations {
+        for parameter in operation.parameters_in("path") {
+            let name = parameter.get("name").and_then(Value::as_str);
+            if let Some(name) = name.filter(|name| !environment.contains_key(*name)) {
+                let example = parameter.get("schema").map(|s| document.example(s));
+                let value = example.as_ref().map(plain).unwrap_or_default();
+                environment.insert(name.to_string(), json!(value));
+            }
+        }
+    }
+
+    let mut resources = vec![
+        workspace,
+        json!({
+            "_id": "env_1",
+            "_type": "environment",
+            "parentId": WORKSPACE_ID,
+            "name": "Base Environment",
+            "data": environment,
+        }),
+    ];
+
+    let mut groups: Vec<String> = Vec::new();
+    let mut requests = Vec::with_capacity(operations.len());
+    for (i, operation) in operations.iter().enumerate() {
+        let parent_id = match operation.tags().first() {
+            Some(tag) => {
+                let index = match groups.iter().position(|g| g == tag) {
+                    Some(index) => index,
+                    None => {
+                        groups.push(tag.to_string());
+                        groups.len() - 1
+                    }
+                };
+                format!("fld_{}", index + 1)
+            }
+            None => WORKSPACE_ID.to_string(),
+        };
+        requests.push(insomnia_request(&document, operation, i + 1, parent_id));
+    }
+    resources.extend(groups.into_iter().enumerate().map(|(i, tag)| {
+        json!({
+            "_id": format!("fld_{}", i + 1),
+            "_type": "request_group",
+            "parentId": WORKSPACE_ID,
+            "name": tag,
+        })
+    }));
+    resources.extend(requests);
+
+    json!({
+        "_type": "export",
+        "__export_format": 4,
+        "__export_source": "swagger-convert",
+        "resources": resources,
+    })
+}
+
+fn insomnia_request(
+    document: &Document,
+    operation: &Operation,
+    id: usize,
+    parent_id: String,
+) -> Value {
+    // insomnia references environment variables as `{{ _.name }}`
+    let path: String = operation
+        .path
+        .split('/')
+        .map(
+            |segment| match segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+                Some(name) => format!("{{{{ _.{name} }}}}"),
+                None => segment.to_string(),
+            },
+        )
+        .collect::<Vec<_>>()
+        .join("/");
+
+    let pair = |parameter: &Value| {
+        let example = parameter.get("schema").map(|s| document.example(s));
+        json!({
+            "name": parameter.get("name").and_then(Value::as_str).unwrap_or_default(),
+            "value": example.as_ref().map(plain).unwrap_or_default(),
+            "disabled": parameter.get("required") != Some(&Value::Bool(true)),
+        })
+    };
+    let parameters: Vec<Value> = operation.parameters_in("query").map(pair).collect();
+    let mut headers: Vec<Value> = operation.parameters_in("header").map(pair).collect();
+
+    let mut body = json!({});
+    if let Some((media_type, content)) = operation.request_body() {
+        let example = content
+            .get("example")
+            .cloned()
+            .or_else(|| content.get("schema").map(|s| document.example(s)))
+            .unwrap_or(Value::Null);
+        body = json!({
+            "mimeType": media_type,
+            "text": serde_json::to_string_pretty(&example).unwrap_or_default(),
+        });
+        headers.push(json!({"name": "Content-Type", "value": media_type}));
+    }
+
+    let mut request = json!({
+        "_id": format!("req_{id}"),
+        "_type": "request",
+        "parentId": parent_id,
+        "name": operation.name(),
+        "method": operation.method.to_uppercase(),
+        "url": format!("{{{{ _.base_url }}}}{path}"),
+        "parameters": parameters,
+        "headers": headers,
+        "body": body,
+    });
+    if let Some(description) = operation.str("description") {
+        request["description"] = json!(description);
+    }
+    request
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::spec::Swagger;
+
+    #[test]
+    fn export_insomnia() {
+        let swagger: Swagger = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "host": "example.com",
+            "schemes": ["https"],
+            "paths": {
+                "/pets/{id}": {
+                    "get": {
+                        "tags": ["pets"],
+                        "summary": "Get a pet",
+                        "parameters": [
+                            {"name": "id", "in": "path", "required": true, "type": "string"},
+                            {"name": "fields", "in": "query", "type": "string"}
+                        ],
+                        "responses": {"200": {"description": "ok"}}
+                    }
+                }
+            }
+        }))
+        .unwrap();
+
+        let export = insomnia_export(&crate::convert(swagger).openapi);
+        let resources = export["resources"].as_array().unwrap();
+
+        assert_eq!(export["__export_format"], 4);
+        assert_eq!(resources[1]["data"]["base_url"], "https://example.com");
+        assert_eq!(resources[1]["data"]["id"], "string");
+        assert_eq!(resources[2]["_type"], "request_group");
+        assert_eq!(resources[2]["name"], "pets");
+        assert_eq!(resources[3]["parentId"], "fld_1");
+        assert_eq!(resources[3]["url"], "{{ _.base_url }}/pets/{{ _.id }}");
+        assert_eq!(
+            resources[3]["parameters"],
+            json!([{"name": "fields", "value": "string", "disabled": true}])
+        );
+    }
+}
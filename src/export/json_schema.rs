@@ -0,0 +1,405 @@
+use std::collections::BTreeMap;
+
+use serde_json::{json, Map, Value};
+use utoipa::openapi::OpenApi;
+
+const SCHEMAS: &str = "#/components/schemas/";
+
+/// JSON Schema draft the exported schemas are written for.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Draft {
+    Draft4,
+    #[default]
+    Draft7,
+    Draft2020_12,
+}
+
+impl Draft {
+    /// Meta schema URI used as `$schema`.
+    pub fn uri(self) -> &'static str {
+        match self {
+            Draft::Draft4 => "http://json-schema.org/draft-04/schema#",
+            Draft::Draft7 => "http://json-schema.org/draft-07/schema#",
+            Draft::Draft2020_12 => "https://json-schema.org/draft/2020-12/schema",
+        }
+    }
+}
+
+/// Exports every component schema as a standalone JSON Schema document.
+///
+/// Returns the documents by file name. References between component schemas become
+/// relative references to the file of the target, so the documents are meant to be placed
+/// in the same directory. OpenAPI specific keywords are rewritten to their equivalent in
+/// `draft`, e.g. `nullable` becomes a `null` type, or dropped if there is none.
+pub fn json_schemas(openapi: &OpenApi, draft: Draft) -> BTreeMap<String, Value> {
+    let reference = |target: &str| match target.split_once('/') {
+        Some((name, pointer)) => format!("{}#/{pointer}", file_name(name)),
+        None => file_name(target),
+    };
+    component_schemas(openapi)
+        .into_iter()
+        .map(|(name, mut schema)| {
+            to_dialect(&mut schema, draft);
+            standalone(&mut schema, &reference);
+            if let Value::Object(object) = &mut schema {
+                object.insert("$schema".to_string(), json!(draft.uri()));
+                object.entry("title").or_insert_with(|| json!(name.clone()));
+            }
+            (file_name(&name), schema)
+        })
+        .collect()
+}
+
+/// Exports every component schema as a draft 7 schema of `schemars`, e.g. to add them to
+/// the definitions of a [`SchemaGenerator`](schemars::gen::SchemaGenerator).
+///
+/// References between component schemas point into `#/definitions/`, where schemars keeps
+/// its definitions by default. Schemas schemars cannot represent are left out.
+#[cfg(feature = "schemars")]
+pub fn schemars_definitions(openapi: &OpenApi) -> schemars::Map<String, schemars::schema::Schema> {
+    let reference = |target: &str| format!("#/definitions/{target}");
+    component_schemas(openapi)
+        .into_iter()
+        .filter_map(|(name, mut schema)| {
+            to_dialect(&mut schema, Draft::Draft7);
+            standalone(&mut schema, &reference);
+            Some((name, serde_json::from_value(schema).ok()?))
+        })
+        .collect()
+}
+
+/// The serialized component schemas of `openapi` by name.
+fn component_schemas(openapi: &OpenApi) -> Map<String, Value> {
+    match serde_json::to_value(openapi) {
+        Ok(mut root) => match root.pointer_mut("/components/schemas").map(Value::take) {
+            Some(Value::Object(schemas)) => schemas,
+            _ => Map::new(),
+        },
+        Err(_) => Map::new(),
+    }
+}
+
+/// File name of the document of the component schema `name`.
+pub fn file_name(name: &str) -> String {
+    let stem: String = name
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '-' | '_' => c,
+            _ => '_',
+        })
+        .collect();
+    format!("{stem}.json")
+}
+
+/// Rewrites the OpenAPI 3.0 keywords of a schema and its subschemas to `draft`.
+pub(crate) fn to_dialect(schema: &mut Value, draft: Draft) {
+    let Value::Object(object) = schema else {
+        return;
+    };
+    // the common workaround for the missing keyword, its schemas are already converted
+    if let Some(pattern_properties) = object.remove("x-patternProperties") {
+        object
+            .entry("patternProperties")
+            .or_insert(pattern_properties);
+    }
+    for subschema in subschemas(object) {
+        to_dialect(subschema, draft);
+    }
+
+    if draft == Draft::Draft4 {
+        object.remove("readOnly");
+        object.remove("writeOnly");
+    }
+    if draft != Draft::Draft2020_12 {
+        object.remove("deprecated");
+    } else {
+        if let Some(definitions) = object.remove("definitions") {
+            object.insert("$defs".to_string(), definitions);
+        }
+        ref_siblings(object);
+    }
+    match object.remove("example") {
+        Some(example) if draft != Draft::Draft4 => {
+            object.insert("examples".to_string(), json!([example]));
+        }
+        _ => {}
+    }
+    if draft == Draft::Draft4 {
+        boolean_bounds(object);
+    } else {
+        numeric_bound(object, "exclusiveMinimum", "minimum");
+        numeric_bound(object, "exclusiveMaximum", "maximum");
+    }
+    if object.remove("nullable") == Some(Value::Bool(true)) {
+        nullable(schema);
+    }
+}
+
+/// Points references at the `reference` to the component schema they target, given the
+/// target relative to `#/components/schemas/`, and drops OpenAPI only keywords.
+fn standalone(schema: &mut Value, reference: &impl Fn(&str) -> String) {
+    let Value::Object(object) = schema else {
+        return;
+    };
+    for subschema in subschemas(object) {
+        standalone(subschema, reference);
+    }
+
+    if let Some(Value::String(location)) = object.get_mut("$ref") {
+        if let Some(target) = location.strip_prefix(SCHEMAS) {
+            *location = reference(target);
+        }
+    }
+    for key in ["discriminator", "xml", "externalDocs"] {
+        object.remove(key);
+    }
+}
+
+/// Schemas nested in the keywords of a schema object.
+fn subschemas(object: &mut Map<String, Value>) -> Vec<&mut Value> {
+    let mut subschemas = Vec::new();
+    for (key, value) in object.iter_mut() {
+        match (key.as_str(), value) {
+            (
+                "properties" | "patternProperties" | "definitions" | "$defs",
+                Value::Object(schemas),
+            ) => subschemas.extend(schemas.values_mut()),
+            ("allOf" | "oneOf" | "anyOf", Value::Array(schemas)) => {
+                subschemas.extend(schemas.iter_mut())
+            }
+            ("items" | "additionalProperties" | "not", schema) => subschemas.push(schema),
+            _ => {}
+        }
+    }
+    subschemas
+}
+
+/// Moves a lone `$ref` out of `allOf` next to the other keywords, which draft 2019-09 on
+/// allows, e.g. out of the wrapper references with a description are converted into.
+fn ref_siblings(object: &mut Map<String, Value>) {
+    if object.contains_key("$ref") {
+        return;
+    }
+    let reference = match object.get("allOf") {
+        Some(Value::Array(items)) if items.len() == 1 => match &items[0] {
+            Value::Object(item) if item.len() == 1 => item.get("$ref").cloned(),
+            _ => None,
+        },
+        _ => None,
+    };
+    if let Some(reference) = reference {
+        object.remove("allOf");
+        object.insert("$ref".to_string(), reference);
+    }
+}
+
+/// Rewrites the numeric `exclusiveMinimum` and `exclusiveMaximum` of a schema and its
+/// subschemas into the boolean flags of OpenAPI 3.0.
+pub(crate) fn boolean_exclusive_bounds(schema: &mut Value) {
+    let Value::Object(object) = schema else {
+        return;
+    };
+    for subschema in subschemas(object) {
+        boolean_exclusive_bounds(subschema);
+    }
+    boolean_bounds(object);
+}
+
+fn boolean_bounds(object: &mut Map<String, Value>) {
+    boolean_bound(object, "exclusiveMinimum", "minimum", |bound, exclusive| {
+        bound > exclusive
+    });
+    boolean_bound(object, "exclusiveMaximum", "maximum", |bound, exclusive| {
+        bound < exclusive
+    });
+}
+
+/// Turns a numeric exclusive bound into a flag on the inclusive `bound`, unless the
+/// inclusive bound is `stricter` anyway.
+fn boolean_bound(
+    object: &mut Map<String, Value>,
+    exclusive: &str,
+    bound: &str,
+    stricter: fn(f64, f64) -> bool,
+) {
+    let Some(value) = object.get(exclusive).and_then(Value::as_f64) else {
+        return;
+    };
+    object.remove(exclusive);
+    match object.get(bound).and_then(Value::as_f64) {
+        Some(inclusive) if stricter(inclusive, value) => {}
+        _ => {
+            object.insert(bound.to_string(), json!(value));
+            object.insert(exclusive.to_string(), json!(true));
+        }
+    }
+}
+
+/// Rewrites the draft 2020-12 keywords of an OpenAPI 3.1 schema and its subschemas to their
+/// OpenAPI 3.0 equivalent, the reverse of [`to_dialect`]. Keywords without one, like a
+/// `type` of several non-null types, are kept.
+pub(crate) fn from_draft_2020_12(schema: &mut Value) {
+    let Value::Object(object) = schema else {
+        return;
+    };
+    for subschema in subschemas(object) {
+        from_draft_2020_12(subschema);
+    }
+
+    if let Some(Value::Array(examples)) = object.get("examples") {
+        if let [example] = examples.as_slice() {
+            let example = example.clone();
+            object.remove("examples");
+            object.insert("example".to_string(), example);
+        }
+    }
+    if let Some(value) = object.remove("const") {
+        object.insert("enum".to_string(), json!([value]));
+    }
+    boolean_bounds(object);
+    if let Some(Value::Array(types)) = object.get("type") {
+        let null = json!("null");
+        let others: Vec<Value> = types.iter().filter(|t| **t != null).cloned().collect();
+        let nullable = others.len() < types.len();
+        if let [schema_type] = others.as_slice() {
+            object.insert("type".to_string(), schema_type.clone());
+            if nullable {
+                object.insert("nullable".to_string(), json!(true));
+            }
+        }
+    }
+    if object.contains_key("$ref") && object.len() > 1 {
+        // siblings of references are ignored by OpenAPI 3.0
+        let reference = object.remove("$ref").unwrap_or_default();
+        object.insert("allOf".to_string(), json!([{"$ref": reference}]));
+    }
+
+    let any_of = match object.get("anyOf") {
+        Some(Value::Array(schemas)) if object.len() == 1 && schemas.len() == 2 => schemas,
+        _ => return,
+    };
+    let null = json!({"type": "null"});
+    let Some(other) = any_of.iter().find(|schema| **schema != null) else {
+        return;
+    };
+    if !any_of.contains(&null) {
+        return;
+    }
+    *schema = match other {
+        Value::Object(other) if !other.contains_key("$ref") => {
+            let mut other = other.clone();
+            other.insert("nullable".to_string(), json!(true));
+            Value::Object(other)
+        }
+        other => json!({"allOf": [other], "nullable": true}),
+    };
+}
+
+/// Turns the boolean `exclusiveMinimum` of OpenAPI 3.0 into the numeric one of draft 6 on.
+fn numeric_bound(object: &mut Map<String, Value>, exclusive: &str, bound: &str) {
+    match object.get(exclusive) {
+        Some(Value::Bool(true)) => {
+            object.remove(exclusive);
+            if let Some(value) = object.remove(bound) {
+                object.insert(exclusive.to_string(), value);
+            }
+        }
+        Some(Value::Bool(false)) => {
+            object.remove(exclusive);
+        }
+        _ => {}
+    }
+}
+
+/// Allows `null` for a schema which had `nullable: true`.
+fn nullable(schema: &mut Value) {
+    if let Some(Value::String(schema_type)) = schema.get("type") {
+        let schema_type = json!([schema_type, "null"]);
+        schema["type"] = schema_type;
+        if let Some(Value::Array(values)) = schema.get_mut("enum") {
+            if !values.contains(&Value::Null) {
+                values.push(Value::Null);
+            }
+        }
+    } else {
+        *schema = json!({"anyOf": [schema.take(), {"type": "null"}]});
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::spec::Swagger;
+
+    #[test]
+    fn export_standalone_schemas() {
+        let swagger: Swagger = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "paths": {},
+            "definitions": {
+                "Pet": {
+                    "type": "object",
+                    "properties": {
+                        "age": {"type": "integer", "minimum": 1},
+                        "owner": {"$ref": "#/definitions/Owner"},
+                        "nickname": {"type": "string", "x-nullable": true, "example": "Rex"}
+                    }
+                },
+                "Owner": {"type": "object", "properties": {"name": {"type": "string"}}}
+            }
+        }))
+        .unwrap();
+
+        let schemas = json_schemas(&crate::convert(swagger).openapi, Draft::Draft7);
+
+        assert_eq!(
+            schemas.keys().collect::<Vec<_>>(),
+            vec!["Owner.json", "Pet.json"]
+        );
+        assert_eq!(
+            schemas["Pet.json"],
+            json!({
+                "$schema": "http://json-schema.org/draft-07/schema#",
+                "title": "Pet",
+                "type": "object",
+                "properties": {
+                    "age": {"type": "integer", "minimum": 1.0},
+                    "owner": {"$ref": "Owner.json"},
+                    "nickname": {"type": ["string", "null"], "examples": ["Rex"]}
+                }
+            })
+        );
+    }
+
+    #[cfg(feature = "schemars")]
+    #[test]
+    fn schemars_definitions_reference_each_other() {
+        let swagger: Swagger = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "paths": {},
+            "definitions": {
+                "Pet": {
+                    "type": "object",
+                    "properties": {"owner": {"$ref": "#/definitions/Owner"}}
+                },
+                "Owner": {"type": "object", "properties": {"name": {"type": "string"}}}
+            }
+        }))
+        .unwrap();
+
+        let definitions = schemars_definitions(&crate::convert(swagger).openapi);
+
+        assert_eq!(
+            serde_json::to_value(&definitions["Pet"]).unwrap(),
+            json!({
+                "type": "object",
+                "properties": {"owner": {"$ref": "#/definitions/Owner"}}
+            })
+        );
+        assert!(definitions.contains_key("Owner"));
+    }
+}
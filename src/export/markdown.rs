@@ -0,0 +1,270 @@
+use std::fmt::Write;
+
+use serde_json::Value;
+use utoipa::openapi::OpenApi;
+
+use super::{Document, Operation};
+
+/// Renders a converted document as Markdown documentation.
+///
+/// Operations get a section per first tag with tables of their parameters and responses,
+/// followed by a listing of the component schemas.
+pub fn markdown(openapi: &OpenApi) -> String {
+    let document = Document::new(openapi);
+    let mut out = String::new();
+
+    let title = document.str("/info/title").unwrap_or("API");
+    let _ = writeln!(out, "# {title}\n");
+    if let Some(version) = document.str("/info/version") {
+        let _ = writeln!(out, "Version: `{version}`\n");
+    }
+    if let Some(description) = document.str("/info/description") {
+        let _ = writeln!(out, "{description}\n");
+    }
+    if let Some(servers) = document.root.get("servers").and_then(Value::as_array) {
+        let urls: Vec<&str> = servers
+            .iter()
+            .filter_map(|s| s.get("url").and_then(Value::as_str))
+            .collect();
+        if !urls.is_empty() {
+            let _ = writeln!(out, "Servers: {}\n", code_list(&urls));
+        }
+    }
+
+    let operations = document.operations();
+    let mut sections: Vec<(Option<&str>, Vec<&Operation>)> = Vec::new();
+    for operation in &operations {
+        let tag = operation.tags().first().copied();
+        match sections.iter_mut().find(|(t, _)| *t == tag) {
+            Some((_, operations)) => operations.push(operation),
+            None => sections.push((tag, vec![operation])),
+        }
+    }
+    // untagged operations come last
+    sections.sort_by_key(|(tag, _)| tag.is_none());
+
+    for (tag, operations) in sections {
+        let _ = writeln!(out, "## {}\n", tag.unwrap_or("Other"));
+        for operation in operations {
+            write_operation(&mut out, &document, operation);
+        }
+    }
+
+    if let Some(schemas) = document
+        .root
+        .pointer("/components/schemas")
+        .and_then(Value::as_object)
+        .filter(|schemas| !schemas.is_empty())
+    {
+        let _ = writeln!(out, "## Schemas\n");
+        for (name, schema) in schemas {
+            write_schema(&mut out, name, schema);
+        }
+    }
+
+    out.truncate(out.trim_end().len());
+    out.push('\n');
+    out
+}
+
+fn write_operation(out: &mut String, document: &Document, operation: &Operation) {
+    let _ = writeln!(
+        out,
+        "### `{} {}`\n",
+        operation.method.to_uppercase(),
+        operation.path
+    );
+    if operation.operation.get("deprecated") == Some(&Value::Bool(true)) {
+        let _ = writeln!(out, "**Deprecated**\n");
+    }
+    if let Some(summary) = operation.str("summary") {
+        let _ = writeln!(out, "{summary}\n");
+    }
+    if let Some(description) = operation.str("description") {
+        let _ = writeln!(out, "{description}\n");
+    }
+
+    if !operation.parameters.is_empty() {
+        let _ = writeln!(out, "**Parameters**\n");
+        let _ = writeln!(out, "| Name | In | Type | Required | Description |");
+        let _ = writeln!(out, "| --- | --- | --- | --- | --- |");
+        for parameter in &operation.parameters {
+            let field = |key| {
+                parameter
+                    .get(key)
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+            };
+            let _ = writeln!(
+                out,
+                "| `{}` | {} | {} | {} | {} |",
+                field("name"),
+                field("in"),
+                parameter.get("schema").map(type_name).unwrap_or_default(),
+                yes_no(parameter.get("required")),
+                cell(field("description")),
+            );
+        }
+        out.push('\n');
+    }
+
+    if let Some((media_type, content)) = operation.request_body() {
+        let schema = content.get("schema").map(type_name).unwrap_or_default();
+        let _ = writeln!(out, "**Request body** (`{media_type}`): {schema}\n");
+    }
+
+    if let Some(responses) = operation
+        .operation
+        .get("responses")
+        .and_then(Value::as_object)
+        .filter(|responses| !responses.is_empty())
+    {
+        let _ = writeln!(out, "**Responses**\n");
+        let _ = writeln!(out, "| Status | Description | Schema |");
+        let _ = writeln!(out, "| --- | --- | --- |");
+        for (status, response) in responses {
+            let response = document.resolve(response);
+            let schema = response
+                .get("content")
+                .and_then(Value::as_object)
+                .and_then(|content| content.values().find_map(|c| c.get("schema")))
+                .map(type_name)
+                .unwrap_or_default();
+            let description = response
+                .get("description")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            let _ = writeln!(out, "| {status} | {} | {schema} |", cell(description));
+        }
+        out.push('\n');
+    }
+}
+
+fn write_schema(out: &mut String, name: &str, schema: &Value) {
+    let _ = writeln!(out, "<a id=\"{}\"></a>\n", anchor(name));
+    let _ = writeln!(out, "### {name}\n");
+    if let Some(description) = schema.get("description").and_then(Value::as_str) {
+        let _ = writeln!(out, "{description}\n");
+    }
+
+    let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+        let _ = writeln!(out, "Type: {}\n", type_name(schema));
+        return;
+    };
+    let required = schema.get("required").and_then(Value::as_array);
+    let _ = writeln!(out, "| Property | Type | Required | Description |");
+    let _ = writeln!(out, "| --- | --- | --- | --- |");
+    for (property, property_schema) in properties {
+        let is_required = required.is_some_and(|r| r.iter().any(|r| r == property.as_str()));
+        let description = property_schema
+            .get("description")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let _ = writeln!(
+            out,
+            "| `{property}` | {} | {} | {} |",
+            type_name(property_schema),
+            if is_required { "yes" } else { "no" },
+            cell(description),
+        );
+    }
+    out.push('\n');
+}
+
+/// Short type description of a schema, references link to the schema listing.
+fn type_name(schema: &Value) -> String {
+    if let Some(location) = schema.get("$ref").and_then(Value::as_str) {
+        let name = location.rsplit('/').next().unwrap_or(location);
+        return format!("[{name}](#{})", anchor(name));
+    }
+    if let Some(all_of) = schema.get("allOf").and_then(Value::as_array) {
+        return all_of.iter().map(type_name).collect::<Vec<_>>().join(" & ");
+    }
+
+    let schema_type = schema
+        .get("type")
+        .and_then(Value::as_str)
+        .unwrap_or("object");
+    let mut name = match (schema_type, schema.get("items")) {
+        ("array", Some(items)) => format!("array of {}", type_name(items)),
+        _ => schema_type.to_string(),
+    };
+    if let Some(format) = schema.get("format").and_then(Value::as_str) {
+        name.push_str(&format!(" ({format})"));
+    }
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        let values: Vec<String> = values.iter().map(Value::to_string).collect();
+        name.push_str(&format!(": {}", values.join(", ")));
+    }
+    name
+}
+
+fn code_list(items: &[&str]) -> String {
+    items
+        .iter()
+        .map(|item| format!("`{item}`"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn yes_no(value: Option<&Value>) -> &'static str {
+    if value == Some(&Value::Bool(true)) {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+/// Text which is safe to place in a table cell.
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', "<br>")
+}
+
+fn anchor(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric() || *c == '-' || *c == '_')
+        .collect::<String>()
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::spec::Swagger;
+
+    #[test]
+    fn render_markdown() {
+        let swagger: Swagger = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "tags": ["pets"],
+                        "summary": "List pets",
+                        "parameters": [
+                            {"name": "limit", "in": "query", "type": "integer", "description": "At most | this many"}
+                        ],
+                        "responses": {
+                            "200": {"description": "ok", "schema": {"type": "array", "items": {"$ref": "#/definitions/Pet"}}}
+                        }
+                    }
+                }
+            },
+            "definitions": {
+                "Pet": {"type": "object", "required": ["name"], "properties": {"name": {"type": "string"}}}
+            }
+        }))
+        .unwrap();
+
+        let markdown = markdown(&crate::convert(swagger).openapi);
+
+        assert!(markdown.starts_with("# Pets\n\nVersion: `1.0`\n"));
+        assert!(markdown.contains("## pets\n\n### `GET /pets`\n\nList pets\n"));
+        assert!(markdown.contains("| `limit` | query | integer | no | At most \\| this many |"));
+        assert!(markdown.contains("| 200 | ok | array of [Pet](#pet) |"));
+        assert!(markdown.contains("| `name` | string | yes |  |"));
+    }
+}
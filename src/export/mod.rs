@@ -0,0 +1,187 @@
+//! Exports of a converted OpenAPI document into formats of other API tools.
+//!
+//! Exporters work on the serialized document, so they see exactly what is written to an
+//! `openapi.json`, including vendor extensions.
+
+use serde_json::Value;
+use utoipa::openapi::{OpenApi, PathItemType};
+
+use crate::{
+    cycle::{self, RefStack},
+    mock,
+    spec::method_name,
+};
+
+mod html;
+mod insomnia;
+mod json_schema;
+mod markdown;
+mod postman;
+
+pub use html::*;
+pub use insomnia::*;
+pub use json_schema::*;
+pub(crate) use json_schema::{boolean_exclusive_bounds, from_draft_2020_12, to_dialect};
+pub use markdown::*;
+pub use postman::*;
+
+/// An operation of the exported document with its path and path level parameters merged in.
+pub(crate) struct Operation<'a> {
+    pub path: &'a str,
+    pub method: &'static str,
+    pub operation: &'a Value,
+    pub parameters: Vec<&'a Value>,
+}
+
+impl Operation<'_> {
+    pub fn str(&self, key: &str) -> Option<&str> {
+        self.operation.get(key).and_then(Value::as_str)
+    }
+
+    /// Summary or operation id of the operation, falling back to method and path.
+    pub fn name(&self) -> String {
+        self.str("summary")
+            .or_else(|| self.str("operationId"))
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("{} {}", self.method.to_uppercase(), self.path))
+    }
+
+    pub fn tags(&self) -> Vec<&str> {
+        self.operation
+            .get("tags")
+            .and_then(Value::as_array)
+            .map(|tags| tags.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn parameters_in<'p>(&'p self, location: &'p str) -> impl Iterator<Item = &'p Value> {
+        self.parameters
+            .iter()
+            .copied()
+            .filter(move |p| p.get("in").and_then(Value::as_str) == Some(location))
+    }
+
+    /// First media type of the request body with its media type object.
+    pub fn request_body(&self) -> Option<(&str, &Value)> {
+        let content = self
+            .operation
+            .pointer("/requestBody/content")?
+            .as_object()?;
+        content
+            .iter()
+            .find(|(media_type, _)| media_type.contains("json"))
+            .or_else(|| content.iter().next())
+            .map(|(media_type, value)| (media_type.as_str(), value))
+    }
+}
+
+/// A serialized OpenAPI document.
+pub(crate) struct Document {
+    root: Value,
+}
+
+impl Document {
+    pub fn new(openapi: &OpenApi) -> Self {
+        Self {
+            root: serde_json::to_value(openapi).unwrap_or_default(),
+        }
+    }
+
+    pub fn str(&self, pointer: &str) -> Option<&str> {
+        self.root.pointer(pointer).and_then(Value::as_str)
+    }
+
+    /// URL of the first server, `/` if there is none.
+    pub fn base_url(&self) -> &str {
+        self.str("/servers/0/url").unwrap_or("/")
+    }
+
+    /// Follows local `$ref`s until a value without one is reached, or one which is not
+    /// followed, see [`RefStack::follow`].
+    pub fn resolve<'a>(&'a self, mut value: &'a Value) -> &'a Value {
+        let mut refs = RefStack::new(cycle::max_depth());
+        while let Some(location) = value.get("$ref").and_then(Value::as_str) {
+            let Some(target) = location
+                .strip_prefix('#')
+                .and_then(|pointer| self.root.pointer(pointer))
+            else {
+                break;
+            };
+            if !refs.follow(location) {
+                break;
+            }
+            value = target;
+        }
+        value
+    }
+
+    /// All operations in path and method order.
+    pub fn operations(&self) -> Vec<Operation<'_>> {
+        let Some(paths) = self.root.get("paths").and_then(Value::as_object) else {
+            return Vec::new();
+        };
+
+        let methods = [
+            PathItemType::Get,
+            PathItemType::Put,
+            PathItemType::Post,
+            PathItemType::Delete,
+            PathItemType::Options,
+            PathItemType::Head,
+            PathItemType::Patch,
+            PathItemType::Trace,
+        ];
+        let mut operations = Vec::new();
+        for (path, path_item) in paths {
+            let path_item = self.resolve(path_item);
+            for method in methods.iter().map(method_name) {
+                let Some(operation) = path_item.get(method) else {
+                    continue;
+                };
+                operations.push(Operation {
+                    path,
+                    method,
+                    operation,
+                    parameters: self.parameters(path_item, operation),
+                });
+            }
+        }
+        operations
+    }
+
+    /// Parameters of an operation, operation level ones override those of the path item.
+    fn parameters<'a>(&'a self, path_item: &'a Value, operation: &'a Value) -> Vec<&'a Value> {
+        let list = |value: &'a Value| {
+            value
+                .get("parameters")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .map(move |p| self.resolve(p))
+        };
+        let key = |p: &Value| (p.get("name").cloned(), p.get("in").cloned());
+
+        let mut parameters: Vec<&Value> = list(operation).collect();
+        for parameter in list(path_item) {
+            if !parameters.iter().any(|p| key(p) == key(parameter)) {
+                parameters.push(parameter);
+            }
+        }
+        parameters
+    }
+
+    /// Example value of a schema, taken from its `example` or generated from its type.
+    pub fn example(&self, schema: &Value) -> Value {
+        mock::example(schema, &self.root)
+    }
+}
+
+/// Example value as written into a URL or header.
+pub(crate) fn plain(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        Value::Array(items) => items.iter().map(plain).collect::<Vec<_>>().join(","),
+        _ => value.to_string(),
+    }
+}
@@ -0,0 +1,234 @@
+use serde_json::{json, Map, Value};
+use utoipa::openapi::OpenApi;
+
+use super::{plain, Document, Operation};
+
+const POSTMAN_SCHEMA: &str = "https://schema.getpostman.com/json/collection/v2.1.0/collection.json";
+
+/// Exports a converted document as a Postman Collection v2.1.
+///
+/// Operations are grouped into a folder per first tag, untagged operations are placed at
+/// the top level. The server URL is exposed as the `baseUrl` collection variable. To export
+/// a Swagger document, [`convert`](crate::convert) it first.
+pub fn postman_collection(openapi: &OpenApi) -> Value {
+    let document = Document::new(openapi);
+
+    let mut info = json!({
+        "name": document.str("/info/title").unwrap_or_default(),
+        "schema": POSTMAN_SCHEMA,
+    });
+    if let Some(description) = document.str("/info/description") {
+        info["description"] = json!(description);
+    }
+
+    let mut items: Vec<Value> = Vec::new();
+    let mut folders: Map<String, Value> = Map::new();
+    for operation in document.operations() {
+        let item = postman_item(&document, &operation);
+        match operation.tags().first() {
+            Some(tag) => {
+                let folder = folders
+                    .entry(tag.to_string())
+                    .or_insert_with(|| json!({"name": tag, "item": []}));
+                folder["item"].as_array_mut().unwrap().push(item);
+            }
+            None => items.push(item),
+        }
+    }
+    let mut item: Vec<Value> = folders.into_values().collect();
+    item.extend(items);
+
+    json!({
+        "info": info,
+        "item": item,
+        "variable": [{"key": "baseUrl", "value": document.base_url().trim_end_matches('/')}],
+    })
+}
+
+fn postman_item(document: &Document, operation: &Operation) -> Value {
+    let header: Vec<Value> = operation
+        .parameters_in("header")
+        .map(|p| key_value(document, p))
+        .collect();
+    let mut request = json!({
+        "method": operation.method.to_uppercase(),
+        "header": header,
+        "url": postman_url(document, operation),
+    });
+    if let Some(description) = operation.str("description") {
+        request["description"] = json!(description);
+    }
+    if let Some(body) = postman_body(document, operation) {
+        request["body"] = body;
+    }
+
+    json!({
+        "name": operation.name(),
+        "request": request,
+    })
+}
+
+fn postman_url(document: &Document, operation: &Operation) -> Value {
+    // postman writes path variables as `:name`
+    let path: Vec<String> = operation
+        .path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(
+            |segment| match segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+                Some(name) => format!(":{name}"),
+                None => segment.to_string(),
+            },
+        )
+        .collect();
+    let query: Vec<Value> = operation
+        .parameters_in("query")
+        .map(|p| {
+            let mut query = key_value(document, p);
+            query["disabled"] = json!(p.get("required") != Some(&Value::Bool(true)));
+            query
+        })
+        .collect();
+    let variable: Vec<Value> = operation
+        .parameters_in("path")
+        .map(|p| key_value(document, p))
+        .collect();
+
+    let mut raw = format!("{{{{baseUrl}}}}/{}", path.join("/"));
+    let enabled: Vec<String> = query
+        .iter()
+        .filter(|q| q["disabled"] == json!(false))
+        .map(|q| {
+            format!(
+                "{}={}",
+                q["key"].as_str().unwrap_or_default(),
+                q["value"].as_str().unwrap_or_default()
+            )
+        })
+        .collect();
+    if !enabled.is_empty() {
+        raw.push('?');
+        raw.push_str(&enabled.join("&"));
+    }
+
+    json!({
+        "raw": raw,
+        "host": ["{{baseUrl}}"],
+        "path": path,
+        "query": query,
+        "variable": variable,
+    })
+}
+
+fn postman_body(document: &Document, operation: &Operation) -> Option<Value> {
+    let (media_type, content) = operation.request_body()?;
+    let example = content
+        .get("example")
+        .cloned()
+        .or_else(|| content.get("schema").map(|schema| document.example(schema)))
+        .unwrap_or(Value::Null);
+
+    let language = if media_type.contains("json") {
+        "json"
+    } else {
+        "text"
+    };
+    let body = match media_type {
+        "application/x-www-form-urlencoded" | "multipart/form-data" => {
+            let mode = if media_type == "multipart/form-data" {
+                "formdata"
+            } else {
+                "urlencoded"
+            };
+            let fields: Vec<Value> = example
+                .as_object()
+                .into_iter()
+                .flatten()
+                .map(|(key, value)| json!({"key": key, "value": plain(value)}))
+                .collect();
+            json!({"mode": mode, mode: fields})
+        }
+        _ => json!({
+            "mode": "raw",
+            "raw": serde_json::to_string_pretty(&example).unwrap_or_default(),
+            "options": {"raw": {"language": language}},
+        }),
+    };
+    Some(body)
+}
+
+/// Postman key value pair of a parameter with its example as value.
+fn key_value(document: &Document, parameter: &Value) -> Value {
+    let value = parameter
+        .get("example")
+        .cloned()
+        .or_else(|| {
+            parameter
+                .get("schema")
+                .map(|schema| document.example(schema))
+        })
+        .map(|example| plain(&example))
+        .unwrap_or_default();
+    let mut key_value = json!({
+        "key": parameter.get("name").and_then(Value::as_str).unwrap_or_default(),
+        "value": value,
+    });
+    if let Some(description) = parameter.get("description") {
+        key_value["description"] = description.clone();
+    }
+    key_value
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::spec::Swagger;
+
+    #[test]
+    fn export_postman_collection() {
+        let swagger: Swagger = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "host": "example.com",
+            "basePath": "/v1",
+            "schemes": ["https"],
+            "paths": {
+                "/pets/{id}": {
+                    "put": {
+                        "tags": ["pets"],
+                        "operationId": "updatePet",
+                        "parameters": [
+                            {"name": "id", "in": "path", "required": true, "type": "integer"},
+                            {"name": "dryRun", "in": "query", "required": true, "type": "boolean"},
+                            {"name": "body", "in": "body", "schema": {"$ref": "#/definitions/Pet"}}
+                        ],
+                        "responses": {"200": {"description": "ok"}}
+                    }
+                }
+            },
+            "definitions": {
+                "Pet": {"type": "object", "properties": {"name": {"type": "string", "example": "Rex"}}}
+            }
+        }))
+        .unwrap();
+
+        let collection = postman_collection(&crate::convert(swagger).openapi);
+
+        assert_eq!(collection["variable"][0]["value"], "https://example.com/v1");
+        let item = &collection["item"][0]["item"][0];
+        assert_eq!(collection["item"][0]["name"], "pets");
+        assert_eq!(item["name"], "updatePet");
+        assert_eq!(item["request"]["method"], "PUT");
+        assert_eq!(
+            item["request"]["url"]["raw"],
+            "{{baseUrl}}/pets/:id?dryRun=true"
+        );
+        assert_eq!(item["request"]["url"]["variable"][0]["value"], "1");
+        assert_eq!(
+            item["request"]["body"]["raw"],
+            serde_json::to_string_pretty(&json!({"name": "Rex"})).unwrap()
+        );
+    }
+}
@@ -0,0 +1,141 @@
+//! C interface for embedding the converter, see `include/swagger_convert.h`.
+//!
+//! The library is only built as an `rlib` by default, the C artifacts are built on demand:
+//!
+//! ```text
+//! cargo rustc --release --lib --features ffi --crate-type cdylib
+//! cargo rustc --release --lib --features ffi --crate-type staticlib
+//! ```
+
+use std::{
+    cell::RefCell,
+    ffi::{c_char, CStr, CString},
+    panic, ptr,
+};
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: String) {
+    // interior NUL bytes would truncate the message on the C side anyway
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(message));
+}
+
+/// Converts a NUL-terminated Swagger 2.0 JSON document into an OpenAPI 3.0 JSON document.
+///
+/// Returns a newly allocated string which must be released with [`swagger_convert_free`],
+/// or null if the conversion failed. In the latter case [`swagger_convert_last_error`]
+/// describes the failure.
+///
+/// # Safety
+///
+/// `swagger` must either be null or point to a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn swagger_convert_json(swagger: *const c_char) -> *mut c_char {
+    LAST_ERROR.with(|e| e.borrow_mut().take());
+
+    if swagger.is_null() {
+        set_last_error("swagger document is null".to_string());
+        return ptr::null_mut();
+    }
+    let swagger = match CStr::from_ptr(swagger).to_str() {
+        Ok(swagger) => swagger,
+        Err(err) => {
+            set_last_error(format!("swagger document is not valid UTF-8: {err}"));
+            return ptr::null_mut();
+        }
+    };
+
+    // never unwind across the FFI boundary
+    match panic::catch_unwind(|| crate::convert_json(swagger)) {
+        Ok(Ok(openapi)) => match CString::new(openapi) {
+            Ok(openapi) => openapi.into_raw(),
+            Err(err) => {
+                set_last_error(format!("openapi document contains a NUL byte: {err}"));
+                ptr::null_mut()
+            }
+        },
+        Ok(Err(err)) => {
+            set_last_error(err.to_string());
+            ptr::null_mut()
+        }
+        Err(_) => {
+            set_last_error("conversion panicked".to_string());
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Returns the error of the last failed [`swagger_convert_json`] call on the current thread,
+/// or null if the last call succeeded.
+///
+/// The returned string is owned by the library and stays valid until the next call to
+/// [`swagger_convert_json`] on the same thread.
+#[no_mangle]
+pub extern "C" fn swagger_convert_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| {
+        e.borrow()
+            .as_ref()
+            .map(|message| message.as_ptr())
+            .unwrap_or(ptr::null())
+    })
+}
+
+/// Releases a string returned by [`swagger_convert_json`].
+///
+/// # Safety
+///
+/// `openapi` must either be null or a pointer returned by [`swagger_convert_json`]
+/// which has not been released yet.
+#[no_mangle]
+pub unsafe extern "C" fn swagger_convert_free(openapi: *mut c_char) {
+    if !openapi.is_null() {
+        drop(CString::from_raw(openapi));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convert_and_free() {
+        let swagger = CString::new(
+            r#"{
+                "swagger": "2.0",
+                "info": {"title": "test", "version": "1.0"},
+                "x-tagGroups": [{"name": "Pets", "tags": []}],
+                "paths": {}
+            }"#,
+        )
+        .unwrap();
+
+        unsafe {
+            let openapi = swagger_convert_json(swagger.as_ptr());
+            assert!(!openapi.is_null());
+            assert!(swagger_convert_last_error().is_null());
+
+            let value: serde_json::Value =
+                serde_json::from_str(CStr::from_ptr(openapi).to_str().unwrap()).unwrap();
+            assert_eq!(value["info"]["title"], "test");
+            assert_eq!(value["x-tagGroups"][0]["name"], "Pets");
+
+            swagger_convert_free(openapi);
+        }
+    }
+
+    #[test]
+    fn invalid_document_sets_last_error() {
+        let swagger = CString::new("{}").unwrap();
+
+        unsafe {
+            let openapi = swagger_convert_json(swagger.as_ptr());
+            assert!(openapi.is_null());
+
+            let error = CStr::from_ptr(swagger_convert_last_error());
+            assert!(error.to_str().unwrap().starts_with("failed to parse"));
+        }
+    }
+}
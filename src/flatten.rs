@@ -0,0 +1,242 @@
+//! Flattening of `allOf` compositions into plain schemas, for consumers whose generators
+//! handle composition poorly.
+//!
+//! Every schema with `allOf` becomes a single schema holding the merged properties,
+//! `required` lists and constraints of itself and its members, with referenced members
+//! resolved against the document. The referenced schemas stay, other references may still
+//! point at them. Members contradicting each other, e.g. by their `type`, are reported and
+//! the first of them wins. Members which cannot be resolved are kept in an `allOf`.
+
+use serde_json::{Map, Value};
+
+use crate::{
+    cycle::{self, RefStack},
+    diagnostics::{self, Diagnostic},
+    walk,
+};
+
+/// Keywords documenting a schema. The schema itself or its first member declaring one wins.
+const ANNOTATIONS: [&str; 10] = [
+    "title",
+    "description",
+    "example",
+    "default",
+    "deprecated",
+    "readOnly",
+    "writeOnly",
+    "externalDocs",
+    "xml",
+    "discriminator",
+];
+
+const LOWER_BOUNDS: [&str; 4] = ["minimum", "minLength", "minItems", "minProperties"];
+const UPPER_BOUNDS: [&str; 4] = ["maximum", "maxLength", "maxItems", "maxProperties"];
+
+/// Flattens every `allOf` within the serialized OpenAPI `document`, see the [module
+/// documentation](self).
+///
+/// Returns the conflicts found, at the pointer of the flattened schema.
+pub fn flatten(document: &mut Value) -> Vec<Diagnostic> {
+    let root = document.clone();
+    let ((), diagnostics) = diagnostics::collect(|| {
+        let mut refs = RefStack::new(cycle::max_depth());
+        flatten_within(document, &root, &mut refs, &mut String::new());
+    });
+    diagnostics
+}
+
+/// Flattens every `allOf` within `value`, the innermost first.
+fn flatten_within(value: &mut Value, root: &Value, refs: &mut RefStack, pointer: &mut String) {
+    walk::objects_mut(value, pointer, &mut |_, _| {}, &mut |object, pointer| {
+        if object.contains_key("allOf") {
+            diagnostics::at(pointer, || {
+                *object = flattened(std::mem::take(object), root, refs);
+            });
+        }
+    });
+}
+
+/// `schema` with the members of its `allOf` merged into it. Nested compositions of the
+/// members are expected to be flattened already.
+fn flattened(
+    mut schema: Map<String, Value>,
+    root: &Value,
+    refs: &mut RefStack,
+) -> Map<String, Value> {
+    let Some(Value::Array(members)) = schema.remove("allOf") else {
+        return schema;
+    };
+    let mut unresolved = Vec::new();
+    for member in members {
+        match resolved(member, root, refs) {
+            Ok(member) => merge(&mut schema, member),
+            Err(member) => unresolved.push(member),
+        }
+    }
+    if !unresolved.is_empty() {
+        schema.insert("allOf".to_string(), Value::Array(unresolved));
+    }
+    schema
+}
+
+/// The flattened schema a member of `allOf` stands for, or the member if it is no schema or
+/// a reference which cannot be followed.
+fn resolved(member: Value, root: &Value, refs: &mut RefStack) -> Result<Map<String, Value>, Value> {
+    let Value::Object(member) = member else {
+        return Err(member);
+    };
+    let Some(Value::String(location)) = member.get("$ref") else {
+        return Ok(member);
+    };
+    let target = location
+        .strip_prefix('#')
+        .and_then(|pointer| root.pointer(pointer));
+    let Some(Value::Object(target)) = target else {
+        return Err(Value::Object(member));
+    };
+    if refs.contains(location) {
+        diagnostics::warn(format!("cannot flatten `{location}`, it references itself"));
+        return Err(Value::Object(member));
+    }
+    if !refs.follow(location) {
+        return Err(Value::Object(member));
+    }
+
+    let truncated = refs.truncated();
+    let mut target = Value::Object(target.clone());
+    // conflicts within the target are reported where it is declared
+    diagnostics::collect(|| flatten_within(&mut target, root, refs, &mut String::new()));
+    refs.leave();
+    if refs.depth() == 0 && !truncated && refs.truncated() {
+        // the warning of the reference not followed went with the conflicts
+        diagnostics::warn(format!(
+            "did not flatten every reference within `{location}`, references are nested \
+             deeper than {}",
+            cycle::max_depth()
+        ));
+    }
+    match target {
+        Value::Object(target) => Ok(target),
+        _ => Err(Value::Object(member)),
+    }
+}
+
+/// Merges the keywords of `member` into `schema`.
+fn merge(schema: &mut Map<String, Value>, member: Map<String, Value>) {
+    for (key, value) in member {
+        let Some(existing) = schema.get_mut(&key) else {
+            schema.insert(key, value);
+            continue;
+        };
+        match (key.as_str(), existing, value) {
+            (_, existing, value) if *existing == value => {}
+            (key, _, _) if ANNOTATIONS.contains(&key) || key.starts_with("x-") => {}
+            ("properties", Value::Object(properties), Value::Object(members)) => {
+                for (name, property) in members {
+                    match properties.get(&name) {
+                        Some(existing) if *existing != property => {
+                            diagnostics::warn(format!(
+                                "property `{name}` is declared differently by members of \
+                                 `allOf`, keeping the first"
+                            ));
+                        }
+                        Some(_) => {}
+                        None => {
+                            properties.insert(name, property);
+                        }
+                    }
+                }
+            }
+            ("required", Value::Array(required), Value::Array(names)) => {
+                for name in names {
+                    if !required.contains(&name) {
+                        required.push(name);
+                    }
+                }
+            }
+            (key, existing, value) if LOWER_BOUNDS.contains(&key) => {
+                if value.as_f64() > existing.as_f64() {
+                    *existing = value;
+                }
+            }
+            (key, existing, value) if UPPER_BOUNDS.contains(&key) => {
+                if value.as_f64() < existing.as_f64() {
+                    *existing = value;
+                }
+            }
+            ("type", existing, value) => diagnostics::error(format!(
+                "contradictory types {existing} and {value} in `allOf`, keeping the first"
+            )),
+            (key, _, _) => {
+                diagnostics::warn(format!("conflicting `{key}` in `allOf`, keeping the first"))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn flatten_all_of() {
+        let mut document = json!({
+            "openapi": "3.0.3",
+            "paths": {},
+            "components": {
+                "schemas": {
+                    "Named": {
+                        "type": "object",
+                        "required": ["name"],
+                        "properties": {"name": {"type": "string", "maxLength": 80}}
+                    },
+                    "Pet": {
+                        "description": "A pet",
+                        "allOf": [
+                            {"$ref": "#/components/schemas/Named"},
+                            {
+                                "type": "object",
+                                "required": ["age"],
+                                "properties": {"age": {"type": "integer", "minimum": 0}}
+                            }
+                        ]
+                    },
+                    "Broken": {
+                        "allOf": [{"type": "string"}, {"type": "integer"}]
+                    }
+                }
+            }
+        });
+
+        let diagnostics = flatten(&mut document);
+
+        assert_eq!(
+            document["components"]["schemas"]["Pet"],
+            json!({
+                "description": "A pet",
+                "type": "object",
+                "required": ["name", "age"],
+                "properties": {
+                    "name": {"type": "string", "maxLength": 80},
+                    "age": {"type": "integer", "minimum": 0}
+                }
+            })
+        );
+        assert_eq!(
+            document["components"]["schemas"]["Broken"],
+            json!({"type": "string"})
+        );
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|d| (d.pointer.as_str(), d.message.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(
+                "/components/schemas/Broken",
+                "contradictory types \"string\" and \"integer\" in `allOf`, keeping the first"
+            )]
+        );
+    }
+}
@@ -0,0 +1,202 @@
+//! Normalization of example and default values to the `format` of their schema, see
+//! [`ConvertOptions::normalize_formats`](crate::ConvertOptions::normalize_formats).
+//!
+//! `date-time` values are rewritten to RFC 3339, e.g. `2024-05-01 12:00:00` becomes
+//! `2024-05-01T12:00:00Z`, and UUID examples are lowercased. Values which still do not match
+//! their format afterwards are reported.
+
+use serde_json::{Map, Value};
+
+use crate::{diagnostics, walk};
+
+/// Members holding values of the schema or parameter they are part of.
+const VALUES: [&str; 3] = ["example", "x-example", "default"];
+
+/// Normalizes the example and default values of every schema and parameter of `document` to
+/// their `format`, reporting the values which do not match it.
+pub(crate) fn normalize(document: &mut Value) {
+    walk::enter_objects(document, &mut String::new(), &mut |object, pointer| {
+        if let Some(Value::String(format)) = object.get("format") {
+            let format = format.clone();
+            normalize_values(object, &format, pointer);
+        }
+    });
+}
+
+fn normalize_values(object: &mut Map<String, Value>, format: &str, pointer: &str) {
+    for key in VALUES {
+        let Some(value) = object.get_mut(key) else {
+            continue;
+        };
+        let normalized = match (format, &*value) {
+            ("date-time", Value::String(s)) => date_time(s),
+            ("uuid", Value::String(s)) if key != "default" => Some(s.to_ascii_lowercase()),
+            _ => None,
+        };
+        if let Some(normalized) = normalized {
+            *value = Value::String(normalized);
+        }
+        if !matches_format(value, format) {
+            diagnostics::at(&format!("{pointer}/{key}"), || {
+                diagnostics::warn(format!("{value} does not match format `{format}`"))
+            });
+        }
+    }
+}
+
+/// `value` as RFC 3339 date-time. Accepts a space or lowercase `t` as separator, offsets
+/// without colon and dates or times without offset, which are taken as UTC.
+fn date_time(value: &str) -> Option<String> {
+    let value = value.trim();
+    if is_date(value) {
+        return Some(format!("{value}T00:00:00Z"));
+    }
+    let date = value.get(..10)?;
+    let time = value.get(10..)?.strip_prefix(['T', 't', ' '])?;
+    if !is_date(date) {
+        return None;
+    }
+
+    let (time, offset) = match time.find(['Z', 'z', '+', '-']) {
+        Some(i) => time.split_at(i),
+        None => (time, ""),
+    };
+    let offset = match offset {
+        "" | "Z" | "z" => "Z".to_string(),
+        _ => {
+            let (sign, digits) = offset.split_at(1);
+            let digits = digits.replace(':', "");
+            match digits.len() {
+                2 if is_digits(&digits) => format!("{sign}{digits}:00"),
+                4 if is_digits(&digits) => format!("{sign}{}:{}", &digits[..2], &digits[2..]),
+                _ => return None,
+            }
+        }
+    };
+    is_time(time).then(|| format!("{date}T{time}{offset}"))
+}
+
+pub(crate) fn matches_format(value: &Value, format: &str) -> bool {
+    match (format, value) {
+        ("date-time", Value::String(s)) => date_time(s).is_some_and(|d| d == *s),
+        ("date", Value::String(s)) => is_date(s),
+        ("uuid", Value::String(s)) => is_uuid(s),
+        ("email", Value::String(s)) => s
+            .split_once('@')
+            .is_some_and(|(local, domain)| !local.is_empty() && domain.contains('.')),
+        ("ipv4", Value::String(s)) => s.parse::<std::net::Ipv4Addr>().is_ok(),
+        ("ipv6", Value::String(s)) => s.parse::<std::net::Ipv6Addr>().is_ok(),
+        ("int32", Value::Number(n)) => n.as_i64().is_some_and(|n| i32::try_from(n).is_ok()),
+        ("int64", Value::Number(n)) => n.as_i64().is_some(),
+        ("date-time" | "date" | "uuid" | "email" | "ipv4" | "ipv6", _) => false,
+        ("int32" | "int64", _) => false,
+        // formats without a fixed syntax, e.g. `password`, or unknown ones
+        _ => true,
+    }
+}
+
+fn is_digits(value: &str) -> bool {
+    !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Whether `value` has the form of `full-date` of RFC 3339.
+fn is_date(value: &str) -> bool {
+    let parts: Vec<&str> = value.split('-').collect();
+    match parts[..] {
+        [year, month, day] => {
+            year.len() == 4 && is_digits(year) && in_range(month, 1, 12) && in_range(day, 1, 31)
+        }
+        _ => false,
+    }
+}
+
+/// Whether `value` has the form of `partial-time` of RFC 3339.
+fn is_time(value: &str) -> bool {
+    let (time, fraction) = value.split_once('.').unwrap_or((value, "0"));
+    let parts: Vec<&str> = time.split(':').collect();
+    match parts[..] {
+        [hour, minute, second] => {
+            in_range(hour, 0, 23)
+                && in_range(minute, 0, 59)
+                && in_range(second, 0, 60)
+                && is_digits(fraction)
+        }
+        _ => false,
+    }
+}
+
+fn in_range(value: &str, min: u32, max: u32) -> bool {
+    value.len() == 2 && is_digits(value) && value.parse().is_ok_and(|v| (min..=max).contains(&v))
+}
+
+fn is_uuid(value: &str) -> bool {
+    let groups: Vec<&str> = value.split('-').collect();
+    groups.iter().map(|g| g.len()).eq([8, 4, 4, 4, 12])
+        && groups
+            .iter()
+            .all(|g| g.bytes().all(|b| b.is_ascii_hexdigit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::ConvertOptions;
+
+    #[test]
+    fn normalize_formats() {
+        let swagger = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "paths": {},
+            "definitions": {
+                "Pet": {
+                    "type": "object",
+                    "properties": {
+                        "born": {
+                            "type": "string",
+                            "format": "date-time",
+                            "example": "2024-05-01 12:30:00+0200",
+                            "default": "2024-05-01"
+                        },
+                        "id": {
+                            "type": "string",
+                            "format": "uuid",
+                            "example": "3F2504E0-4F89-11D3-9A0C-0305E82C3301"
+                        },
+                        "owner": {"type": "string", "format": "email", "example": "nobody"}
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        let options = ConvertOptions {
+            normalize_formats: true,
+            ..Default::default()
+        };
+
+        let conversion = crate::convert_with(swagger, &options);
+
+        let document = conversion.to_value();
+        let properties = &document["components"]["schemas"]["Pet"]["properties"];
+        assert_eq!(properties["born"]["example"], "2024-05-01T12:30:00+02:00");
+        assert_eq!(properties["born"]["default"], "2024-05-01T00:00:00Z");
+        assert_eq!(
+            properties["id"]["example"],
+            "3f2504e0-4f89-11d3-9a0c-0305e82c3301"
+        );
+        let mismatches: Vec<_> = conversion
+            .diagnostics
+            .iter()
+            .filter(|d| d.message.contains("does not match format"))
+            .map(|d| (d.pointer.as_str(), d.message.as_str()))
+            .collect();
+        assert_eq!(
+            mismatches,
+            vec![(
+                "/definitions/Pet/properties/owner/example",
+                "\"nobody\" does not match format `email`"
+            )]
+        );
+    }
+}
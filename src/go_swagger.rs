@@ -0,0 +1,93 @@
+//! Compatibility with the documents go-swagger generates, see
+//! [`ConvertOptions::go_swagger`](crate::ConvertOptions::go_swagger).
+//!
+//! go-swagger marks nullable schemas with `x-isnullable` rather than `x-nullable`, and
+//! properties serialized even when empty with `x-omitempty: false`. Such properties are
+//! always present, so they are required. Other extensions, like `x-go-name`, are kept.
+
+use serde_json::{json, Map, Value};
+
+use crate::walk;
+
+/// Maps the go-swagger extensions of `document` onto the keywords and extensions they mean.
+pub(crate) fn adapt(document: &mut Value) {
+    walk::enter_objects(document, &mut String::new(), &mut |object, _| {
+        nullable(object);
+        always_present(object);
+    });
+}
+
+/// Replaces `x-isnullable` with `x-nullable`, unless both are given.
+fn nullable(object: &mut Map<String, Value>) {
+    let Some(nullable) = object.remove("x-isnullable") else {
+        return;
+    };
+    object.entry("x-nullable").or_insert(nullable);
+}
+
+/// Requires the properties with `x-omitempty: false`.
+fn always_present(object: &mut Map<String, Value>) {
+    let Some(Value::Object(properties)) = object.get("properties") else {
+        return;
+    };
+    let present: Vec<String> = properties
+        .iter()
+        .filter(|(_, property)| property.get("x-omitempty") == Some(&Value::Bool(false)))
+        .map(|(name, _)| name.clone())
+        .collect();
+    if present.is_empty() {
+        return;
+    }
+    let required = object.entry("required").or_insert_with(|| json!([]));
+    let Value::Array(required) = required else {
+        return;
+    };
+    for name in present {
+        if !required.iter().any(|r| *r == *name) {
+            required.push(Value::String(name));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::ConvertOptions;
+
+    #[test]
+    fn adapt_go_swagger() {
+        let swagger = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "paths": {},
+            "definitions": {
+                "Pet": {
+                    "type": "object",
+                    "required": ["name"],
+                    "properties": {
+                        "name": {"type": "string", "x-go-name": "Name"},
+                        "age": {"type": "integer", "x-omitempty": false},
+                        "owner": {"type": "string", "x-isnullable": true},
+                        "weight": {"type": "number", "minimum": 0, "exclusiveMinimum": true}
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        let options = ConvertOptions {
+            go_swagger: true,
+            ..Default::default()
+        };
+
+        let conversion = crate::convert_with(swagger, &options);
+
+        assert!(conversion.diagnostics.is_empty());
+        let pet = &conversion.to_value()["components"]["schemas"]["Pet"];
+        assert_eq!(pet["required"], json!(["name", "age"]));
+        assert_eq!(pet["properties"]["name"]["x-go-name"], "Name");
+        assert_eq!(pet["properties"]["owner"]["nullable"], json!(true));
+        assert!(pet["properties"]["owner"].get("x-isnullable").is_none());
+        assert_eq!(pet["properties"]["weight"]["exclusiveMinimum"], json!(true));
+    }
+}
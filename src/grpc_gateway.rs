@@ -0,0 +1,237 @@
+//! Compatibility with the documents protoc-gen-swagger and grpc-gateway generate, see
+//! [`ConvertOptions::grpc_gateway`](crate::ConvertOptions::grpc_gateway).
+//!
+//! `protobufAny` becomes the JSON mapping of `google.protobuf.Any`, an object with a `@type`
+//! next to the fields of the message it holds. The `default` error response every operation
+//! repeats becomes a shared response, and the inline wrappers of streamed results become
+//! definitions named after the streamed message.
+
+use serde_json::{json, Map, Value};
+
+const METHODS: [&str; 7] = ["get", "put", "post", "delete", "options", "head", "patch"];
+
+/// Definition of `google.protobuf.Any`.
+const ANY: &str = "protobufAny";
+/// Definitions of the error grpc-gateway responds with, by generator version.
+const STATUS: [&str; 2] = ["runtimeError", "rpcStatus"];
+/// Title prefix of the wrappers of streamed results.
+const STREAM_RESULT: &str = "Stream result of ";
+
+/// Rewrites the grpc-gateway quirks of `document` into their idiomatic form.
+pub(crate) fn adapt(document: &mut Value) {
+    let mut responses = document["responses"]
+        .as_object()
+        .cloned()
+        .unwrap_or_default();
+    let mut definitions = document["definitions"]
+        .as_object()
+        .cloned()
+        .unwrap_or_default();
+    if let Some(any) = definitions.get_mut(ANY) {
+        any_schema(any);
+    }
+
+    let paths = document.get_mut("paths").and_then(Value::as_object_mut);
+    for path_item in paths.into_iter().flat_map(|paths| paths.values_mut()) {
+        let Value::Object(path_item) = path_item else {
+            continue;
+        };
+        for (method, operation) in path_item.iter_mut() {
+            if !METHODS.contains(&method.as_str()) {
+                continue;
+            }
+            let Some(Value::Object(operation_responses)) = operation.get_mut("responses") else {
+                continue;
+            };
+            for (status, response) in operation_responses.iter_mut() {
+                if status == "default" {
+                    status_response(response, &mut responses);
+                } else if status.starts_with('2') {
+                    stream_result(response, &mut definitions);
+                }
+            }
+        }
+    }
+
+    if !responses.is_empty() {
+        document["responses"] = Value::Object(responses);
+    }
+    if !definitions.is_empty() {
+        document["definitions"] = Value::Object(definitions);
+    }
+}
+
+/// Replaces the generated `protobufAny`, which describes the binary encoding of `Any`, with
+/// its JSON mapping.
+fn any_schema(any: &mut Value) {
+    let description = any.get("description").cloned();
+    *any = json!({
+        "type": "object",
+        "properties": {
+            "@type": {
+                "type": "string",
+                "description": "URL identifying the type of the message, e.g. \
+                                `type.googleapis.com/google.protobuf.Duration`."
+            }
+        },
+        "required": ["@type"],
+        "additionalProperties": true
+    });
+    if let Some(description) = description {
+        any["description"] = description;
+    }
+}
+
+/// Replaces an error response with a reference to the shared response named after its
+/// schema, unless a different shared response has that name.
+fn status_response(response: &mut Value, shared: &mut Map<String, Value>) {
+    let name = match response.pointer("/schema/$ref").and_then(Value::as_str) {
+        Some(location) => match location.strip_prefix("#/definitions/") {
+            Some(name) if STATUS.contains(&name) => name.to_string(),
+            _ => return,
+        },
+        None => return,
+    };
+    match shared.get(&name) {
+        Some(existing) if *existing != *response => return,
+        Some(_) => {}
+        None => {
+            shared.insert(name.clone(), response.clone());
+        }
+    }
+    *response = json!({"$ref": format!("#/responses/{name}")});
+}
+
+/// Replaces the inline wrapper of a streamed result, an object with the `result` and the
+/// `error` of each message, with a reference to the definition `<message>StreamResult`.
+fn stream_result(response: &mut Value, definitions: &mut Map<String, Value>) {
+    let Some(schema) = response.get_mut("schema") else {
+        return;
+    };
+    let message = match schema["title"].as_str() {
+        Some(title) => match title.strip_prefix(STREAM_RESULT) {
+            Some(message) => message.to_string(),
+            None => return,
+        },
+        None => return,
+    };
+    if schema.pointer("/properties/result").is_none() {
+        return;
+    }
+    let name = format!("{message}StreamResult");
+    let mut wrapper = schema.take();
+    if let Value::Object(wrapper) = &mut wrapper {
+        wrapper.remove("title");
+        wrapper.entry("description").or_insert_with(|| {
+            json!(format!(
+                "A `{message}` or an error, each streamed message is sent as a JSON object of \
+                 its own."
+            ))
+        });
+    }
+    match definitions.get(&name) {
+        Some(existing) if *existing != wrapper => {
+            *schema = wrapper;
+            return;
+        }
+        Some(_) => {}
+        None => {
+            definitions.insert(name.clone(), wrapper);
+        }
+    }
+    *schema = json!({"$ref": format!("#/definitions/{name}")});
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::ConvertOptions;
+
+    #[test]
+    fn adapt_grpc_gateway() {
+        let error = json!({
+            "description": "An unexpected error response.",
+            "schema": {"$ref": "#/definitions/rpcStatus"}
+        });
+        let swagger = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {"title": "pets.proto", "version": "version not set"},
+            "paths": {
+                "/v1/pets": {
+                    "get": {
+                        "operationId": "Pets_WatchPets",
+                        "responses": {
+                            "200": {
+                                "description": "A successful response.(streaming responses)",
+                                "schema": {
+                                    "type": "object",
+                                    "properties": {
+                                        "result": {"$ref": "#/definitions/v1Pet"},
+                                        "error": {"$ref": "#/definitions/rpcStatus"}
+                                    },
+                                    "title": "Stream result of v1Pet"
+                                }
+                            },
+                            "default": error
+                        }
+                    },
+                    "post": {
+                        "operationId": "Pets_AddPet",
+                        "responses": {
+                            "200": {
+                                "description": "A successful response.",
+                                "schema": {"$ref": "#/definitions/v1Pet"}
+                            },
+                            "default": error
+                        }
+                    }
+                }
+            },
+            "definitions": {
+                "protobufAny": {
+                    "type": "object",
+                    "properties": {"@type": {"type": "string"}},
+                    "additionalProperties": {}
+                },
+                "rpcStatus": {
+                    "type": "object",
+                    "properties": {
+                        "code": {"type": "integer", "format": "int32"},
+                        "message": {"type": "string"},
+                        "details": {
+                            "type": "array",
+                            "items": {"$ref": "#/definitions/protobufAny"}
+                        }
+                    }
+                },
+                "v1Pet": {"type": "object", "properties": {"name": {"type": "string"}}}
+            }
+        }))
+        .unwrap();
+        let options = ConvertOptions {
+            grpc_gateway: true,
+            ..Default::default()
+        };
+
+        let conversion = crate::convert_with(swagger, &options);
+
+        assert!(conversion.diagnostics.is_empty());
+        let document = conversion.to_value();
+        let pets = &document["paths"]["/v1/pets"];
+        for method in ["get", "post"] {
+            assert_eq!(
+                pets[method]["responses"]["default"],
+                json!({"$ref": "#/components/responses/rpcStatus"})
+            );
+        }
+        assert_eq!(
+            pets["get"]["responses"]["200"]["content"]["application/json"]["schema"],
+            json!({"$ref": "#/components/schemas/v1PetStreamResult"})
+        );
+        let schemas = &document["components"]["schemas"];
+        assert!(schemas["v1PetStreamResult"].get("title").is_none());
+        assert_eq!(schemas["protobufAny"]["required"], json!(["@type"]));
+        assert_eq!(schemas["protobufAny"]["additionalProperties"], json!(true));
+    }
+}
@@ -0,0 +1,503 @@
+//! Inference of a draft Swagger 2.0 document from a HAR capture.
+//!
+//! Every captured request contributes its path, method, query and header parameters and
+//! the shapes of its JSON request and response bodies. Path segments which look like
+//! identifiers become path parameters, so `/users/42` and `/users/7` end up as the single
+//! path `/users/{userId}`. The result is a starting point for documenting a service and
+//! usually needs editing before it is published.
+//!
+//! http://www.softwareishard.com/blog/har-12-spec/
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use serde_with::skip_serializing_none;
+
+use crate::spec::Swagger;
+
+/// Headers which are set by every client and carry no information about the API.
+const IGNORED_HEADERS: [&str; 12] = [
+    "accept",
+    "accept-encoding",
+    "accept-language",
+    "cache-control",
+    "connection",
+    "content-length",
+    "content-type",
+    "cookie",
+    "host",
+    "origin",
+    "referer",
+    "user-agent",
+];
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Har {
+    pub log: Log,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Log {
+    #[serde(default)]
+    pub entries: Vec<Entry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Entry {
+    pub request: Request,
+    pub response: Response,
+}
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    pub method: String,
+    pub url: String,
+    #[serde(default)]
+    pub headers: Vec<NameValue>,
+    #[serde(default)]
+    pub query_string: Vec<NameValue>,
+    pub post_data: Option<PostData>,
+}
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[serde(rename_all = "camelCase")]
+pub struct Response {
+    pub status: u16,
+    pub status_text: Option<String>,
+    pub content: Option<Content>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct NameValue {
+    pub name: String,
+    pub value: String,
+}
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[serde(rename_all = "camelCase")]
+pub struct PostData {
+    pub mime_type: String,
+    pub text: Option<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[serde(rename_all = "camelCase")]
+pub struct Content {
+    pub mime_type: Option<String>,
+    pub text: Option<String>,
+}
+
+/// Observations of a single operation across all captured requests.
+#[derive(Default)]
+struct Observed {
+    calls: usize,
+    /// Parameter name and location, with the merged type and the number of calls using it.
+    parameters: BTreeMap<(String, &'static str), (Value, usize)>,
+    consumes: Vec<String>,
+    produces: Vec<String>,
+    body: Option<Value>,
+    responses: BTreeMap<u16, (String, Option<Value>)>,
+}
+
+/// Infers a draft Swagger 2.0 document from the requests of a HAR capture.
+///
+/// Host and scheme are taken from the first request.
+pub fn infer(har: Har) -> Result<Swagger, serde_json::Error> {
+    let mut swagger = Map::new();
+    swagger.insert("swagger".to_string(), json!("2.0"));
+    swagger.insert(
+        "info".to_string(),
+        json!({"title": "Inferred API", "version": "0.1.0"}),
+    );
+
+    let mut operations: BTreeMap<(String, String), Observed> = BTreeMap::new();
+    for (i, entry) in har.log.entries.into_iter().enumerate() {
+        let Some((scheme, host, path, _)) = split_url(&entry.request.url) else {
+            continue;
+        };
+        if i == 0 {
+            swagger.insert("schemes".to_string(), json!([scheme]));
+            swagger.insert("host".to_string(), json!(host));
+        }
+
+        let (template, path_parameters) = template_path(path);
+        let method = entry.request.method.to_ascii_lowercase();
+        let observed = operations.entry((template, method)).or_default();
+        observe(observed, entry, path_parameters);
+    }
+
+    let mut paths = Map::new();
+    for ((path, method), observed) in operations {
+        let path_item = paths
+            .entry(path)
+            .or_insert_with(|| Value::Object(Map::new()));
+        path_item[method] = operation(observed);
+    }
+    swagger.insert("paths".to_string(), Value::Object(paths));
+
+    serde_json::from_value(Value::Object(swagger))
+}
+
+fn observe(observed: &mut Observed, entry: Entry, path_parameters: Vec<(String, String)>) {
+    observed.calls += 1;
+
+    let mut parameter = |name: String, location: &'static str, value: &str| {
+        let schema = schema_of_text(value);
+        observed
+            .parameters
+            .entry((name, location))
+            .and_modify(|(merged, count)| {
+                *merged = merge(merged.take(), schema.clone());
+                *count += 1;
+            })
+            .or_insert((schema, 1));
+    };
+    for (name, value) in path_parameters {
+        parameter(name, "path", &value);
+    }
+    for query in entry.request.query_string {
+        parameter(query.name, "query", &query.value);
+    }
+    for header in entry.request.headers {
+        let lowercase = header.name.to_ascii_lowercase();
+        if !IGNORED_HEADERS.contains(&lowercase.as_str()) && !lowercase.starts_with(':') {
+            parameter(header.name, "header", &header.value);
+        }
+    }
+
+    if let Some(post_data) = entry.request.post_data {
+        let mime_type = media_type(&post_data.mime_type);
+        if let Some(schema) = post_data.text.as_deref().and_then(json_schema) {
+            observed.body = Some(match observed.body.take() {
+                Some(body) => merge(body, schema),
+                None => schema,
+            });
+        }
+        if !observed.consumes.contains(&mime_type) {
+            observed.consumes.push(mime_type);
+        }
+    }
+
+    let response = entry.response;
+    let description = response
+        .status_text
+        .filter(|text| !text.is_empty())
+        .unwrap_or_else(|| "response".to_string());
+    let mut schema = None;
+    if let Some(content) = response.content {
+        if let Some(mime_type) = content.mime_type.as_deref().map(media_type) {
+            if !mime_type.is_empty() && !observed.produces.contains(&mime_type) {
+                observed.produces.push(mime_type);
+            }
+        }
+        schema = content.text.as_deref().and_then(json_schema);
+    }
+    match observed.responses.get_mut(&response.status) {
+        Some((_, merged)) => {
+            *merged = match (merged.take(), schema) {
+                (Some(a), Some(b)) => Some(merge(a, b)),
+                (a, b) => a.or(b),
+            }
+        }
+        None => {
+            observed
+                .responses
+                .insert(response.status, (description, schema));
+        }
+    }
+}
+
+fn operation(observed: Observed) -> Value {
+    let mut operation = Map::new();
+
+    let mut parameters: Vec<Value> = observed
+        .parameters
+        .into_iter()
+        .map(|((name, location), (schema, count))| {
+            let mut parameter = match schema {
+                Value::Object(object) => object,
+                _ => Map::new(),
+            };
+            // only scalars can be parameters
+            if !matches!(
+                parameter.get("type").and_then(Value::as_str),
+                Some("integer" | "number" | "boolean")
+            ) {
+                parameter = Map::from_iter([("type".to_string(), json!("string"))]);
+            }
+            parameter.insert("name".to_string(), json!(name));
+            parameter.insert("in".to_string(), json!(location));
+            let required = location == "path" || count == observed.calls;
+            parameter.insert("required".to_string(), json!(required));
+            Value::Object(parameter)
+        })
+        .collect();
+    if let Some(schema) = observed.body {
+        parameters.push(json!({"name": "body", "in": "body", "required": true, "schema": schema}));
+    }
+    if !parameters.is_empty() {
+        operation.insert("parameters".to_string(), Value::Array(parameters));
+    }
+    if !observed.consumes.is_empty() {
+        operation.insert("consumes".to_string(), json!(observed.consumes));
+    }
+    if !observed.produces.is_empty() {
+        operation.insert("produces".to_string(), json!(observed.produces));
+    }
+
+    let responses: Map<String, Value> = observed
+        .responses
+        .into_iter()
+        .map(|(status, (description, schema))| {
+            let mut response = json!({ "description": description });
+            if let Some(schema) = schema {
+                response["schema"] = schema;
+            }
+            (status.to_string(), response)
+        })
+        .collect();
+    operation.insert("responses".to_string(), Value::Object(responses));
+
+    Value::Object(operation)
+}
+
+/// Splits an absolute URL into scheme, host, path and query.
+fn split_url(url: &str) -> Option<(&str, &str, &str, &str)> {
+    let (scheme, rest) = url.split_once("://")?;
+    let rest = rest.split('#').next().unwrap_or_default();
+    let (rest, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let (host, path) = rest.find('/').map_or((rest, "/"), |i| rest.split_at(i));
+    Some((scheme, host, path, query))
+}
+
+/// Replaces identifier-like segments of `path` with parameters named after the preceding
+/// segment, and returns the template with the observed parameter values.
+fn template_path(path: &str) -> (String, Vec<(String, String)>) {
+    let mut parameters: Vec<(String, String)> = Vec::new();
+    let mut previous = "";
+    let segments: Vec<String> = path
+        .split('/')
+        .map(|segment| {
+            if !is_identifier(segment) {
+                previous = segment;
+                return segment.to_string();
+            }
+            let resource = previous.strip_suffix('s').unwrap_or(previous);
+            let mut name = if resource.is_empty() {
+                "id".to_string()
+            } else {
+                format!("{resource}Id")
+            };
+            if parameters.iter().any(|(n, _)| *n == name) {
+                name = format!("{name}{}", parameters.len() + 1);
+            }
+            parameters.push((name.clone(), segment.to_string()));
+            format!("{{{name}}}")
+        })
+        .collect();
+    (segments.join("/"), parameters)
+}
+
+/// Numbers, UUIDs and long hexadecimal strings.
+fn is_identifier(segment: &str) -> bool {
+    if segment.is_empty() {
+        return false;
+    }
+    let digits = segment.chars().all(|c| c.is_ascii_digit());
+    let uuid = segment.len() == 36 && segment.chars().all(|c| c.is_ascii_hexdigit() || c == '-');
+    let hex = segment.len() >= 16 && segment.chars().all(|c| c.is_ascii_hexdigit());
+    digits || uuid || hex
+}
+
+/// Media type without parameters, e.g. `application/json` for `application/json; charset=utf-8`.
+fn media_type(mime_type: &str) -> String {
+    mime_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_string()
+}
+
+fn json_schema(text: &str) -> Option<Value> {
+    serde_json::from_str(text).ok().as_ref().and_then(schema_of)
+}
+
+/// Schema of a parameter value, which is always captured as text.
+fn schema_of_text(text: &str) -> Value {
+    let schema = match serde_json::from_str::<Value>(text) {
+        Ok(value @ (Value::Number(_) | Value::Bool(_))) => schema_of(&value),
+        _ => None,
+    };
+    schema.unwrap_or_else(|| json!({"type": "string"}))
+}
+
+/// Schema describing `value`, `None` for `null` which carries no type.
+fn schema_of(value: &Value) -> Option<Value> {
+    let schema = match value {
+        Value::Null => return None,
+        Value::Bool(_) => json!({"type": "boolean"}),
+        Value::Number(n) if n.is_f64() => json!({"type": "number"}),
+        Value::Number(_) => json!({"type": "integer"}),
+        Value::String(_) => json!({"type": "string"}),
+        Value::Array(items) => {
+            let items = items
+                .iter()
+                .filter_map(schema_of)
+                .reduce(merge)
+                .unwrap_or_else(|| json!({"type": "object"}));
+            json!({"type": "array", "items": items})
+        }
+        Value::Object(object) => {
+            let properties: Map<String, Value> = object
+                .iter()
+                .filter_map(|(k, v)| Some((k.clone(), schema_of(v)?)))
+                .collect();
+            let mut schema = json!({"type": "object"});
+            if !properties.is_empty() {
+                schema["required"] = json!(properties.keys().collect::<Vec<_>>());
+                schema["properties"] = Value::Object(properties);
+            }
+            schema
+        }
+    };
+    Some(schema)
+}
+
+/// Merges two observed schemas. Properties are unioned and only those present in both are
+/// kept as required.
+fn merge(a: Value, b: Value) -> Value {
+    let type_of = |v: &Value| v.get("type").and_then(Value::as_str).map(str::to_string);
+    match (type_of(&a).as_deref(), type_of(&b).as_deref()) {
+        (Some("object"), Some("object")) => {
+            let (Value::Object(mut a), Value::Object(mut b)) = (a, b) else {
+                unreachable!()
+            };
+            let required_b = b.remove("required").unwrap_or_else(|| json!([]));
+            let required: Vec<Value> = a
+                .remove("required")
+                .and_then(|r| r.as_array().cloned())
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|r| required_b.as_array().is_some_and(|b| b.contains(r)))
+                .collect();
+
+            let mut properties = match a.remove("properties") {
+                Some(Value::Object(properties)) => properties,
+                _ => Map::new(),
+            };
+            if let Some(Value::Object(other)) = b.remove("properties") {
+                for (name, schema) in other {
+                    let merged = match properties.remove(&name) {
+                        Some(existing) => merge(existing, schema),
+                        None => schema,
+                    };
+                    properties.insert(name, merged);
+                }
+            }
+
+            let mut merged = json!({"type": "object"});
+            if !required.is_empty() {
+                merged["required"] = Value::Array(required);
+            }
+            if !properties.is_empty() {
+                merged["properties"] = Value::Object(properties);
+            }
+            merged
+        }
+        (Some("array"), Some("array")) => {
+            let (Value::Object(mut a), Value::Object(mut b)) = (a, b) else {
+                unreachable!()
+            };
+            let items = match (a.remove("items"), b.remove("items")) {
+                (Some(a), Some(b)) => merge(a, b),
+                (a, b) => a.or(b).unwrap_or_else(|| json!({"type": "object"})),
+            };
+            json!({"type": "array", "items": items})
+        }
+        (Some("integer"), Some("number")) | (Some("number"), Some("integer")) => {
+            json!({"type": "number"})
+        }
+        _ => a,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn infer_from_har() {
+        let entry = |id: u32, body: Value| {
+            json!({
+                "request": {
+                    "method": "GET",
+                    "url": format!("https://api.example.com/users/{id}?verbose=true"),
+                    "headers": [{"name": "Accept", "value": "application/json"}],
+                    "queryString": [{"name": "verbose", "value": "true"}]
+                },
+                "response": {
+                    "status": 200,
+                    "statusText": "OK",
+                    "content": {"mimeType": "application/json; charset=utf-8", "text": body.to_string()}
+                }
+            })
+        };
+        let har: Har = serde_json::from_value(json!({
+            "log": {
+                "entries": [
+                    entry(1, json!({"id": 1, "name": "a", "age": 3})),
+                    entry(2, json!({"id": 2, "name": "b", "email": null}))
+                ]
+            }
+        }))
+        .unwrap();
+
+        let swagger = infer(har).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&swagger).unwrap()["paths"],
+            json!({
+                "/users/{userId}": {
+                    "get": {
+                        "produces": ["application/json"],
+                        "parameters": [
+                            {"name": "userId", "in": "path", "required": true, "type": "integer"},
+                            {"name": "verbose", "in": "query", "required": true, "type": "boolean"}
+                        ],
+                        "responses": {
+                            "200": {
+                                "description": "OK",
+                                "schema": {
+                                    "type": "object",
+                                    "required": ["id", "name"],
+                                    "properties": {
+                                        "age": {"type": "integer"},
+                                        "id": {"type": "integer"},
+                                        "name": {"type": "string"}
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            })
+        );
+    }
+}
@@ -0,0 +1,521 @@
+//! Version-agnostic intermediate representation of API descriptions.
+//!
+//! Every supported input is loaded into a [`Document`], see [`load`]: Swagger 1.2 API
+//! declarations are upgraded to Swagger 2.0, which is converted like by [`convert_str`], and
+//! OpenAPI 3.0 and 3.1 documents are read as they are. A [`Document`] is written as either
+//! OpenAPI version, so supporting another format takes a reader or writer for it rather than
+//! a converter per pair of versions. There is no writer for Swagger 2.0, which cannot express
+//! much of the model, like `oneOf` or request bodies of several media types, so documents are
+//! only converted away from it.
+//!
+//! Schemas are kept in the dialect of OpenAPI 3.0, see [`Schema`]. Members which are not
+//! modelled, like `tags`, examples or extensions, are kept as they are in the `rest` of the
+//! object declaring them, so reading and writing a document of the same version only drops
+//! members which are set to their default, like `required: false`.
+//!
+//! The model is owned by this crate, so unlike [`Conversion::openapi`] it does not tie callers
+//! to the version of utoipa used here, see [`Conversion::to_document`]. With the `model`
+//! feature, a [`Document`] is (de)serialized as OpenAPI document.
+//!
+//! [`Conversion::openapi`]: crate::Conversion::openapi
+//! [`Conversion::to_document`]: crate::Conversion::to_document
+
+use std::collections::BTreeMap;
+
+use serde_json::{Map, Value};
+
+use crate::{
+    convert_str, convert_with,
+    spec::{v1, Swagger},
+    ConvertOptions, Error,
+};
+
+mod openapi;
+
+/// Format a [`Document`] was loaded from.
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub enum Version {
+    Swagger1_2,
+    Swagger2_0,
+    #[default]
+    OpenApi3_0,
+    OpenApi3_1,
+}
+
+/// A JSON schema in the dialect of OpenAPI 3.0, e.g. with `nullable` rather than a `null`
+/// type and boolean `exclusiveMinimum` and `exclusiveMaximum`.
+pub type Schema = Value;
+
+/// Names of the security schemes which have to be satisfied together, with the scopes
+/// required of each.
+pub type SecurityRequirement = BTreeMap<String, Vec<String>>;
+
+/// An element or a reference to one declared in the components.
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub enum RefOr<T> {
+    Ref(Reference),
+    T(T),
+}
+
+#[derive(Default, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Reference {
+    pub location: String,
+    /// Members next to `$ref`, like the `summary` and `description` OpenAPI 3.1 allows.
+    pub rest: Map<String, Value>,
+}
+
+#[derive(Default, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Document {
+    pub source: Version,
+    pub info: Info,
+    pub servers: Vec<Value>,
+    pub paths: BTreeMap<String, PathItem>,
+    /// Webhooks of OpenAPI 3.1, written as `x-webhooks` to OpenAPI 3.0.
+    pub webhooks: BTreeMap<String, PathItem>,
+    pub components: Components,
+    pub security: Option<Vec<SecurityRequirement>>,
+    pub rest: Map<String, Value>,
+}
+
+#[derive(Default, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Info {
+    pub title: String,
+    pub version: String,
+    pub description: Option<String>,
+    pub rest: Map<String, Value>,
+}
+
+#[derive(Default, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct PathItem {
+    /// Parameters shared by the operations.
+    pub parameters: Vec<RefOr<Parameter>>,
+    /// Operations by lowercase method.
+    pub operations: BTreeMap<String, Operation>,
+    pub rest: Map<String, Value>,
+}
+
+#[derive(Default, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Operation {
+    pub operation_id: Option<String>,
+    pub summary: Option<String>,
+    pub description: Option<String>,
+    pub tags: Vec<String>,
+    pub deprecated: bool,
+    pub parameters: Vec<RefOr<Parameter>>,
+    pub request_body: Option<RefOr<RequestBody>>,
+    /// Responses by status code or `default`.
+    pub responses: BTreeMap<String, RefOr<Response>>,
+    /// Requirements replacing the ones of the document, none at all if empty.
+    pub security: Option<Vec<SecurityRequirement>>,
+    pub rest: Map<String, Value>,
+}
+
+#[derive(Default, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Parameter {
+    pub name: String,
+    /// Where the parameter is passed, like `query` or `header`.
+    pub location: String,
+    pub description: Option<String>,
+    pub required: bool,
+    pub deprecated: bool,
+    pub schema: Option<Schema>,
+    pub rest: Map<String, Value>,
+}
+
+#[derive(Default, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct RequestBody {
+    pub description: Option<String>,
+    pub required: bool,
+    /// Media types by name.
+    pub content: BTreeMap<String, MediaType>,
+    pub rest: Map<String, Value>,
+}
+
+#[derive(Default, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Response {
+    pub description: String,
+    pub headers: BTreeMap<String, RefOr<Header>>,
+    /// Media types by name.
+    pub content: BTreeMap<String, MediaType>,
+    pub rest: Map<String, Value>,
+}
+
+#[derive(Default, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Header {
+    pub description: Option<String>,
+    pub required: bool,
+    pub deprecated: bool,
+    pub schema: Option<Schema>,
+    pub rest: Map<String, Value>,
+}
+
+#[derive(Default, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct MediaType {
+    pub schema: Option<Schema>,
+    pub rest: Map<String, Value>,
+}
+
+#[derive(Default, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Components {
+    pub schemas: BTreeMap<String, Schema>,
+    pub responses: BTreeMap<String, RefOr<Response>>,
+    pub parameters: BTreeMap<String, RefOr<Parameter>>,
+    pub request_bodies: BTreeMap<String, RefOr<RequestBody>>,
+    pub headers: BTreeMap<String, RefOr<Header>>,
+    pub security_schemes: BTreeMap<String, RefOr<SecurityScheme>>,
+    pub rest: Map<String, Value>,
+}
+
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct SecurityScheme {
+    pub kind: SecurityKind,
+    pub description: Option<String>,
+    pub rest: Map<String, Value>,
+}
+
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub enum SecurityKind {
+    Http {
+        scheme: String,
+        bearer_format: Option<String>,
+    },
+    ApiKey {
+        name: String,
+        /// Where the key is passed, `query`, `header` or `cookie`.
+        location: String,
+    },
+    OAuth2 {
+        /// The flows by name, as declared.
+        flows: Map<String, Value>,
+    },
+    OpenIdConnect {
+        url: String,
+    },
+    MutualTls,
+    /// A scheme of an unknown or incomplete `type`, which is kept in the `rest`.
+    Other,
+}
+
+impl Document {
+    /// Converts a Swagger 2.0 document like [`convert_with`] does.
+    pub fn from_swagger(swagger: Swagger, options: &ConvertOptions) -> Self {
+        convert_with(swagger, options).to_document()
+    }
+
+    /// Upgrades a Swagger 1.2 resource listing and the API declarations of its resources
+    /// like [`v1::upgrade`] does and converts the result like [`Self::from_swagger`].
+    pub fn from_v1(
+        listing: v1::ResourceListing,
+        declarations: Vec<v1::ApiDeclaration>,
+        options: &ConvertOptions,
+    ) -> Result<Self, Error> {
+        let swagger = v1::upgrade(listing, declarations).map_err(Error::Parse)?;
+        let mut document = Self::from_swagger(swagger, options);
+        document.source = Version::Swagger1_2;
+        Ok(document)
+    }
+
+    /// Reads an OpenAPI 3.0 or 3.1 document.
+    pub fn from_openapi(document: Value) -> Result<Self, Error> {
+        let version = document
+            .get("openapi")
+            .and_then(Value::as_str)
+            .unwrap_or("unknown");
+        let source = if version.starts_with("3.0") {
+            Version::OpenApi3_0
+        } else if version.starts_with("3.1") {
+            Version::OpenApi3_1
+        } else {
+            return Err(Error::UnsupportedVersion(version.to_string()));
+        };
+        Ok(openapi::read(document, source))
+    }
+
+    /// Reads the document written by a conversion of a Swagger 2.0 document, see
+    /// [`Conversion::to_document`](crate::Conversion::to_document).
+    pub(crate) fn converted(document: Value) -> Self {
+        let mut document = Self::from_openapi(document)
+            .expect("conversions write documents of a supported OpenAPI version");
+        document.source = Version::Swagger2_0;
+        document
+    }
+
+    /// Writes the document as OpenAPI 3.1 if `version` is [`Version::OpenApi3_1`], as
+    /// OpenAPI 3.0 otherwise.
+    pub fn to_openapi(&self, version: Version) -> Value {
+        openapi::write(self, version)
+    }
+
+    /// The operations of all paths with their path and method.
+    pub fn operations(&self) -> impl Iterator<Item = (&str, &str, &Operation)> {
+        self.paths.iter().flat_map(|(path, path_item)| {
+            path_item
+                .operations
+                .iter()
+                .map(move |(method, operation)| (path.as_str(), method.as_str(), operation))
+        })
+    }
+}
+
+/// Serializes the document as OpenAPI, see [`Document::to_openapi`] with its `source`.
+#[cfg(feature = "model")]
+impl serde::Serialize for Document {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_openapi(self.source).serialize(serializer)
+    }
+}
+
+/// Deserializes an OpenAPI 3.0 or 3.1 document, see [`Document::from_openapi`].
+#[cfg(feature = "model")]
+impl<'de> serde::Deserialize<'de> for Document {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let document = Value::deserialize(deserializer)?;
+        Document::from_openapi(document).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Loads a Swagger 1.2 API declaration, a Swagger 2.0 document or an OpenAPI 3.0 or 3.1
+/// document, see the [module documentation](self).
+///
+/// Swagger documents are converted with `options`, without their diagnostics. Use
+/// [`Document::from_v1`] for a 1.2 API with a resource listing.
+pub fn load(source: &str, options: &ConvertOptions) -> Result<Document, Error> {
+    let document: Value = serde_json::from_str(source).map_err(Error::Parse)?;
+    if document.get("openapi").is_some() {
+        return Document::from_openapi(document);
+    }
+    match document.get("swagger").and_then(Value::as_str) {
+        Some("2.0") => return Ok(convert_str(source, options)?.to_document()),
+        Some(version) => return Err(Error::UnsupportedVersion(version.to_string())),
+        None => {}
+    }
+    match document.get("swaggerVersion").and_then(Value::as_str) {
+        Some("1.2") => {
+            let declaration = serde_json::from_value(document).map_err(Error::Parse)?;
+            let listing = v1::ResourceListing {
+                swagger_version: "1.2".to_string(),
+                apis: Vec::new(),
+                api_version: None,
+                info: None,
+                authorizations: BTreeMap::new(),
+            };
+            Document::from_v1(listing, vec![declaration], options)
+        }
+        version => Err(Error::UnsupportedVersion(
+            version.unwrap_or("unknown").to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn load_every_version() {
+        let swagger = json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "paths": {"/pets": {"get": {"responses": {"204": {"description": "none"}}}}}
+        });
+        let declaration = json!({
+            "swaggerVersion": "1.2",
+            "basePath": "http://example.com/api",
+            "resourcePath": "/pets",
+            "apis": [{"path": "/pets", "operations": [{"method": "GET", "type": "void"}]}]
+        });
+        let openapi = json!({
+            "openapi": "3.1.0",
+            "info": {"title": "Pets", "version": "2.0"},
+            "paths": {"/pets": {"get": {"responses": {"204": {"description": "none"}}}}}
+        });
+        let options = ConvertOptions::default();
+
+        for (source, version) in [
+            (swagger, Version::Swagger2_0),
+            (declaration, Version::Swagger1_2),
+            (openapi, Version::OpenApi3_1),
+        ] {
+            let document = load(&source.to_string(), &options).unwrap();
+            assert_eq!(document.source, version);
+            let operations: Vec<_> = document
+                .operations()
+                .map(|(path, method, _)| (path, method))
+                .collect();
+            assert_eq!(operations, [("/pets", "get")]);
+        }
+        assert!(matches!(
+            load(r#"{"swaggerVersion": "1.1"}"#, &options),
+            Err(Error::UnsupportedVersion(version)) if version == "1.1"
+        ));
+    }
+
+    #[test]
+    fn convert_into_document() {
+        let swagger: Swagger = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "paths": {},
+            "x-webhooks": {"newPet": {"post": {"responses": {"200": {"description": "ok"}}}}},
+            "definitions": {
+                "Pet": {
+                    "type": "object",
+                    "properties": {"name": {"type": "string", "x-nullable": true}}
+                }
+            }
+        }))
+        .unwrap();
+        let options = ConvertOptions {
+            output: crate::output::OutputOptions {
+                target: crate::output::Target::V3_1,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let document = Document::from_swagger(swagger, &options);
+
+        assert_eq!(document.source, Version::Swagger2_0);
+        assert_eq!(
+            document.components.schemas["Pet"]["properties"]["name"],
+            json!({"type": "string", "nullable": true})
+        );
+        assert!(document.webhooks["newPet"].operations.contains_key("post"));
+    }
+
+    #[cfg(feature = "model")]
+    #[test]
+    fn serialize_as_openapi() {
+        let openapi = json!({
+            "openapi": "3.0.3",
+            "info": {"title": "Pets", "version": "1.0"},
+            "paths": {"/pets": {"get": {"responses": {"204": {"description": "none"}}}}}
+        });
+
+        let document: Document = serde_json::from_value(openapi.clone()).unwrap();
+
+        assert_eq!(document.source, Version::OpenApi3_0);
+        assert_eq!(serde_json::to_value(&document).unwrap(), openapi);
+        assert!(serde_json::from_value::<Document>(json!({"swagger": "2.0"})).is_err());
+    }
+
+    #[test]
+    fn move_between_openapi_versions() {
+        let pet = json!({
+            "type": "object",
+            "properties": {
+                "name": {"type": ["string", "null"]},
+                "age": {"type": "integer", "exclusiveMinimum": 0}
+            }
+        });
+        let openapi = json!({
+            "openapi": "3.1.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "paths": {},
+            "webhooks": {"newPet": {"post": {"responses": {"200": {"description": "ok"}}}}},
+            "components": {"schemas": {"Pet": pet}}
+        });
+
+        let document = Document::from_openapi(openapi.clone()).unwrap();
+
+        assert_eq!(
+            document.components.schemas["Pet"]["properties"],
+            json!({
+                "name": {"type": "string", "nullable": true},
+                "age": {"type": "integer", "minimum": 0.0, "exclusiveMinimum": true}
+            })
+        );
+        let openapi_3_0 = document.to_openapi(Version::OpenApi3_0);
+        assert_eq!(openapi_3_0["openapi"], "3.0.3");
+        assert!(openapi_3_0.get("webhooks").is_none());
+        assert_eq!(
+            openapi_3_0["x-webhooks"]["newPet"]["post"]["responses"]["200"]["description"],
+            "ok"
+        );
+        let openapi_3_1 = document.to_openapi(Version::OpenApi3_1);
+        assert_eq!(
+            openapi_3_1["components"]["schemas"]["Pet"]["properties"],
+            json!({
+                "name": {"type": ["string", "null"]},
+                "age": {"type": "integer", "exclusiveMinimum": 0.0}
+            })
+        );
+        assert_eq!(openapi_3_1["webhooks"], openapi["webhooks"]);
+    }
+
+    #[test]
+    fn keep_unmodelled_members() {
+        let openapi = json!({
+            "openapi": "3.0.3",
+            "info": {"title": "Pets", "version": "1.0", "x-logo": {"url": "logo.png"}},
+            "tags": [{"name": "pets"}],
+            "paths": {
+                "/pets/{id}": {
+                    "summary": "A pet",
+                    "parameters": [{"$ref": "#/components/parameters/Id"}],
+                    "get": {
+                        "tags": ["pets"],
+                        "deprecated": true,
+                        "responses": {
+                            "200": {
+                                "description": "ok",
+                                "headers": {"X-Rate-Limit": {"schema": {"type": "integer"}}},
+                                "content": {
+                                    "application/json": {
+                                        "schema": {"$ref": "#/components/schemas/Pet"},
+                                        "example": {"name": "Rex"}
+                                    }
+                                },
+                                "links": {"owner": {"operationId": "getOwner"}}
+                            }
+                        },
+                        "security": [],
+                        "x-internal": true
+                    }
+                }
+            },
+            "components": {
+                "schemas": {"Pet": {"type": "object", "x-tags": ["pets"]}},
+                "parameters": {
+                    "Id": {"name": "id", "in": "path", "required": true, "style": "simple"}
+                },
+                "securitySchemes": {
+                    "token": {"type": "http", "scheme": "bearer", "bearerFormat": "JWT"},
+                    "custom": {"type": "x-custom", "description": "Something else"}
+                },
+                "examples": {"Rex": {"value": {"name": "Rex"}}}
+            },
+            "security": [{"token": []}]
+        });
+
+        let document = Document::from_openapi(openapi.clone()).unwrap();
+
+        assert!(matches!(
+            &document.components.security_schemes["custom"],
+            RefOr::T(SecurityScheme {
+                kind: SecurityKind::Other,
+                ..
+            })
+        ));
+        let token = SecurityRequirement::from([("token".to_string(), Vec::new())]);
+        assert_eq!(document.security, Some(vec![token]));
+        assert_eq!(document.to_openapi(Version::OpenApi3_0), openapi);
+    }
+}
@@ -0,0 +1,536 @@
+//! Reading and writing [`Document`]s as OpenAPI 3.0 and 3.1.
+//!
+//! Members of an unexpected type are not read but kept in the `rest` of their object.
+
+use std::collections::BTreeMap;
+
+use serde::de::DeserializeOwned;
+use serde_json::{json, Map, Value};
+
+use super::*;
+use crate::export::{self, Draft};
+
+const METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+pub(super) fn read(document: Value, source: Version) -> Document {
+    Reader { source }.document(document)
+}
+
+pub(super) fn write(document: &Document, version: Version) -> Value {
+    Writer { version }.document(document)
+}
+
+struct Reader {
+    source: Version,
+}
+
+impl Reader {
+    fn document(&self, document: Value) -> Document {
+        let mut rest = object(document);
+        rest.remove("openapi");
+        let info = take_object(&mut rest, "info").unwrap_or_default();
+        let webhooks = match self.source {
+            Version::OpenApi3_1 => "webhooks",
+            _ => "x-webhooks",
+        };
+        Document {
+            source: self.source,
+            info: self.info(info),
+            servers: take(&mut rest, "servers").unwrap_or_default(),
+            paths: self.path_items(take_object(&mut rest, "paths")),
+            webhooks: self.path_items(take_object(&mut rest, webhooks)),
+            components: self.components(take_object(&mut rest, "components")),
+            security: take(&mut rest, "security"),
+            rest,
+        }
+    }
+
+    fn info(&self, mut rest: Map<String, Value>) -> Info {
+        Info {
+            title: take(&mut rest, "title").unwrap_or_default(),
+            version: take(&mut rest, "version").unwrap_or_default(),
+            description: take(&mut rest, "description"),
+            rest,
+        }
+    }
+
+    fn path_items(&self, path_items: Option<Map<String, Value>>) -> BTreeMap<String, PathItem> {
+        let path_items = path_items.unwrap_or_default().into_iter();
+        path_items
+            .map(|(path, path_item)| (path, self.path_item(object(path_item))))
+            .collect()
+    }
+
+    fn path_item(&self, mut rest: Map<String, Value>) -> PathItem {
+        let operations = METHODS
+            .iter()
+            .filter_map(|method| {
+                let operation = take_object(&mut rest, method)?;
+                Some((method.to_string(), self.operation(operation)))
+            })
+            .collect();
+        PathItem {
+            parameters: self.parameters(&mut rest),
+            operations,
+            rest,
+        }
+    }
+
+    fn operation(&self, mut rest: Map<String, Value>) -> Operation {
+        let responses = take_object(&mut rest, "responses").unwrap_or_default();
+        Operation {
+            operation_id: take(&mut rest, "operationId"),
+            summary: take(&mut rest, "summary"),
+            description: take(&mut rest, "description"),
+            tags: take(&mut rest, "tags").unwrap_or_default(),
+            deprecated: take(&mut rest, "deprecated").unwrap_or_default(),
+            parameters: self.parameters(&mut rest),
+            request_body: take_object(&mut rest, "requestBody")
+                .map(|body| ref_or(body, |body| self.request_body(body))),
+            responses: self.map(Some(responses), |response| self.response(response)),
+            security: take(&mut rest, "security"),
+            rest,
+        }
+    }
+
+    fn parameters(&self, rest: &mut Map<String, Value>) -> Vec<RefOr<Parameter>> {
+        let parameters: Vec<Value> = take(rest, "parameters").unwrap_or_default();
+        parameters
+            .into_iter()
+            .map(|parameter| ref_or(object(parameter), |p| self.parameter(p)))
+            .collect()
+    }
+
+    fn parameter(&self, mut rest: Map<String, Value>) -> Parameter {
+        Parameter {
+            name: take(&mut rest, "name").unwrap_or_default(),
+            location: take(&mut rest, "in").unwrap_or_default(),
+            description: take(&mut rest, "description"),
+            required: take(&mut rest, "required").unwrap_or_default(),
+            deprecated: take(&mut rest, "deprecated").unwrap_or_default(),
+            schema: self.schema(&mut rest),
+            rest,
+        }
+    }
+
+    fn request_body(&self, mut rest: Map<String, Value>) -> RequestBody {
+        RequestBody {
+            description: take(&mut rest, "description"),
+            required: take(&mut rest, "required").unwrap_or_default(),
+            content: self.content(&mut rest),
+            rest,
+        }
+    }
+
+    fn response(&self, mut rest: Map<String, Value>) -> Response {
+        let headers = take_object(&mut rest, "headers");
+        Response {
+            description: take(&mut rest, "description").unwrap_or_default(),
+            headers: self.map(headers, |header| self.header(header)),
+            content: self.content(&mut rest),
+            rest,
+        }
+    }
+
+    fn header(&self, mut rest: Map<String, Value>) -> Header {
+        Header {
+            description: take(&mut rest, "description"),
+            required: take(&mut rest, "required").unwrap_or_default(),
+            deprecated: take(&mut rest, "deprecated").unwrap_or_default(),
+            schema: self.schema(&mut rest),
+            rest,
+        }
+    }
+
+    fn content(&self, rest: &mut Map<String, Value>) -> BTreeMap<String, MediaType> {
+        let content = take_object(rest, "content").unwrap_or_default();
+        content
+            .into_iter()
+            .map(|(name, media_type)| {
+                let mut rest = object(media_type);
+                let schema = self.schema(&mut rest);
+                (name, MediaType { schema, rest })
+            })
+            .collect()
+    }
+
+    fn components(&self, components: Option<Map<String, Value>>) -> Components {
+        let mut rest = components.unwrap_or_default();
+        let schemas = take_object(&mut rest, "schemas").unwrap_or_default();
+        Components {
+            schemas: schemas
+                .into_iter()
+                .map(|(name, schema)| (name, self.dialect(schema)))
+                .collect(),
+            responses: self.map(take_object(&mut rest, "responses"), |r| self.response(r)),
+            parameters: self.map(take_object(&mut rest, "parameters"), |p| self.parameter(p)),
+            request_bodies: self.map(take_object(&mut rest, "requestBodies"), |body| {
+                self.request_body(body)
+            }),
+            headers: self.map(take_object(&mut rest, "headers"), |h| self.header(h)),
+            security_schemes: self.map(take_object(&mut rest, "securitySchemes"), security_scheme),
+            rest,
+        }
+    }
+
+    fn map<T>(
+        &self,
+        members: Option<Map<String, Value>>,
+        read: impl Fn(Map<String, Value>) -> T,
+    ) -> BTreeMap<String, RefOr<T>> {
+        let members = members.unwrap_or_default().into_iter();
+        members
+            .map(|(name, member)| (name, ref_or(object(member), &read)))
+            .collect()
+    }
+
+    fn schema(&self, rest: &mut Map<String, Value>) -> Option<Schema> {
+        rest.remove("schema").map(|schema| self.dialect(schema))
+    }
+
+    /// `schema` in the dialect of OpenAPI 3.0.
+    fn dialect(&self, mut schema: Value) -> Schema {
+        if self.source == Version::OpenApi3_1 {
+            export::from_draft_2020_12(&mut schema);
+        }
+        schema
+    }
+}
+
+fn security_scheme(mut rest: Map<String, Value>) -> SecurityScheme {
+    let is_string =
+        |rest: &Map<String, Value>, key: &str| rest.get(key).is_some_and(Value::is_string);
+    let scheme_type = rest.get("type").and_then(Value::as_str).map(str::to_string);
+    let kind = match scheme_type.as_deref() {
+        Some("http") if is_string(&rest, "scheme") => SecurityKind::Http {
+            scheme: take(&mut rest, "scheme").unwrap_or_default(),
+            bearer_format: take(&mut rest, "bearerFormat"),
+        },
+        Some("apiKey") if is_string(&rest, "name") && is_string(&rest, "in") => {
+            SecurityKind::ApiKey {
+                name: take(&mut rest, "name").unwrap_or_default(),
+                location: take(&mut rest, "in").unwrap_or_default(),
+            }
+        }
+        Some("oauth2") if rest.get("flows").is_some_and(Value::is_object) => SecurityKind::OAuth2 {
+            flows: take_object(&mut rest, "flows").unwrap_or_default(),
+        },
+        Some("openIdConnect") if is_string(&rest, "openIdConnectUrl") => {
+            SecurityKind::OpenIdConnect {
+                url: take(&mut rest, "openIdConnectUrl").unwrap_or_default(),
+            }
+        }
+        Some("mutualTLS") => SecurityKind::MutualTls,
+        _ => SecurityKind::Other,
+    };
+    if kind != SecurityKind::Other {
+        rest.remove("type");
+    }
+    SecurityScheme {
+        kind,
+        description: take(&mut rest, "description"),
+        rest,
+    }
+}
+
+fn ref_or<T>(mut rest: Map<String, Value>, read: impl FnOnce(Map<String, Value>) -> T) -> RefOr<T> {
+    match take(&mut rest, "$ref") {
+        Some(location) => RefOr::Ref(Reference { location, rest }),
+        None => RefOr::T(read(rest)),
+    }
+}
+
+fn object(value: Value) -> Map<String, Value> {
+    match value {
+        Value::Object(object) => object,
+        _ => Map::new(),
+    }
+}
+
+/// Removes the member `key` from `object` if it is a `T`.
+fn take<T: DeserializeOwned>(object: &mut Map<String, Value>, key: &str) -> Option<T> {
+    let value = object.remove(key)?;
+    match T::deserialize(&value) {
+        Ok(taken) => Some(taken),
+        Err(_) => {
+            object.insert(key.to_string(), value);
+            None
+        }
+    }
+}
+
+fn take_object(object: &mut Map<String, Value>, key: &str) -> Option<Map<String, Value>> {
+    match object.remove(key)? {
+        Value::Object(taken) => Some(taken),
+        value => {
+            object.insert(key.to_string(), value);
+            None
+        }
+    }
+}
+
+struct Writer {
+    version: Version,
+}
+
+impl Writer {
+    fn document(&self, document: &Document) -> Value {
+        let mut root = document.rest.clone();
+        let (openapi, webhooks) = match self.version {
+            Version::OpenApi3_1 => ("3.1.0", "webhooks"),
+            _ => ("3.0.3", "x-webhooks"),
+        };
+        if self.version != Version::OpenApi3_1 {
+            root.remove("jsonSchemaDialect");
+        }
+        root.insert("openapi".to_string(), json!(openapi));
+        root.insert("info".to_string(), self.info(&document.info));
+        insert_non_empty(&mut root, "servers", json!(document.servers));
+        let paths = self.path_items(&document.paths);
+        if self.version != Version::OpenApi3_1 || !document.paths.is_empty() {
+            root.insert("paths".to_string(), paths);
+        }
+        if !document.webhooks.is_empty() {
+            root.insert(webhooks.to_string(), self.path_items(&document.webhooks));
+        }
+        insert_non_empty(
+            &mut root,
+            "components",
+            self.components(&document.components),
+        );
+        insert_some(&mut root, "security", &document.security);
+        Value::Object(root)
+    }
+
+    fn info(&self, info: &Info) -> Value {
+        let mut object = info.rest.clone();
+        object.insert("title".to_string(), json!(info.title));
+        object.insert("version".to_string(), json!(info.version));
+        insert_some(&mut object, "description", &info.description);
+        Value::Object(object)
+    }
+
+    fn path_items(&self, path_items: &BTreeMap<String, PathItem>) -> Value {
+        let path_items = path_items.iter();
+        path_items
+            .map(|(path, path_item)| (path.clone(), self.path_item(path_item)))
+            .collect()
+    }
+
+    fn path_item(&self, path_item: &PathItem) -> Value {
+        let mut object = path_item.rest.clone();
+        for (method, operation) in &path_item.operations {
+            object.insert(method.clone(), self.operation(operation));
+        }
+        insert_non_empty(
+            &mut object,
+            "parameters",
+            self.parameters(&path_item.parameters),
+        );
+        Value::Object(object)
+    }
+
+    fn operation(&self, operation: &Operation) -> Value {
+        let mut object = operation.rest.clone();
+        insert_some(&mut object, "operationId", &operation.operation_id);
+        insert_some(&mut object, "summary", &operation.summary);
+        insert_some(&mut object, "description", &operation.description);
+        insert_non_empty(&mut object, "tags", json!(operation.tags));
+        insert_true(&mut object, "deprecated", operation.deprecated);
+        insert_non_empty(
+            &mut object,
+            "parameters",
+            self.parameters(&operation.parameters),
+        );
+        if let Some(body) = &operation.request_body {
+            let body = ref_or_value(body, |body| self.request_body(body));
+            object.insert("requestBody".to_string(), body);
+        }
+        let responses = self.map(&operation.responses, |r| self.response(r));
+        object.insert("responses".to_string(), responses);
+        insert_some(&mut object, "security", &operation.security);
+        Value::Object(object)
+    }
+
+    fn parameters(&self, parameters: &[RefOr<Parameter>]) -> Value {
+        let parameters = parameters.iter();
+        parameters
+            .map(|parameter| ref_or_value(parameter, |p| self.parameter(p)))
+            .collect()
+    }
+
+    fn parameter(&self, parameter: &Parameter) -> Value {
+        let mut object = parameter.rest.clone();
+        object.insert("name".to_string(), json!(parameter.name));
+        object.insert("in".to_string(), json!(parameter.location));
+        insert_some(&mut object, "description", &parameter.description);
+        insert_true(&mut object, "required", parameter.required);
+        insert_true(&mut object, "deprecated", parameter.deprecated);
+        self.insert_schema(&mut object, &parameter.schema);
+        Value::Object(object)
+    }
+
+    fn request_body(&self, body: &RequestBody) -> Value {
+        let mut object = body.rest.clone();
+        insert_some(&mut object, "description", &body.description);
+        insert_true(&mut object, "required", body.required);
+        object.insert("content".to_string(), self.content(&body.content));
+        Value::Object(object)
+    }
+
+    fn response(&self, response: &Response) -> Value {
+        let mut object = response.rest.clone();
+        object.insert("description".to_string(), json!(response.description));
+        let headers = self.map(&response.headers, |header| self.header(header));
+        insert_non_empty(&mut object, "headers", headers);
+        insert_non_empty(&mut object, "content", self.content(&response.content));
+        Value::Object(object)
+    }
+
+    fn header(&self, header: &Header) -> Value {
+        let mut object = header.rest.clone();
+        insert_some(&mut object, "description", &header.description);
+        insert_true(&mut object, "required", header.required);
+        insert_true(&mut object, "deprecated", header.deprecated);
+        self.insert_schema(&mut object, &header.schema);
+        Value::Object(object)
+    }
+
+    fn content(&self, content: &BTreeMap<String, MediaType>) -> Value {
+        let content = content.iter();
+        content
+            .map(|(name, media_type)| {
+                let mut object = media_type.rest.clone();
+                self.insert_schema(&mut object, &media_type.schema);
+                (name.clone(), Value::Object(object))
+            })
+            .collect()
+    }
+
+    fn components(&self, components: &Components) -> Value {
+        let mut object = components.rest.clone();
+        let schemas: Map<String, Value> = components
+            .schemas
+            .iter()
+            .map(|(name, schema)| (name.clone(), self.dialect(schema)))
+            .collect();
+        insert_non_empty(&mut object, "schemas", Value::Object(schemas));
+        let members = [
+            (
+                "responses",
+                self.map(&components.responses, |r| self.response(r)),
+            ),
+            (
+                "parameters",
+                self.map(&components.parameters, |p| self.parameter(p)),
+            ),
+            (
+                "requestBodies",
+                self.map(&components.request_bodies, |body| self.request_body(body)),
+            ),
+            ("headers", self.map(&components.headers, |h| self.header(h))),
+            (
+                "securitySchemes",
+                self.map(&components.security_schemes, security_scheme_value),
+            ),
+        ];
+        for (key, value) in members {
+            insert_non_empty(&mut object, key, value);
+        }
+        Value::Object(object)
+    }
+
+    fn map<T>(&self, members: &BTreeMap<String, RefOr<T>>, write: impl Fn(&T) -> Value) -> Value {
+        let members = members.iter();
+        members
+            .map(|(name, member)| (name.clone(), ref_or_value(member, &write)))
+            .collect()
+    }
+
+    fn insert_schema(&self, object: &mut Map<String, Value>, schema: &Option<Schema>) {
+        if let Some(schema) = schema {
+            object.insert("schema".to_string(), self.dialect(schema));
+        }
+    }
+
+    /// `schema` in the dialect of the written version.
+    fn dialect(&self, schema: &Schema) -> Value {
+        let mut schema = schema.clone();
+        if self.version == Version::OpenApi3_1 {
+            export::to_dialect(&mut schema, Draft::Draft2020_12);
+        }
+        schema
+    }
+}
+
+fn security_scheme_value(scheme: &SecurityScheme) -> Value {
+    let mut object = scheme.rest.clone();
+    let mut insert = |key: &str, value: Value| object.insert(key.to_string(), value);
+    match &scheme.kind {
+        SecurityKind::Http {
+            scheme,
+            bearer_format,
+        } => {
+            insert("type", json!("http"));
+            insert("scheme", json!(scheme));
+            if let Some(bearer_format) = bearer_format {
+                insert("bearerFormat", json!(bearer_format));
+            }
+        }
+        SecurityKind::ApiKey { name, location } => {
+            insert("type", json!("apiKey"));
+            insert("name", json!(name));
+            insert("in", json!(location));
+        }
+        SecurityKind::OAuth2 { flows } => {
+            insert("type", json!("oauth2"));
+            insert("flows", Value::Object(flows.clone()));
+        }
+        SecurityKind::OpenIdConnect { url } => {
+            insert("type", json!("openIdConnect"));
+            insert("openIdConnectUrl", json!(url));
+        }
+        SecurityKind::MutualTls => {
+            insert("type", json!("mutualTLS"));
+        }
+        SecurityKind::Other => {}
+    }
+    insert_some(&mut object, "description", &scheme.description);
+    Value::Object(object)
+}
+
+fn ref_or_value<T>(member: &RefOr<T>, write: impl FnOnce(&T) -> Value) -> Value {
+    match member {
+        RefOr::Ref(reference) => {
+            let mut object = reference.rest.clone();
+            object.insert("$ref".to_string(), json!(reference.location));
+            Value::Object(object)
+        }
+        RefOr::T(member) => write(member),
+    }
+}
+
+fn insert_some<T: serde::Serialize>(object: &mut Map<String, Value>, key: &str, value: &Option<T>) {
+    if let Some(value) = value {
+        object.insert(key.to_string(), json!(value));
+    }
+}
+
+fn insert_true(object: &mut Map<String, Value>, key: &str, value: bool) {
+    if value {
+        object.insert(key.to_string(), json!(true));
+    }
+}
+
+fn insert_non_empty(object: &mut Map<String, Value>, key: &str, value: Value) {
+    let empty = match &value {
+        Value::Object(members) => members.is_empty(),
+        Value::Array(values) => values.is_empty(),
+        _ => false,
+    };
+    if !empty {
+        object.insert(key.to_string(), value);
+    }
+}
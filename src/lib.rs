@@ -1,4 +1,280 @@
+use std::{collections::BTreeMap, io, sync::Arc};
+
+use diagnostics::Diagnostic;
+use utoipa::openapi::OpenApi;
+
+mod anonymize;
+#[cfg(feature = "tokio")]
+mod async_convert;
+pub mod codegen;
+pub mod compare;
+pub mod coverage;
+mod cycle;
+mod deprecated;
+pub mod diagnostics;
+mod discriminator;
+mod dotnet;
+mod error;
+pub mod export;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod flatten;
+mod formats;
+mod go_swagger;
+mod grpc_gateway;
+pub mod har;
+pub mod ir;
+mod media_type;
+pub mod mock;
+pub mod normalize;
+mod options;
+pub mod output;
+mod parallel;
+mod partial;
+pub mod prune;
+#[cfg(feature = "python")]
+mod python;
+pub mod sanitize;
+pub mod sink;
 pub mod spec;
+pub mod stream;
+mod trace;
+#[cfg(any(feature = "utoipa4", feature = "utoipa5"))]
+mod utoipa_versions;
+mod validate;
+mod walk;
+
+#[cfg(feature = "tokio")]
+pub use async_convert::{convert_async_reader, convert_url};
+pub use error::Error;
+pub use options::ConvertOptions;
+
+/// Result of a conversion together with the findings reported along the way.
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Conversion {
+    pub openapi: OpenApi,
+    pub diagnostics: Vec<Diagnostic>,
+    output: output::OutputOptions,
+    source_order: output::SourceOrder,
+    provenance: Option<output::Provenance>,
+    additions: spec::Additions,
+}
+
+impl Conversion {
+    /// The converted document as it is written out, with the [`output`] options applied and
+    /// without empty containers like `components: {}`.
+    ///
+    /// Object members are sorted by key, so identical input results in identical output.
+    /// Serializing [`Conversion::openapi`] directly does not guarantee that, utoipa keeps
+    /// extensions in a `HashMap`.
+    pub fn to_value(&self) -> serde_json::Value {
+        trace::phase("serialize", || {
+            let mut document = serde_json::to_value(&self.openapi).unwrap_or_default();
+            self.additions.apply(&mut document);
+            output::finalize(&mut document, &self.output);
+            if let Some(provenance) = &self.provenance {
+                document["x-converted-from"] = serde_json::json!(provenance);
+            }
+            document
+        })
+    }
+
+    /// [`Conversion::to_value`] as [`ir::Document`], a model owned by this crate, e.g. for
+    /// callers which depend on another version of utoipa than this crate.
+    pub fn to_document(&self) -> ir::Document {
+        ir::Document::converted(self.to_value())
+    }
+
+    /// Splits [`Conversion::to_value`] into the main document and a file per component
+    /// schema as given by `layout`, referencing each other by relative `$ref`s.
+    ///
+    /// Returns the documents by their path relative to the main document.
+    pub fn to_files(&self, layout: &output::Layout) -> BTreeMap<String, serde_json::Value> {
+        output::split(self.to_value(), layout)
+    }
+
+    /// Writes [`Conversion::to_value`] in the [`Syntax`](output::Syntax) of the output
+    /// options, with the paths and component schemas in their [`Order`](output::Order).
+    pub fn to_writer(&self, writer: impl io::Write) -> Result<(), Error> {
+        let document = self.to_value();
+        let document = output::ordered(&document, self.output.order, &self.source_order);
+        sink::Sink::new(writer, self.output.syntax).write(&document)
+    }
+
+    /// Like [`Conversion::to_writer`], but writes to a tokio
+    /// [`AsyncWrite`](tokio::io::AsyncWrite).
+    #[cfg(feature = "tokio")]
+    pub async fn to_async_writer(
+        &self,
+        writer: impl tokio::io::AsyncWrite + Unpin,
+    ) -> Result<(), Error> {
+        let document = self.to_value();
+        let document = output::ordered(&document, self.output.order, &self.source_order);
+        sink::AsyncSink::new(writer, self.output.syntax)
+            .write(&document)
+            .await
+    }
+}
+
+/// Converts a Swagger 2.0 document into OpenAPI 3.0, collecting the conversion diagnostics.
+pub fn convert(swagger: spec::Swagger) -> Conversion {
+    convert_with(swagger, &ConvertOptions::default())
+}
+
+/// Like [`convert`], but with custom [`ConvertOptions`].
+pub fn convert_with(swagger: spec::Swagger, options: &ConvertOptions) -> Conversion {
+    let ((openapi, additions), diagnostics) = options::scope(Arc::new(options.clone()), || {
+        diagnostics::collect(|| {
+            let swagger = prepare(swagger, options);
+            let mut swagger = media_type::check(swagger);
+            let additions = spec::Additions::take(&mut swagger);
+            (finish(spec::convert_checked(swagger), options), additions)
+        })
+    });
+    trace::count("diagnostics", diagnostics.len());
+    Conversion {
+        openapi,
+        diagnostics,
+        output: options.output.clone(),
+        source_order: Default::default(),
+        provenance: options.output.stamp.then(|| output::Provenance::new(None)),
+        additions,
+    }
+}
+
+/// Runs the passes over the JSON form of `swagger` enabled in `options`. The document is
+/// serialized and parsed only once for all of them, and not at all if none is enabled.
+/// Unused shared responses are reported from `swagger` itself unless a pass needs the JSON
+/// form anyway.
+///
+/// A document which no longer parses afterwards is reported as error and converted without
+/// the changes of the passes, or replaced with a placeholder when anonymizing.
+fn prepare(swagger: spec::Swagger, options: &ConvertOptions) -> spec::Swagger {
+    let rewrites = options.grpc_gateway
+        || options.go_swagger
+        || options.dotnet
+        || options.exclude_deprecated
+        || options.normalize_formats
+        || options.validate_defaults
+        || options.anonymize
+        || options.prune.responses;
+    if !rewrites && !options.validate_examples {
+        prune::report_responses(&swagger, options.prune);
+        return swagger;
+    }
+    let failed = |swagger: spec::Swagger, err: serde_json::Error| {
+        if options.anonymize {
+            diagnostics::error(format!(
+                "dropped the whole document, the anonymized document does not parse: {err}"
+            ));
+            anonymize::placeholder()
+        } else {
+            diagnostics::error(format!(
+                "converted the document unchanged, the prepared document does not parse: {err}"
+            ));
+            swagger
+        }
+    };
+    let mut document = match serde_json::to_value(&swagger) {
+        Ok(document) => document,
+        Err(err) => return failed(swagger, err),
+    };
+
+    if options.grpc_gateway {
+        grpc_gateway::adapt(&mut document);
+    }
+    if options.go_swagger {
+        go_swagger::adapt(&mut document);
+    }
+    if options.dotnet {
+        dotnet::adapt(&mut document);
+    }
+    if options.exclude_deprecated {
+        deprecated::exclude(&mut document);
+    }
+    if options.normalize_formats {
+        formats::normalize(&mut document);
+    }
+    if options.validate_defaults {
+        validate::defaults(&mut document);
+    }
+    if options.validate_examples {
+        validate::examples(&document);
+    }
+    if options.anonymize {
+        anonymize::anonymize(&mut document);
+    }
+    prune::responses(&mut document, options.prune);
+
+    if !rewrites {
+        return swagger;
+    }
+    match serde_json::from_value(document) {
+        Ok(prepared) => prepared,
+        Err(err) => failed(swagger, err),
+    }
+}
+
+/// Parses and converts a Swagger 2.0 JSON document.
+///
+/// Diagnostics reported while parsing precede the ones of the conversion. With
+/// [`ConvertOptions::partial`] set, path items and definitions which fail to parse are
+/// skipped and reported as errors instead of failing the whole document.
+pub fn convert_str(swagger: &str, options: &ConvertOptions) -> Result<Conversion, Error> {
+    let (swagger, mut diagnostics) = diagnostics::collect(|| {
+        trace::phase("parse", || {
+            if options.partial {
+                let mut document: serde_json::Value = serde_json::from_str(swagger)?;
+                partial::prune(&mut document);
+                serde_json::from_value::<spec::Swagger>(document)
+            } else {
+                serde_json::from_str(swagger)
+            }
+        })
+    });
+    let mut conversion = convert_with(swagger.map_err(Error::Parse)?, options);
+    diagnostics.append(&mut conversion.diagnostics);
+    conversion.diagnostics = diagnostics;
+    if options.output.order == output::Order::Source {
+        conversion.source_order = output::SourceOrder::parse(swagger);
+    }
+    if options.output.stamp {
+        conversion.provenance = Some(output::Provenance::new(Some(swagger)));
+    }
+    Ok(conversion)
+}
+
+/// Applies the passes over the whole converted document enabled in `options`.
+fn finish(mut openapi: OpenApi, options: &ConvertOptions) -> OpenApi {
+    spec::require_security(
+        &mut openapi,
+        &options.required_security,
+        options.required_security_target,
+    );
+    prune::unused(&mut openapi, options.prune);
+    if options.examples {
+        openapi = mock::inject(openapi, cycle::max_depth());
+    }
+    openapi
+}
+
+/// Converts a Swagger 2.0 JSON document into a pretty printed OpenAPI 3.0 JSON document,
+/// like [`convert_str`] with the default options followed by [`Conversion::to_value`]. The
+/// diagnostics are dropped.
+///
+/// The output is deterministic, see [`Conversion::to_value`].
+pub fn convert_json(swagger: &str) -> Result<String, Error> {
+    let openapi = convert_str(swagger, &ConvertOptions::default())?.to_value();
+    serde_json::to_string_pretty(&openapi).map_err(Error::Serialize)
+}
+
+/// Converts a Swagger 2.0 document held as JSON value into an OpenAPI 3.0 JSON value, like
+/// [`convert`] followed by [`Conversion::to_value`]. The diagnostics are dropped, use
+/// [`convert`] to inspect them.
+pub fn convert_value(swagger: serde_json::Value) -> Result<serde_json::Value, Error> {
+    let swagger: spec::Swagger = serde_json::from_value(swagger).map_err(Error::Parse)?;
+    Ok(convert(swagger).to_value())
+}
 
 #[cfg(test)]
 #[macro_export]
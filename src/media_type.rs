@@ -0,0 +1,151 @@
+//! Validation of the media types listed by `consumes` and `produces`.
+//!
+//! Media types are matched case-insensitively, so their type, subtype and parameter names
+//! are lowercased, e.g. `application/JSON` becomes `application/json`. Entries which are no
+//! media type at all, like `json`, are reported and kept as they are.
+
+use crate::{
+    diagnostics,
+    spec::{self, PathItem, Swagger},
+};
+
+/// Shorthands sometimes written instead of a media type, with the media type meant.
+const SHORTHANDS: [(&str, &str); 5] = [
+    ("json", "application/json"),
+    ("xml", "application/xml"),
+    ("form", "application/x-www-form-urlencoded"),
+    ("text", "text/plain"),
+    ("html", "text/html"),
+];
+
+/// Checks the root and operation level `consumes` and `produces` of `swagger`, normalizing
+/// the casing of their media types.
+pub(crate) fn check(mut swagger: Swagger) -> Swagger {
+    check_list(&mut swagger.consumes, "/consumes");
+    check_list(&mut swagger.produces, "/produces");
+    for (path, path_item) in swagger.paths.paths.iter_mut() {
+        check_path_item(path, path_item);
+    }
+    swagger
+}
+
+/// Checks the `consumes` and `produces` of the operations of the item of `path`.
+pub(crate) fn check_path_item(path: &str, path_item: &mut PathItem) {
+    let pointer = format!("/paths/{}", diagnostics::escape(path));
+    for (method, operation) in path_item.operations.iter_mut() {
+        let pointer = format!("{pointer}/{}", spec::method_name(method));
+        check_list(&mut operation.consumes, &format!("{pointer}/consumes"));
+        check_list(&mut operation.produces, &format!("{pointer}/produces"));
+    }
+}
+
+fn check_list(media_types: &mut Option<Vec<String>>, pointer: &str) {
+    for (i, media_type) in media_types.iter_mut().flatten().enumerate() {
+        diagnostics::at(&format!("{pointer}/{i}"), || match normalized(media_type) {
+            Some(normalized) if normalized != *media_type => {
+                diagnostics::warn(format!(
+                    "normalized media type `{media_type}` to `{normalized}`"
+                ));
+                *media_type = normalized;
+            }
+            Some(_) => {}
+            None => {
+                let hint = SHORTHANDS
+                    .iter()
+                    .find(|(shorthand, _)| media_type.trim().eq_ignore_ascii_case(shorthand))
+                    .map(|(_, meant)| format!(", did you mean `{meant}`?"))
+                    .unwrap_or_default();
+                diagnostics::warn(format!("`{media_type}` is not a valid media type{hint}"));
+            }
+        });
+    }
+}
+
+/// `media_type` with lowercase type, subtype and parameter names and without superfluous
+/// whitespace, or `None` if it is not of the form `type/subtype; name=value`.
+fn normalized(media_type: &str) -> Option<String> {
+    let mut parts = media_type.split(';');
+    let (type_name, subtype) = parts.next()?.trim().split_once('/')?;
+    let wildcard_or_token = |name: &str| name == "*" || is_token(name);
+    if !wildcard_or_token(type_name)
+        || !wildcard_or_token(subtype)
+        || type_name == "*" && subtype != "*"
+    {
+        return None;
+    }
+    let mut normalized = format!("{type_name}/{subtype}").to_ascii_lowercase();
+    for parameter in parts {
+        let (name, value) = parameter.trim().split_once('=')?;
+        let quoted = value.len() >= 2 && value.starts_with('"') && value.ends_with('"');
+        if !is_token(name) || !(quoted || is_token(value)) {
+            return None;
+        }
+        normalized.push_str(&format!("; {}={value}", name.to_ascii_lowercase()));
+    }
+    Some(normalized)
+}
+
+/// Whether `name` is a restricted name of RFC 6838.
+fn is_token(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "!#$&-^_.+".contains(c))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    #[test]
+    fn check_media_types() {
+        let swagger = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "consumes": ["application/json", "json"],
+            "produces": ["application/JSON", "text/plain;Charset=utf-8"],
+            "paths": {
+                "/pets": {
+                    "get": {"produces": ["*/*", "image/*", "*/png", "text//html"], "responses": {}}
+                }
+            }
+        }))
+        .unwrap();
+
+        let (swagger, diagnostics) = crate::diagnostics::collect(|| super::check(swagger));
+
+        assert_eq!(
+            swagger.produces.unwrap(),
+            ["application/json", "text/plain; charset=utf-8"]
+        );
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|d| (d.pointer.as_str(), d.message.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "/consumes/1",
+                    "`json` is not a valid media type, did you mean `application/json`?"
+                ),
+                (
+                    "/produces/0",
+                    "normalized media type `application/JSON` to `application/json`"
+                ),
+                (
+                    "/produces/1",
+                    "normalized media type `text/plain;Charset=utf-8` to \
+                     `text/plain; charset=utf-8`"
+                ),
+                (
+                    "/paths/~1pets/get/produces/2",
+                    "`*/png` is not a valid media type"
+                ),
+                (
+                    "/paths/~1pets/get/produces/3",
+                    "`text//html` is not a valid media type"
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,395 @@
+//! Generation of example payloads from converted schemas.
+//!
+//! Examples respect `enum`, `format`, length and range limits, `multipleOf` and simple
+//! `pattern`s, and prefer any `example` or `default` already present in the document. With
+//! [`ConvertOptions::examples`](crate::ConvertOptions::examples) set, they are added to
+//! component schemas and media types which have no example of their own.
+
+use serde_json::{json, Map, Value};
+use utoipa::openapi::OpenApi;
+
+use crate::{
+    cycle::{self, RefStack},
+    diagnostics,
+};
+
+/// Maximum nesting of generated examples, deeper values are left out.
+const MAX_DEPTH: usize = 8;
+
+/// Generates an example for `schema`, resolving local `$ref`s against the document `root`.
+pub fn example(schema: &Value, root: &Value) -> Value {
+    generate(schema, root, cycle::max_depth())
+}
+
+fn generate(schema: &Value, root: &Value, max_ref_depth: usize) -> Value {
+    Generator {
+        root,
+        refs: RefStack::new(max_ref_depth),
+    }
+    .example(schema, None, 0)
+    .unwrap_or(Value::Null)
+}
+
+/// Adds generated examples to the component schemas and media types of `openapi`.
+pub(crate) fn inject(openapi: OpenApi, max_ref_depth: usize) -> OpenApi {
+    let Ok(mut root) = serde_json::to_value(&openapi) else {
+        return openapi;
+    };
+    let document = root.clone();
+
+    if let Some(schemas) = root
+        .pointer_mut("/components/schemas")
+        .and_then(Value::as_object_mut)
+    {
+        for (name, schema) in schemas.iter_mut() {
+            if schema.get("$ref").is_none() && schema.get("example").is_none() {
+                let pointer = format!("/definitions/{}", diagnostics::escape(name));
+                let example =
+                    diagnostics::at(&pointer, || generate(schema, &document, max_ref_depth));
+                schema["example"] = example;
+            }
+        }
+    }
+
+    if let Some(paths) = root.get_mut("paths").and_then(Value::as_object_mut) {
+        for (path, path_item) in paths.iter_mut() {
+            let Some(path_item) = path_item.as_object_mut() else {
+                continue;
+            };
+            for (method, operation) in path_item.iter_mut() {
+                let pointer = format!("/paths/{}/{method}", diagnostics::escape(path));
+                if let Some(content) = operation.pointer_mut("/requestBody/content") {
+                    diagnostics::at(&pointer, || {
+                        inject_content(content, &document, max_ref_depth)
+                    });
+                }
+                if let Some(responses) = operation
+                    .get_mut("responses")
+                    .and_then(Value::as_object_mut)
+                {
+                    for (status, response) in responses.iter_mut() {
+                        if let Some(content) = response.get_mut("content") {
+                            let pointer = format!("{pointer}/responses/{status}");
+                            diagnostics::at(&pointer, || {
+                                inject_content(content, &document, max_ref_depth)
+                            });
+                        }
+                    }
+                }
+            }
+        }
+    }
+    if let Some(responses) = root
+        .pointer_mut("/components/responses")
+        .and_then(Value::as_object_mut)
+    {
+        for (name, response) in responses.iter_mut() {
+            if let Some(content) = response.get_mut("content") {
+                let pointer = format!("/responses/{}", diagnostics::escape(name));
+                diagnostics::at(&pointer, || {
+                    inject_content(content, &document, max_ref_depth)
+                });
+            }
+        }
+    }
+
+    serde_json::from_value(root).unwrap_or(openapi)
+}
+
+fn inject_content(content: &mut Value, document: &Value, max_ref_depth: usize) {
+    let Some(content) = content.as_object_mut() else {
+        return;
+    };
+    for media_type in content.values_mut() {
+        let has_example = media_type.get("example").is_some()
+            || media_type.get("examples").is_some_and(|e| e != &json!({}));
+        if let (false, Some(schema)) = (has_example, media_type.get("schema")) {
+            let example = generate(schema, document, max_ref_depth);
+            media_type["example"] = example;
+        }
+    }
+}
+
+struct Generator<'a> {
+    root: &'a Value,
+    /// References being expanded, to stop at recursive schemas.
+    refs: RefStack,
+}
+
+impl<'a> Generator<'a> {
+    fn example(&mut self, schema: &'a Value, name: Option<&str>, depth: usize) -> Option<Value> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        if let Some(location) = schema.get("$ref").and_then(Value::as_str) {
+            let target = location
+                .strip_prefix('#')
+                .and_then(|pointer| self.root.pointer(pointer))?;
+            if !self.refs.follow(location) {
+                return None;
+            }
+            let example = self.example(target, name, depth + 1);
+            self.refs.leave();
+            return example;
+        }
+
+        if let Some(example) = schema.get("example").or_else(|| schema.get("default")) {
+            return Some(example.clone());
+        }
+        if let Some(first) = schema
+            .get("enum")
+            .and_then(Value::as_array)
+            .and_then(|e| e.first())
+        {
+            return Some(first.clone());
+        }
+        if let Some(all_of) = schema.get("allOf").and_then(Value::as_array) {
+            let mut merged = Map::new();
+            for item in all_of {
+                if let Some(Value::Object(object)) = self.example(item, name, depth + 1) {
+                    merged.extend(object);
+                }
+            }
+            return Some(Value::Object(merged));
+        }
+        for key in ["oneOf", "anyOf"] {
+            if let Some(first) = schema
+                .get(key)
+                .and_then(Value::as_array)
+                .and_then(|s| s.first())
+            {
+                return self.example(first, name, depth + 1);
+            }
+        }
+
+        let value = match schema.get("type").and_then(Value::as_str) {
+            Some("string") => Value::String(string(schema, name)),
+            Some("integer") => json!(number(schema, true) as i64),
+            Some("number") => json!(number(schema, false)),
+            Some("boolean") => json!(true),
+            Some("array") => {
+                let count = uint(schema, "minItems").unwrap_or(1).max(1);
+                let item = match schema.get("items") {
+                    Some(items) => self.example(items, name, depth + 1),
+                    None => None,
+                };
+                Value::Array(item.map(|item| vec![item; count]).unwrap_or_default())
+            }
+            _ => {
+                let mut object = Map::new();
+                if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+                    for (property, property_schema) in properties {
+                        if let Some(value) =
+                            self.example(property_schema, Some(property.as_str()), depth + 1)
+                        {
+                            object.insert(property.clone(), value);
+                        }
+                    }
+                }
+                if let Some(values) = schema
+                    .get("additionalProperties")
+                    .filter(|values| values.is_object())
+                {
+                    if let Some(value) = self.example(values, None, depth + 1) {
+                        object.insert("key".to_string(), value);
+                    }
+                }
+                Value::Object(object)
+            }
+        };
+        Some(value)
+    }
+}
+
+fn uint(schema: &Value, key: &str) -> Option<usize> {
+    schema
+        .get(key)
+        .and_then(Value::as_u64)
+        .and_then(|n| n.try_into().ok())
+}
+
+fn string(schema: &Value, name: Option<&str>) -> String {
+    let format = schema.get("format").and_then(Value::as_str);
+    let value = match format {
+        Some("date") => "2024-01-01".to_string(),
+        Some("date-time") => "2024-01-01T12:00:00Z".to_string(),
+        Some("time") => "12:00:00".to_string(),
+        Some("email") => "user@example.com".to_string(),
+        Some("uuid") => "3fa85f64-5717-4562-b3fc-2c963f66afa6".to_string(),
+        Some("uri" | "url") => "https://example.com".to_string(),
+        Some("hostname") => "example.com".to_string(),
+        Some("ipv4") => "192.0.2.1".to_string(),
+        Some("ipv6") => "2001:db8::1".to_string(),
+        Some("byte") => "ZXhhbXBsZQ==".to_string(),
+        Some("password") => "********".to_string(),
+        _ => match schema
+            .get("pattern")
+            .and_then(Value::as_str)
+            .and_then(from_pattern)
+        {
+            Some(value) => return value,
+            None => from_name(name.unwrap_or_default()).to_string(),
+        },
+    };
+
+    let min = uint(schema, "minLength").unwrap_or(0);
+    let max = uint(schema, "maxLength").unwrap_or(usize::MAX);
+    let mut value: String = value.chars().take(max).collect();
+    while value.chars().count() < min {
+        value.push('x');
+    }
+    value
+}
+
+/// Plausible value for a string property based on its name.
+fn from_name(name: &str) -> &'static str {
+    let name = name.to_ascii_lowercase();
+    match name.as_str() {
+        n if n.contains("email") => "user@example.com",
+        n if n.ends_with("url") || n.ends_with("uri") => "https://example.com",
+        n if n.contains("phone") => "+1-555-0100",
+        n if n.contains("country") => "US",
+        n if n.contains("city") => "Springfield",
+        n if n.contains("name") => "Jane Doe",
+        n if n.ends_with("id") => "abc123",
+        _ => "string",
+    }
+}
+
+/// Number within `minimum` and `maximum` which is a multiple of `multipleOf`.
+fn number(schema: &Value, integer: bool) -> f64 {
+    let get = |key| schema.get(key).and_then(Value::as_f64);
+    let step = if integer { 1.0 } else { 0.5 };
+    let exclusive = |key| schema.get(key) == Some(&Value::Bool(true));
+
+    // exclusive bounds are either flags of the inclusive ones or numbers themselves
+    let min = get("minimum")
+        .map(|m| {
+            if exclusive("exclusiveMinimum") {
+                m + step
+            } else {
+                m
+            }
+        })
+        .or_else(|| get("exclusiveMinimum").map(|m| m + step));
+    let max = get("maximum")
+        .map(|m| {
+            if exclusive("exclusiveMaximum") {
+                m - step
+            } else {
+                m
+            }
+        })
+        .or_else(|| get("exclusiveMaximum").map(|m| m - step));
+    let mut value = match (min, max) {
+        (Some(min), _) => min,
+        (None, Some(max)) => max.min(1.0),
+        (None, None) => 1.0,
+    };
+    if let Some(multiple_of) = get("multipleOf").filter(|m| *m > 0.0) {
+        value = (value / multiple_of).ceil() * multiple_of;
+    }
+    if integer {
+        value = value.ceil();
+    }
+    value
+}
+
+/// String matching a simple regular expression, `None` for unsupported constructs.
+fn from_pattern(pattern: &str) -> Option<String> {
+    let pattern = pattern.strip_prefix('^').unwrap_or(pattern);
+    let pattern = pattern.strip_suffix('$').unwrap_or(pattern);
+    let mut chars = pattern.chars().peekable();
+    let mut out = String::new();
+
+    while let Some(c) = chars.next() {
+        let atom = match c {
+            '\\' => match chars.next()? {
+                'd' => '1',
+                'w' => 'a',
+                's' => ' ',
+                escaped @ ('.' | '-' | '/' | '\\' | '(' | ')' | '[' | ']' | '{' | '}' | '+'
+                | '*' | '?' | '^' | '$' | '|') => escaped,
+                _ => return None,
+            },
+            '[' => {
+                let mut class = String::new();
+                loop {
+                    match chars.next()? {
+                        ']' => break,
+                        '\\' => class.push(chars.next()?),
+                        c => class.push(c),
+                    }
+                }
+                if class.starts_with('^') {
+                    return None;
+                }
+                class.chars().next()?
+            }
+            '.' => 'a',
+            '(' | ')' | '|' | '*' | '+' | '?' | '{' | '}' => return None,
+            c => c,
+        };
+
+        let count = match chars.peek() {
+            Some('+' | '?' | '*') => {
+                chars.next();
+                1
+            }
+            Some('{') => {
+                chars.next();
+                let mut quantifier = String::new();
+                loop {
+                    match chars.next()? {
+                        '}' => break,
+                        c => quantifier.push(c),
+                    }
+                }
+                quantifier.split(',').next()?.trim().parse().ok()?
+            }
+            _ => 1,
+        };
+        out.extend(std::iter::repeat(atom).take(count));
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn generate_example() {
+        let root = json!({
+            "components": {
+                "schemas": {
+                    "Node": {
+                        "type": "object",
+                        "properties": {
+                            "email": {"type": "string"},
+                            "code": {"type": "string", "pattern": "^[A-Z]{3}-\\d{2}$"},
+                            "status": {"type": "string", "enum": ["active", "blocked"]},
+                            "count": {"type": "integer", "minimum": 3, "exclusiveMinimum": true, "multipleOf": 2},
+                            "created": {"type": "string", "format": "date-time"},
+                            "children": {"type": "array", "items": {"$ref": "#/components/schemas/Node"}}
+                        }
+                    }
+                }
+            }
+        });
+
+        assert_eq!(
+            example(&json!({"$ref": "#/components/schemas/Node"}), &root),
+            json!({
+                "email": "user@example.com",
+                "code": "AAA-11",
+                "status": "active",
+                "count": 4,
+                "created": "2024-01-01T12:00:00Z",
+                "children": []
+            })
+        );
+    }
+}
@@ -0,0 +1,264 @@
+//! Canonical form of OpenAPI documents, e.g. to compare documents written by different
+//! generators or to hash them.
+//!
+//! Normalizing rewrites a serialized document such that equivalent spellings become equal:
+//!
+//! - references to a component schema which is just a `$ref` point at its target instead
+//! - duplicate `enum` values are dropped
+//! - media types are lowercased and their parameters separated by `"; "`
+//! - path templates lose duplicate and trailing slashes and whitespace within parameters
+//! - an `allOf` of a single schema is replaced by the schema where that means the same
+//!
+//! Object members are sorted by key anyway, see [`Conversion::to_value`].
+//!
+//! [`Conversion::to_value`]: crate::Conversion::to_value
+
+use serde_json::{Map, Value};
+
+use crate::{
+    cycle::{self, RefStack},
+    diagnostics::{self, Diagnostic},
+    walk,
+};
+
+const SCHEMAS: &str = "#/components/schemas/";
+
+/// Rewrites the serialized OpenAPI `document` into its canonical form, see the [module
+/// documentation](self).
+///
+/// Returns the paths and media types which were dropped because they collide with another
+/// one once normalized.
+pub fn normalize(document: &mut Value) -> Vec<Diagnostic> {
+    let ((), diagnostics) = diagnostics::collect(|| {
+        let aliases = aliases(document);
+        if let Some(Value::Object(paths)) = document.get_mut("paths") {
+            diagnostics::scope("paths", || {
+                *paths = renamed(std::mem::take(paths), path_template);
+            });
+        }
+        walk::objects_mut(
+            document,
+            &mut String::new(),
+            &mut |object, pointer| keywords(object, &aliases, pointer),
+            &mut |object, _| single_all_of(object),
+        );
+    });
+    diagnostics
+}
+
+/// Targets of the component schemas which are just a `$ref` to another one, following
+/// chains of them. Chains ending in a cycle are left alone.
+fn aliases(document: &Value) -> Map<String, Value> {
+    let Some(Value::Object(schemas)) = document.pointer("/components/schemas") else {
+        return Map::new();
+    };
+
+    let mut aliases = Map::new();
+    for name in schemas.keys() {
+        let location = format!("{SCHEMAS}{name}");
+        let mut refs = RefStack::new(cycle::max_depth());
+        let mut target = location.clone();
+        let pointer = format!("/components/schemas/{}", diagnostics::escape(name));
+        diagnostics::at(&pointer, || {
+            let _ = refs.enter(&location);
+            while let Some(next) = target
+                .strip_prefix(SCHEMAS)
+                .and_then(|name| alias(schemas, name))
+            {
+                if refs.contains(next) {
+                    target = location.clone();
+                    break;
+                }
+                if !refs.follow(next) {
+                    break;
+                }
+                target = next.to_string();
+            }
+        });
+        if target != location {
+            aliases.insert(location, Value::String(target));
+        }
+    }
+    aliases
+}
+
+/// Normalizes the keywords of `object` at `pointer`, before the objects within it.
+fn keywords(object: &mut Map<String, Value>, aliases: &Map<String, Value>, pointer: &str) {
+    if let Some(Value::String(location)) = object.get("$ref") {
+        if let Some(target) = aliases.get(location) {
+            object.insert("$ref".to_string(), target.clone());
+        }
+    }
+    if let Some(Value::Array(values)) = object.get_mut("enum") {
+        let mut seen = Vec::new();
+        values.retain(|value| {
+            let duplicate = seen.contains(value);
+            if !duplicate {
+                seen.push(value.clone());
+            }
+            !duplicate
+        });
+    }
+    if let Some(Value::Object(content)) = object.get_mut("content") {
+        diagnostics::at(&format!("{pointer}/content"), || {
+            *content = renamed(std::mem::take(content), media_type);
+        });
+    }
+}
+
+/// Target of the component schema `name` if it is just a `$ref`.
+fn alias<'a>(schemas: &'a Map<String, Value>, name: &str) -> Option<&'a str> {
+    let schema = schemas.get(name)?.as_object()?;
+    let target = schema.get("$ref")?.as_str()?;
+    (schema.len() == 1).then_some(target)
+}
+
+/// `members` with their keys normalized by `normalize`. Members whose key collides with an
+/// earlier one are dropped and reported.
+fn renamed(members: Map<String, Value>, normalize: fn(&str) -> String) -> Map<String, Value> {
+    let mut renamed = Map::new();
+    for (key, value) in members {
+        let normalized = normalize(&key);
+        if renamed.contains_key(&normalized) {
+            diagnostics::scope(&key, || {
+                diagnostics::warn(format!("dropped, it is the same as `{normalized}`"))
+            });
+            continue;
+        }
+        renamed.insert(normalized, value);
+    }
+    renamed
+}
+
+fn path_template(path: &str) -> String {
+    let mut normalized = String::with_capacity(path.len() + 1);
+    let mut in_parameter = false;
+    for c in path.chars() {
+        match c {
+            '{' => in_parameter = true,
+            '}' => in_parameter = false,
+            '/' if normalized.ends_with('/') => continue,
+            c if in_parameter && c.is_whitespace() => continue,
+            _ => {}
+        }
+        normalized.push(c);
+    }
+    if !normalized.starts_with('/') {
+        normalized.insert(0, '/');
+    }
+    if normalized.len() > 1 && normalized.ends_with('/') {
+        normalized.pop();
+    }
+    normalized
+}
+
+fn media_type(media_type: &str) -> String {
+    media_type
+        .split(';')
+        .map(|part| {
+            part.split_whitespace()
+                .collect::<String>()
+                .to_ascii_lowercase()
+        })
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Replaces an `allOf` of a single schema with the schema, unless the other keywords of the
+/// schema holding it would change its meaning.
+fn single_all_of(object: &mut Map<String, Value>) {
+    let member = match object.get("allOf") {
+        Some(Value::Array(members)) if members.len() == 1 => match &members[0] {
+            Value::Object(member) => member,
+            _ => return,
+        },
+        _ => return,
+    };
+    // keywords next to `$ref` are ignored in OpenAPI 3.0
+    let keeps_meaning = if member.contains_key("$ref") {
+        object.len() == 1
+    } else {
+        member.keys().all(|key| !object.contains_key(key))
+    };
+    if keeps_meaning {
+        if let Some(Value::Array(mut members)) = object.remove("allOf") {
+            if let Some(Value::Object(member)) = members.pop() {
+                object.extend(member);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn normalize_document() {
+        let mut document = json!({
+            "openapi": "3.0.3",
+            "paths": {
+                "/pets/{ id }/": {
+                    "get": {
+                        "responses": {
+                            "200": {
+                                "description": "ok",
+                                "content": {
+                                    "Application/JSON ;charset=UTF-8": {
+                                        "schema": {"$ref": "#/components/schemas/Animal"}
+                                    }
+                                }
+                            }
+                        }
+                    }
+                },
+                "/pets/{id}/": {"delete": {"responses": {"204": {"description": "gone"}}}}
+            },
+            "components": {
+                "schemas": {
+                    "Animal": {"$ref": "#/components/schemas/Pet"},
+                    "Pet": {
+                        "type": "object",
+                        "properties": {
+                            "kind": {"type": "string", "enum": ["cat", "dog", "cat"]},
+                            "owner": {
+                                "description": "The owner",
+                                "allOf": [{"type": "string"}]
+                            }
+                        }
+                    }
+                }
+            }
+        });
+
+        let diagnostics = normalize(&mut document);
+
+        let content = json!({
+            "application/json; charset=utf-8": {"schema": {"$ref": "#/components/schemas/Pet"}}
+        });
+        assert_eq!(
+            document["paths"]["/pets/{id}"]["get"]["responses"]["200"]["content"],
+            content
+        );
+        assert_eq!(
+            document["components"]["schemas"]["Pet"]["properties"],
+            json!({
+                "kind": {"type": "string", "enum": ["cat", "dog"]},
+                "owner": {"description": "The owner", "type": "string"}
+            })
+        );
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|d| (d.pointer.as_str(), d.message.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(
+                "/paths/~1pets~1{id}~1",
+                "dropped, it is the same as `/pets/{id}`"
+            )]
+        );
+    }
+}
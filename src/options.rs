@@ -0,0 +1,230 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use utoipa::openapi::{
+    info::{Contact, License},
+    server::ServerVariable,
+    ExternalDocs,
+};
+
+use crate::{
+    output::OutputOptions,
+    prune::PruneOptions,
+    sanitize::SanitizeOptions,
+    spec::{
+        self, BasePath, ExtensionRegistry, OperationIdTemplate, RefNamespace, RequiredSecurity,
+        SecurityTarget,
+    },
+};
+
+/// Options controlling how a Swagger document is converted.
+#[derive(Default, Clone)]
+pub struct ConvertOptions {
+    /// Decides which vendor extensions are emitted and how.
+    pub extensions: ExtensionRegistry,
+    /// Normalizations applied to description texts.
+    pub sanitize: SanitizeOptions,
+    /// Convert structurally identical schemas only once and reuse the result.
+    pub cache_schemas: bool,
+    /// Add generated examples to schemas and media types without one, see [`crate::mock`].
+    pub examples: bool,
+    /// Maximum nesting of `$ref`s followed by any pass, e.g. when validating examples or
+    /// generating them, 32 if unset. References nested deeper than that are not followed
+    /// and reported as warning. Cycles are not followed either.
+    pub max_ref_depth: Option<usize>,
+    /// Skip path items and definitions which fail to parse, see [`crate::convert_str`].
+    pub partial: bool,
+    /// Accept constructs Swagger 2.0 forbids but which tools commonly emit, when OpenAPI 3.0
+    /// can represent them:
+    ///
+    /// - status code ranges like `2XX` are kept instead of dropped,
+    /// - enum values of the wrong type, like `"1"` in an integer enum, are coerced instead of
+    ///   dropped,
+    /// - with [`ConvertOptions::validate_defaults`], defaults of the wrong type are coerced
+    ///   instead of reported.
+    pub lenient: bool,
+    /// Drop operations and schema properties marked `deprecated` or `x-deprecated`, reporting
+    /// each of them. Not supported by [`crate::stream`].
+    pub exclude_deprecated: bool,
+    /// Rewrite `date-time` examples and defaults to RFC 3339 and lowercase UUID examples,
+    /// reporting the values which do not match their `format`. Not supported by
+    /// [`crate::stream`].
+    pub normalize_formats: bool,
+    /// Report defaults which do not match the schema or parameter declaring them. Defaults
+    /// of the wrong type are coerced instead when converting leniently. Not supported by
+    /// [`crate::stream`].
+    pub validate_defaults: bool,
+    /// Report examples which do not match the schema or parameter they belong to. Not
+    /// supported by [`crate::stream`].
+    pub validate_examples: bool,
+    /// Replace descriptions, examples, contact details and the host with placeholders, e.g.
+    /// to share a proprietary document in a bug report. Not supported by [`crate::stream`].
+    pub anonymize: bool,
+    /// Handle the quirks of documents generated by protoc-gen-swagger for grpc-gateway, e.g.
+    /// map `protobufAny` to the JSON form of `google.protobuf.Any`. Not supported by
+    /// [`crate::stream`].
+    pub grpc_gateway: bool,
+    /// Map the extensions of documents generated by go-swagger, e.g. `x-isnullable` onto
+    /// `nullable`. Not supported by [`crate::stream`].
+    pub go_swagger: bool,
+    /// Normalize the conventions of documents generated by Swashbuckle or NSwag, e.g. rename
+    /// duplicate `operationId`s. Not supported by [`crate::stream`].
+    pub dotnet: bool,
+    /// Rewrite every `operationId` with this template.
+    pub operation_ids: Option<OperationIdTemplate>,
+    /// Security requirements every converted document must carry.
+    pub required_security: Vec<RequiredSecurity>,
+    /// Whether [`ConvertOptions::required_security`] applies to the document or to the
+    /// operations without requirements.
+    pub required_security_target: SecurityTarget,
+    /// License of the API, replacing the one of the document.
+    pub license: Option<License>,
+    /// Contact of the API, replacing the one of the document.
+    pub contact: Option<Contact>,
+    /// URL of the terms of service of the API, replacing the one of the document.
+    pub terms_of_service: Option<String>,
+    /// External docs of the document, replacing the ones of the document.
+    pub external_docs: Option<ExternalDocs>,
+    /// External docs by tag name, replacing the ones of the tags of the document. Tags the
+    /// document does not declare are added.
+    pub tag_external_docs: BTreeMap<String, ExternalDocs>,
+    /// Host the servers are generated with instead of the `host` or `x-hosts` of the document.
+    pub host: Option<String>,
+    /// Change of the `basePath` the servers are generated from. Servers are only generated
+    /// for documents with a `host`, or with [`ConvertOptions::host`] set.
+    pub base_path: Option<BasePath>,
+    /// Server variables by name, declaring the placeholders of a templated host or base path
+    /// like `{region}.api.example.com`. They replace the ones of `x-server-variables`.
+    pub server_variables: BTreeMap<String, ServerVariable>,
+    /// Where rewritten references point.
+    pub refs: RefNamespace,
+    /// Unused components to drop. Unused components are reported either way.
+    pub prune: PruneOptions,
+    /// Options of the written document, see [`Conversion::to_value`](crate::Conversion::to_value).
+    pub output: OutputOptions,
+}
+
+/// Number of the last conversion started by [`scope`].
+static CONVERSIONS: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    static CURRENT: RefCell<Option<Arc<ConvertOptions>>> = const { RefCell::new(None) };
+    static DEFAULT: Arc<ConvertOptions> = Arc::new(ConvertOptions::default());
+    /// Number of the conversion running on this thread, 0 outside of one.
+    static CONVERSION: Cell<u64> = const { Cell::new(0) };
+    /// Number of the conversion the caches of this thread belong to.
+    static CACHED: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Runs `f` with `options` installed as the options of the current conversion.
+///
+/// Conversion caches depend on the options, so they are dropped when entering and leaving.
+pub(crate) fn scope<T>(options: Arc<ConvertOptions>, f: impl FnOnce() -> T) -> T {
+    let conversion = CONVERSIONS.fetch_add(1, Ordering::Relaxed) + 1;
+    let _restore = Restore {
+        options: CURRENT.with(|c| c.replace(Some(options))),
+        conversion: CONVERSION.replace(conversion),
+        cached: Some(0),
+    };
+    spec::clear_caches();
+    CACHED.set(conversion);
+    f()
+}
+
+/// The conversion running on this thread, to continue parts of it on other threads with
+/// [`resume`].
+#[cfg_attr(not(feature = "parallel"), allow(dead_code))]
+pub(crate) struct Suspended {
+    options: Arc<ConvertOptions>,
+    conversion: u64,
+}
+
+/// Suspends the conversion running on this thread, see [`Suspended`].
+#[cfg_attr(not(feature = "parallel"), allow(dead_code))]
+pub(crate) fn suspend() -> Suspended {
+    Suspended {
+        options: current(),
+        conversion: CONVERSION.get(),
+    }
+}
+
+/// Runs `f` as part of the `suspended` conversion.
+///
+/// Unlike [`scope`], the caches are kept when leaving, so a thread converting many parts of
+/// the same conversion fills them once. They are dropped once it works on another conversion,
+/// and when leaving if the thread was running another one itself.
+#[cfg_attr(not(feature = "parallel"), allow(dead_code))]
+pub(crate) fn resume<T>(suspended: &Suspended, f: impl FnOnce() -> T) -> T {
+    let previous_conversion = CONVERSION.replace(suspended.conversion);
+    let _restore = Restore {
+        options: CURRENT.with(|c| c.replace(Some(suspended.options.clone()))),
+        conversion: previous_conversion,
+        cached: (previous_conversion != 0 && previous_conversion != suspended.conversion)
+            .then_some(previous_conversion),
+    };
+    if CACHED.get() != suspended.conversion {
+        spec::clear_caches();
+        CACHED.set(suspended.conversion);
+    }
+    f()
+}
+
+/// Reinstalls the conversion a thread ran before [`scope`] or [`resume`] when dropped, so it
+/// is also left if the conversion panics.
+struct Restore {
+    options: Option<Arc<ConvertOptions>>,
+    conversion: u64,
+    /// The conversion the caches belong to once left, `None` to keep them.
+    cached: Option<u64>,
+}
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        if let Some(cached) = self.cached {
+            spec::clear_caches();
+            CACHED.set(cached);
+        }
+        CONVERSION.set(self.conversion);
+        CURRENT.with(|c| c.replace(self.options.take()));
+    }
+}
+
+/// Options of the conversion running on this thread, or the defaults outside of [`scope`].
+pub(crate) fn current() -> Arc<ConvertOptions> {
+    CURRENT
+        .with(|c| c.borrow().clone())
+        .unwrap_or_else(|| DEFAULT.with(Arc::clone))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic;
+
+    use super::*;
+
+    #[test]
+    fn scope_restored_after_panic() {
+        let options = Arc::new(ConvertOptions {
+            host: Some("api.example.com".to_string()),
+            ..ConvertOptions::default()
+        });
+
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            scope(options, || {
+                let suspended = suspend();
+                resume(&suspended, || panic!("conversion failed"))
+            })
+        }));
+
+        assert!(result.is_err());
+        assert!(current().host.is_none());
+        assert_eq!(CONVERSION.get(), 0);
+        assert_eq!(CACHED.get(), 0);
+    }
+}
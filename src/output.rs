@@ -0,0 +1,896 @@
+//! Options for the document written out after the conversion.
+//!
+//! The converted [`OpenApi`](utoipa::openapi::OpenApi) is an OpenAPI 3.0 document. Output
+//! options are applied to its serialized form in [`crate::Conversion::to_value`], which
+//! allows targeting versions the typed model cannot represent.
+//!
+//! JSON values sort object members by key, so the [`Order`] of paths and component schemas
+//! is applied when writing the document in [`crate::Conversion::to_writer`], along with its
+//! [`Syntax`].
+
+use std::{collections::BTreeMap, fmt};
+
+use serde::{
+    de::{self, IgnoredAny, MapAccess},
+    ser::SerializeMap,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    export::{self, Draft},
+    walk,
+};
+
+/// OpenAPI version of the written document.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    #[default]
+    V3_0,
+    /// OpenAPI 3.1, with schemas in the JSON Schema 2020-12 dialect.
+    V3_1,
+}
+
+/// Default `jsonSchemaDialect` of OpenAPI 3.1 documents.
+pub const OAS_BASE_DIALECT: &str = "https://spec.openapis.org/oas/3.1/dialect/base";
+
+/// Order of the paths and component schemas of the written document.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    #[default]
+    Alphabetical,
+    /// The order of the input document. Only known when converting from a string, e.g. with
+    /// [`crate::convert_str`], alphabetical otherwise.
+    Source,
+    /// Paths grouped by the first tag of their operations, in the order of the top level
+    /// `tags` followed by undeclared tags. Untagged paths come last.
+    Tag,
+}
+
+/// Serialization of the written document, see [`crate::sink`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Syntax {
+    /// Indented JSON.
+    #[default]
+    Json,
+    /// JSON without any whitespace.
+    CompactJson,
+    #[cfg(feature = "yaml")]
+    Yaml,
+}
+
+/// Options applied when writing the converted document.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct OutputOptions {
+    pub target: Target,
+    pub order: Order,
+    pub syntax: Syntax,
+    /// `jsonSchemaDialect` of OpenAPI 3.1 output, [`OAS_BASE_DIALECT`] if unset.
+    pub dialect: Option<String>,
+    /// Also declare the dialect as `$schema` of every component schema of OpenAPI 3.1 output,
+    /// for tools extracting them as standalone schemas.
+    pub schema_dialect: bool,
+    /// Add a [`Provenance`] as `x-converted-from` to the root of the document.
+    pub stamp: bool,
+    /// Drop descriptions, examples and external docs, e.g. for gateways which only need
+    /// the structure and constraints. Responses keep an empty description, which is required.
+    pub slim: bool,
+}
+
+/// Which tool and input produced a document, see [`OutputOptions::stamp`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Provenance {
+    pub tool: String,
+    pub version: String,
+    /// 64 bit FNV-1a hash of the input, only known when converting from a string.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_hash: Option<String>,
+    /// Time of the conversion in RFC 3339 format.
+    pub timestamp: String,
+}
+
+impl Provenance {
+    pub(crate) fn new(source: Option<&str>) -> Self {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default();
+        Self {
+            tool: env!("CARGO_PKG_NAME").to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            source_hash: source.map(|source| format!("fnv1a64:{:016x}", fnv1a(source))),
+            timestamp: rfc3339(now),
+        }
+    }
+}
+
+fn fnv1a(source: &str) -> u64 {
+    source.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Formats seconds since the Unix epoch as UTC date and time.
+fn rfc3339(seconds: u64) -> String {
+    let (days, time) = (seconds / 86400, seconds % 86400);
+    // civil date from days since 1970-01-01, http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719468;
+    let era = z / 146097;
+    let day_of_era = z % 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+/// Files a document is split into by [`crate::Conversion::to_files`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layout {
+    /// File name of the main document.
+    pub document: String,
+    /// Directory of the component schema files, relative to the main document.
+    pub schemas: String,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self {
+            document: "openapi.json".to_string(),
+            schemas: "schemas".to_string(),
+        }
+    }
+}
+
+/// Order of the paths and definitions of an input document.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+pub(crate) struct SourceOrder {
+    #[serde(default, deserialize_with = "keys")]
+    paths: Vec<String>,
+    #[serde(default, deserialize_with = "keys")]
+    definitions: Vec<String>,
+}
+
+impl SourceOrder {
+    /// Reads the order from a Swagger document, skipping over everything else.
+    pub(crate) fn parse(swagger: &str) -> Self {
+        serde_json::from_str(swagger).unwrap_or_default()
+    }
+}
+
+fn keys<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    struct Keys;
+
+    impl<'de> de::Visitor<'de> for Keys {
+        type Value = Vec<String>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a map")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut keys = Vec::new();
+            while let Some(key) = map.next_key::<String>()? {
+                map.next_value::<IgnoredAny>()?;
+                keys.push(key);
+            }
+            Ok(keys)
+        }
+    }
+
+    deserializer.deserialize_map(Keys)
+}
+
+/// Applies `options` to the serialized converted document.
+pub(crate) fn finalize(document: &mut Value, options: &OutputOptions) {
+    if options.slim {
+        slim(document);
+    }
+    omit_empty(document);
+    match options.target {
+        Target::V3_0 => visit_schemas(document, &mut export::boolean_exclusive_bounds),
+        Target::V3_1 => {
+            let dialect = options.dialect.as_deref().unwrap_or(OAS_BASE_DIALECT);
+            document["openapi"] = json!("3.1.0");
+            document["jsonSchemaDialect"] = json!(dialect);
+            visit_schemas(document, &mut |schema| {
+                export::to_dialect(schema, Draft::Draft2020_12)
+            });
+            if let (true, Some(Value::Object(schemas))) = (
+                options.schema_dialect,
+                document.pointer_mut("/components/schemas"),
+            ) {
+                for schema in schemas.values_mut().filter_map(Value::as_object_mut) {
+                    schema.insert("$schema".to_string(), json!(dialect));
+                }
+            }
+        }
+    }
+}
+
+/// Drops the documentation of every object within `value`, see [`OutputOptions::slim`].
+fn slim(value: &mut Value) {
+    match value {
+        Value::Object(object) => {
+            for key in ["description", "example", "examples", "externalDocs"] {
+                object.remove(key);
+            }
+            for (key, value) in object.iter_mut() {
+                match (key.as_str(), value) {
+                    (key, Value::Object(members)) if walk::NAMED.contains(&key) => {
+                        for (_, member) in walk::named_mut(key, members) {
+                            slim(member);
+                            if let ("responses", Value::Object(response)) = (key, member) {
+                                if !response.contains_key("$ref") {
+                                    response.insert("description".to_string(), json!(""));
+                                }
+                            }
+                        }
+                    }
+                    (key, _) if walk::is_value(key) => {}
+                    (_, value) => slim(value),
+                }
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(slim),
+        _ => {}
+    }
+}
+
+/// Drops empty containers which mean the same as their absence, e.g. empty `components` or
+/// `required: []`.
+///
+/// Empty `security` lists are kept, an empty list of an operation opts out of the top level
+/// requirements and documents that the operation needs no authentication either way.
+fn omit_empty(document: &mut Value) {
+    let Value::Object(root) = document else {
+        return;
+    };
+
+    if let Some(Value::Object(components)) = root.get_mut("components") {
+        components.retain(|_, members| !is_empty(members));
+    }
+    remove_empty(root, &["components", "servers", "tags"]);
+
+    for paths in ["paths", "webhooks"] {
+        let Some(Value::Object(paths)) = root.get_mut(paths) else {
+            continue;
+        };
+        for path_item in paths.values_mut().filter_map(Value::as_object_mut) {
+            remove_empty(path_item, &["parameters", "servers"]);
+            for operation in path_item.values_mut().filter_map(Value::as_object_mut) {
+                remove_empty(operation, &["tags", "parameters", "servers", "callbacks"]);
+                if let Some(Value::Object(responses)) = operation.get_mut("responses") {
+                    for response in responses.values_mut().filter_map(Value::as_object_mut) {
+                        remove_empty(response, &["content", "headers", "links"]);
+                    }
+                }
+            }
+        }
+    }
+    if let Some(Value::Object(responses)) = root
+        .get_mut("components")
+        .and_then(|components| components.get_mut("responses"))
+    {
+        for response in responses.values_mut().filter_map(Value::as_object_mut) {
+            remove_empty(response, &["content", "headers", "links"]);
+        }
+    }
+    remove_empty_required(document);
+}
+
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::Object(object) => object.is_empty(),
+        Value::Array(array) => array.is_empty(),
+        _ => false,
+    }
+}
+
+fn remove_empty(object: &mut Map<String, Value>, keys: &[&str]) {
+    for key in keys {
+        if object.get(*key).is_some_and(is_empty) {
+            object.remove(*key);
+        }
+    }
+}
+
+/// Drops `required: []` of schemas, other `required` members are booleans.
+fn remove_empty_required(document: &mut Value) {
+    walk::enter_objects(document, &mut String::new(), &mut |object, _| {
+        if object.get("required") == Some(&json!([])) {
+            object.remove("required");
+        }
+    });
+}
+
+const SCHEMAS: &str = "#/components/schemas/";
+
+/// Splits a finalized document into the main document and a file per component schema,
+/// keyed by their path relative to the main document.
+///
+/// The main document keeps the component schemas as references to their files, so tools
+/// resolving `#/components/schemas/...` still find them.
+pub(crate) fn split(mut document: Value, layout: &Layout) -> BTreeMap<String, Value> {
+    let schemas_dir = layout.schemas.trim_end_matches('/');
+    let up = "../".repeat(schemas_dir.split('/').filter(|s| !s.is_empty()).count());
+    let mut files = BTreeMap::new();
+
+    let schemas = match document.pointer_mut("/components/schemas") {
+        Some(Value::Object(schemas)) => std::mem::take(schemas),
+        _ => Map::new(),
+    };
+    for (name, mut schema) in schemas {
+        let file_name = export::file_name(&name);
+        rewrite_refs(
+            &mut schema,
+            &|location| match location.strip_prefix(SCHEMAS) {
+                Some(target) => schema_file(target, ""),
+                None => format!("{up}{}{location}", layout.document),
+            },
+        );
+        let path = match schemas_dir {
+            "" => file_name.clone(),
+            dir => format!("{dir}/{file_name}"),
+        };
+        document["components"]["schemas"][&name] = json!({ "$ref": path });
+        files.insert(path, schema);
+    }
+
+    rewrite_refs(
+        &mut document,
+        &|location| match location.strip_prefix(SCHEMAS) {
+            Some(target) => schema_file(target, schemas_dir),
+            None => location.to_string(),
+        },
+    );
+    files.insert(layout.document.clone(), document);
+    files
+}
+
+/// Relative reference to the file of the schema `target`, which may continue with a pointer.
+fn schema_file(target: &str, dir: &str) -> String {
+    let file = match target.split_once('/') {
+        Some((name, pointer)) => format!("{}#/{pointer}", export::file_name(name)),
+        None => export::file_name(target),
+    };
+    match dir {
+        "" => file,
+        dir => format!("{dir}/{file}"),
+    }
+}
+
+/// Replaces every local `$ref` of a value with `rewrite` of it.
+fn rewrite_refs(value: &mut Value, rewrite: &dyn Fn(&str) -> String) {
+    walk::enter_objects(value, &mut String::new(), &mut |object, _| {
+        if let Some(Value::String(location)) = object.get_mut("$ref") {
+            if location.starts_with('#') {
+                *location = rewrite(location);
+            }
+        }
+    });
+}
+
+/// Serializes a finalized document with its paths and component schemas in `order`.
+pub(crate) fn ordered<'a>(
+    document: &'a Value,
+    order: Order,
+    source: &'a SourceOrder,
+) -> impl Serialize + 'a {
+    let Value::Object(document) = document else {
+        return Member::Value(document);
+    };
+    let tags = match document.get("tags") {
+        Some(Value::Array(tags)) => tags
+            .iter()
+            .filter_map(|tag| tag.get("name").and_then(Value::as_str))
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    let members = document
+        .iter()
+        .map(|(key, value)| {
+            let member = match (key.as_str(), value) {
+                ("paths", Value::Object(paths)) => match order {
+                    Order::Alphabetical => Member::Value(value),
+                    Order::Source => arrange(paths, |path, _| position(&source.paths, path)),
+                    Order::Tag => arrange(paths, |_, path_item| tag_position(&tags, path_item)),
+                },
+                ("components", Value::Object(components)) if order == Order::Source => {
+                    Member::Object(
+                        components
+                            .iter()
+                            .map(|(key, value)| match (key.as_str(), value) {
+                                ("schemas", Value::Object(schemas)) => (
+                                    key,
+                                    arrange(schemas, |name, _| position(&source.definitions, name)),
+                                ),
+                                _ => (key, Member::Value(value)),
+                            })
+                            .collect(),
+                    )
+                }
+                _ => Member::Value(value),
+            };
+            (key, member)
+        })
+        .collect();
+    Member::Object(members)
+}
+
+/// Value whose object members are serialized in a given order.
+enum Member<'a> {
+    Value(&'a Value),
+    Object(Vec<(&'a String, Member<'a>)>),
+}
+
+impl Serialize for Member<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Member::Value(value) => value.serialize(serializer),
+            Member::Object(members) => {
+                let mut map = serializer.serialize_map(Some(members.len()))?;
+                for (key, member) in members {
+                    map.serialize_entry(key, member)?;
+                }
+                map.end()
+            }
+        }
+    }
+}
+
+/// Members of `object` ordered by `rank`, members of equal rank stay sorted by key.
+fn arrange<'a>(
+    object: &'a Map<String, Value>,
+    rank: impl Fn(&'a str, &'a Value) -> (usize, Option<&'a str>),
+) -> Member<'a> {
+    let mut members: Vec<_> = object.iter().collect();
+    members.sort_by_cached_key(|&(key, value)| (rank(key, value), key.as_str()));
+    Member::Object(
+        members
+            .into_iter()
+            .map(|(key, value)| (key, Member::Value(value)))
+            .collect(),
+    )
+}
+
+/// Rank of a key listed in `keys`, unlisted keys go last.
+fn position<'a>(keys: &[String], key: &str) -> (usize, Option<&'a str>) {
+    let index = keys.iter().position(|k| k == key);
+    (index.unwrap_or(usize::MAX), None)
+}
+
+/// Rank of a path item by the first tag of its operations within the declared `tags`.
+fn tag_position<'a>(tags: &[&str], path_item: &'a Value) -> (usize, Option<&'a str>) {
+    let tag = [
+        "get", "put", "post", "delete", "options", "head", "patch", "trace",
+    ]
+    .into_iter()
+    .filter_map(|method| path_item.get(method)?.get("tags")?.get(0)?.as_str())
+    .next();
+    match tag {
+        Some(tag) => match tags.iter().position(|t| *t == tag) {
+            Some(index) => (index, None),
+            None => (tags.len(), Some(tag)),
+        },
+        None => (usize::MAX, None),
+    }
+}
+
+/// Calls `visit` with every schema of the document which is not nested in another schema.
+fn visit_schemas(value: &mut Value, visit: &mut impl FnMut(&mut Value)) {
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object.iter_mut() {
+                match (key.as_str(), value) {
+                    ("schema", schema) => visit(schema),
+                    ("schemas", Value::Object(schemas)) => {
+                        schemas.values_mut().for_each(&mut *visit)
+                    }
+                    (key, Value::Object(members)) if walk::NAMED.contains(&key) => {
+                        for (_, member) in walk::named_mut(key, members) {
+                            visit_schemas(member, visit);
+                        }
+                    }
+                    (key, _) if walk::is_value(key) => {}
+                    (_, value) => visit_schemas(value, visit),
+                }
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(|v| visit_schemas(v, visit)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{spec::Swagger, ConvertOptions};
+
+    #[test]
+    fn target_openapi_3_1() {
+        let swagger: Swagger = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "parameters": [{"name": "limit", "in": "query", "type": "integer", "x-nullable": true}],
+                        "responses": {"200": {"description": "ok", "schema": {"$ref": "#/definitions/Pet"}}}
+                    }
+                }
+            },
+            "definitions": {
+                "Pet": {
+                    "type": "object",
+                    "properties": {
+                        "age": {"type": "integer", "minimum": 0, "exclusiveMinimum": true},
+                        "name": {"type": "string", "x-nullable": true, "example": "Rex"},
+                        "tags": {"type": "array", "items": {"type": "string", "x-nullable": true}}
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        let options = ConvertOptions {
+            output: OutputOptions {
+                target: Target::V3_1,
+                schema_dialect: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let document = crate::convert_with(swagger, &options).to_value();
+
+        assert_eq!(document["openapi"], "3.1.0");
+        assert_eq!(document["jsonSchemaDialect"], OAS_BASE_DIALECT);
+        assert_eq!(
+            document["components"]["schemas"]["Pet"]["$schema"],
+            OAS_BASE_DIALECT
+        );
+        assert_eq!(
+            document["components"]["schemas"]["Pet"]["properties"],
+            json!({
+                "age": {"type": "integer", "exclusiveMinimum": 0.0},
+                "name": {"type": ["string", "null"], "examples": ["Rex"]},
+                "tags": {"type": "array", "items": {"type": ["string", "null"]}}
+            })
+        );
+        assert_eq!(
+            document["paths"]["/pets"]["get"]["parameters"][0]["schema"],
+            json!({"type": ["integer", "null"]})
+        );
+    }
+
+    #[test]
+    fn pattern_properties() {
+        let swagger: Swagger = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {"title": "Labels", "version": "1.0"},
+            "paths": {},
+            "definitions": {
+                "Labels": {
+                    "type": "object",
+                    "x-patternProperties": {"^[a-z]+$": {"$ref": "#/definitions/Label"}}
+                },
+                "Label": {"type": "string"}
+            }
+        }))
+        .unwrap();
+        let convert = |target| {
+            let options = ConvertOptions {
+                output: OutputOptions {
+                    target,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            crate::convert_with(swagger.clone(), &options).to_value()["components"]["schemas"]
+                ["Labels"]
+                .clone()
+        };
+
+        let pattern_properties = json!({"^[a-z]+$": {"$ref": "#/components/schemas/Label"}});
+        assert_eq!(
+            convert(Target::V3_0)["x-patternProperties"],
+            pattern_properties
+        );
+        let labels = convert(Target::V3_1);
+        assert_eq!(labels["patternProperties"], pattern_properties);
+        assert!(labels.get("x-patternProperties").is_none());
+    }
+
+    #[test]
+    fn slim_profile() {
+        let mut document = json!({
+            "openapi": "3.0.3",
+            "info": {"title": "Pets", "version": "1.0", "description": "All about pets"},
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "description": "Lists pets",
+                        "externalDocs": {"url": "https://example.com"},
+                        "responses": {
+                            "200": {
+                                "description": "ok",
+                                "content": {"application/json": {"example": [{"name": "Rex"}]}}
+                            }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "Pet": {
+                        "type": "object",
+                        "description": "A pet",
+                        "properties": {
+                            "description": {"type": "string", "maxLength": 80, "example": "Cute"}
+                        }
+                    }
+                }
+            }
+        });
+        let options = OutputOptions {
+            slim: true,
+            ..Default::default()
+        };
+
+        finalize(&mut document, &options);
+
+        assert_eq!(
+            document,
+            json!({
+                "openapi": "3.0.3",
+                "info": {"title": "Pets", "version": "1.0"},
+                "paths": {
+                    "/pets": {
+                        "get": {
+                            "responses": {
+                                "200": {"description": "", "content": {"application/json": {}}}
+                            }
+                        }
+                    }
+                },
+                "components": {
+                    "schemas": {
+                        "Pet": {
+                            "type": "object",
+                            "properties": {"description": {"type": "string", "maxLength": 80}}
+                        }
+                    }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn slim_keeps_named_members() {
+        let mut document = json!({
+            "openapi": "3.0.3",
+            "servers": [{
+                "url": "https://{description}.example.com",
+                "variables": {"description": {"default": "api", "description": "Subdomain"}}
+            }],
+            "components": {
+                "schemas": {
+                    "Pet": {
+                        "type": "object",
+                        "discriminator": {
+                            "propertyName": "kind",
+                            "mapping": {"example": "#/components/schemas/Pet"}
+                        }
+                    }
+                },
+                "securitySchemes": {
+                    "description": {"type": "http", "scheme": "basic", "description": "Basic"}
+                }
+            }
+        });
+        let options = OutputOptions {
+            slim: true,
+            ..Default::default()
+        };
+
+        finalize(&mut document, &options);
+
+        assert_eq!(
+            document["servers"][0]["variables"],
+            json!({"description": {"default": "api"}})
+        );
+        assert_eq!(
+            document["components"]["schemas"]["Pet"]["discriminator"]["mapping"],
+            json!({"example": "#/components/schemas/Pet"})
+        );
+        assert_eq!(
+            document["components"]["securitySchemes"],
+            json!({"description": {"type": "http", "scheme": "basic"}})
+        );
+    }
+
+    #[test]
+    fn omit_empty_structures() {
+        let mut document = json!({
+            "openapi": "3.0.3",
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "tags": [],
+                        "security": [],
+                        "responses": {"200": {"description": "ok", "content": {}}}
+                    }
+                }
+            },
+            "components": {"schemas": {}, "responses": {}},
+            "security": []
+        });
+        let mut secured = document.clone();
+        secured["security"] = json!([{}]);
+        secured["components"]["schemas"] = json!({
+            "Pet": {"type": "object", "required": [], "example": {"required": []}}
+        });
+
+        omit_empty(&mut document);
+        omit_empty(&mut secured);
+
+        assert_eq!(
+            document,
+            json!({
+                "openapi": "3.0.3",
+                "paths": {
+                    "/pets": {
+                        "get": {"security": [], "responses": {"200": {"description": "ok"}}}
+                    }
+                },
+                "security": []
+            })
+        );
+        assert_eq!(secured["security"], json!([{}]));
+        assert_eq!(secured["paths"]["/pets"]["get"]["security"], json!([]));
+        assert_eq!(
+            secured["components"],
+            json!({"schemas": {"Pet": {"type": "object", "example": {"required": []}}}})
+        );
+    }
+
+    #[test]
+    fn stamp_provenance() {
+        let swagger = json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "paths": {}
+        })
+        .to_string();
+        let options = ConvertOptions {
+            output: OutputOptions {
+                stamp: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let document = crate::convert_str(&swagger, &options).unwrap().to_value();
+
+        let stamp = &document["x-converted-from"];
+        assert_eq!(stamp["tool"], "swagger-convert");
+        assert_eq!(stamp["version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(
+            stamp["sourceHash"],
+            format!("fnv1a64:{:016x}", fnv1a(&swagger))
+        );
+        assert_eq!(rfc3339(0), "1970-01-01T00:00:00Z");
+        assert_eq!(rfc3339(951825600), "2000-02-29T12:00:00Z");
+    }
+
+    #[test]
+    fn split_schemas() {
+        let document = json!({
+            "paths": {"/pets": {"get": {"responses": {"200": {
+                "description": "ok",
+                "content": {"application/json": {"schema": {"$ref": "#/components/schemas/Pet"}}}
+            }}}}},
+            "components": {
+                "schemas": {
+                    "Pet": {
+                        "type": "object",
+                        "properties": {
+                            "owner": {"$ref": "#/components/schemas/Owner"},
+                            "error": {"$ref": "#/components/responses/Error"},
+                            "example": {"$ref": "#/components/schemas/Owner"}
+                        },
+                        "x-patternProperties": {"^x-": {"$ref": "#/components/schemas/Owner"}},
+                        "example": {"$ref": "#/components/schemas/Owner"}
+                    },
+                    "Owner": {"type": "object"}
+                }
+            }
+        });
+
+        let files = split(document, &Layout::default());
+
+        assert_eq!(
+            files.keys().collect::<Vec<_>>(),
+            vec!["openapi.json", "schemas/Owner.json", "schemas/Pet.json"]
+        );
+        assert_eq!(
+            files["schemas/Pet.json"]["properties"],
+            json!({
+                "owner": {"$ref": "Owner.json"},
+                "error": {"$ref": "../openapi.json#/components/responses/Error"},
+                "example": {"$ref": "Owner.json"}
+            })
+        );
+        assert_eq!(
+            files["schemas/Pet.json"]["x-patternProperties"],
+            json!({"^x-": {"$ref": "Owner.json"}})
+        );
+        // an example is a value, whatever it looks like
+        assert_eq!(
+            files["schemas/Pet.json"]["example"],
+            json!({"$ref": "#/components/schemas/Owner"})
+        );
+        let main = &files["openapi.json"];
+        assert_eq!(
+            main["components"]["schemas"]["Pet"],
+            json!({"$ref": "schemas/Pet.json"})
+        );
+        assert_eq!(
+            main.pointer("/paths/~1pets/get/responses/200/content/application~1json/schema"),
+            Some(&json!({"$ref": "schemas/Pet.json"}))
+        );
+    }
+
+    #[test]
+    fn order_paths() {
+        let swagger = json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "paths": {
+                "/c": {"get": {"responses": {}}},
+                "/b": {"get": {"tags": ["owners"], "responses": {}}},
+                "/a": {"get": {"tags": ["pets"], "responses": {}}}
+            },
+            "definitions": {"Pet": {"type": "object"}, "Owner": {"type": "object"}}
+        })
+        .to_string();
+        let written = |order| {
+            let options = ConvertOptions {
+                output: OutputOptions {
+                    order,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            let mut out = Vec::new();
+            crate::convert_str(&swagger, &options)
+                .unwrap()
+                .to_writer(&mut out)
+                .unwrap();
+            let out = String::from_utf8(out).unwrap();
+            let position = |key: &str| out.find(&format!("{key:?}")).unwrap();
+            let mut keys = ["/a", "/b", "/c", "Owner", "Pet"];
+            keys.sort_by_key(|key| position(key));
+            keys
+        };
+
+        assert_eq!(
+            written(Order::Alphabetical),
+            ["Owner", "Pet", "/a", "/b", "/c"]
+        );
+        assert_eq!(written(Order::Source), ["Pet", "Owner", "/c", "/b", "/a"]);
+        assert_eq!(written(Order::Tag), ["Owner", "Pet", "/b", "/a", "/c"]);
+    }
+}
@@ -0,0 +1,60 @@
+//! Conversion of large maps, spread over the rayon thread pool with the `parallel` feature.
+
+use std::collections::BTreeMap;
+
+/// Converts every value of `map` with `f`, keeping the keys.
+#[cfg(not(feature = "parallel"))]
+pub(crate) fn convert_map<K, V, O>(map: BTreeMap<K, V>, f: impl Fn(&K, V) -> O) -> BTreeMap<K, O>
+where
+    K: Ord,
+{
+    map.into_iter()
+        .map(|(k, v)| {
+            let o = f(&k, v);
+            (k, o)
+        })
+        .collect()
+}
+
+/// Converts every value of `map` with `f` on the rayon thread pool, keeping the keys.
+///
+/// The options and diagnostic scope of the calling thread are carried over to the workers,
+/// and diagnostics are reported in key order, so the outcome is identical to a sequential
+/// conversion. Workers keep their conversion caches across the entries, see
+/// [`options::resume`].
+///
+/// [`options::resume`]: crate::options::resume
+#[cfg(feature = "parallel")]
+pub(crate) fn convert_map<K, V, O>(
+    map: BTreeMap<K, V>,
+    f: impl Fn(&K, V) -> O + Sync,
+) -> BTreeMap<K, O>
+where
+    K: Ord + Send,
+    V: Send,
+    O: Send,
+{
+    use rayon::prelude::*;
+
+    use crate::{diagnostics, options};
+
+    let conversion = options::suspend();
+    let pointer = diagnostics::pointer();
+    let converted: Vec<_> = map
+        .into_par_iter()
+        .map(|(k, v)| {
+            let (o, reported) = options::resume(&conversion, || {
+                diagnostics::at(&pointer, || diagnostics::collect(|| f(&k, v)))
+            });
+            (k, o, reported)
+        })
+        .collect();
+
+    converted
+        .into_iter()
+        .map(|(k, o, reported)| {
+            diagnostics::extend(reported);
+            (k, o)
+        })
+        .collect()
+}
@@ -0,0 +1,76 @@
+//! Partial conversion, skipping the parts of a document which cannot be parsed.
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::{
+    diagnostics,
+    spec::{PathItem, RefOr, Schema},
+};
+
+/// Removes the path items and definitions of `document` which fail to parse, reporting each
+/// of them as an error.
+pub(crate) fn prune(document: &mut Value) {
+    prune_entries::<PathItem>(document, "paths");
+    prune_entries::<RefOr<Schema>>(document, "definitions");
+}
+
+fn prune_entries<T: DeserializeOwned>(document: &mut Value, key: &str) {
+    let Some(entries) = document.get_mut(key).and_then(Value::as_object_mut) else {
+        return;
+    };
+    diagnostics::scope(key, || {
+        entries.retain(|name, entry| {
+            if name.starts_with("x-") {
+                return true;
+            }
+            let Err(err) = T::deserialize(&*entry) else {
+                return true;
+            };
+            diagnostics::scope(name, || diagnostics::error(format!("skipped: {err}")));
+            false
+        });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::{diagnostics::Severity, ConvertOptions};
+
+    #[test]
+    fn skip_invalid_entries() {
+        let swagger = json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "paths": {
+                "/pets": {"get": {"responses": {"200": {"description": "ok"}}}},
+                "/broken": {"get": {"parameters": [{"name": "id", "in": "nowhere"}], "responses": {}}}
+            },
+            "definitions": {
+                "Pet": {"type": "object"},
+                "Broken": {"type": "object", "properties": 5}
+            }
+        });
+        let options = ConvertOptions {
+            partial: true,
+            ..Default::default()
+        };
+
+        let conversion = crate::convert_str(&swagger.to_string(), &options).unwrap();
+
+        let document = conversion.to_value();
+        assert!(document["paths"].get("/pets").is_some());
+        assert!(document["paths"].get("/broken").is_none());
+        assert!(document["components"]["schemas"].get("Pet").is_some());
+        let errors: Vec<&str> = conversion
+            .diagnostics
+            .iter()
+            .filter(|d| d.severity == Severity::Error)
+            .map(|d| d.pointer.as_str())
+            .collect();
+        assert_eq!(errors, vec!["/paths/~1broken", "/definitions/Broken"]);
+        assert!(crate::convert_str(&swagger.to_string(), &ConvertOptions::default()).is_err());
+    }
+}
@@ -0,0 +1,287 @@
+//! Detection of components nothing in the document uses, which are reported and, with
+//! [`ConvertOptions::prune`](crate::ConvertOptions::prune), dropped.
+
+use std::collections::BTreeSet;
+
+use serde_json::Value;
+use utoipa::openapi::OpenApi;
+
+use crate::{diagnostics, spec};
+
+/// Unused components dropped from the converted document, all kept by default.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PruneOptions {
+    /// Drop security schemes no security requirement names.
+    pub security_schemes: bool,
+    /// Drop shared responses no `$ref` points at.
+    pub responses: bool,
+}
+
+/// Reports the shared responses of `document` no `$ref` points at, dropping them as
+/// `options` ask.
+pub(crate) fn responses(document: &mut Value, options: PruneOptions) {
+    if document.get("responses").is_none() {
+        return;
+    }
+    let mut refs = Vec::new();
+    spec::local_refs(document, &mut String::new(), &mut refs);
+    let used: BTreeSet<String> = refs.into_iter().map(|(_, location)| location).collect();
+    unused_responses(document, &used, options);
+}
+
+/// Like [`responses`], with the locations of the `$ref`s of the document `used` collected
+/// already, e.g. while streaming its paths.
+pub(crate) fn unused_responses(
+    document: &mut Value,
+    used: &BTreeSet<String>,
+    options: PruneOptions,
+) {
+    let Some(Value::Object(responses)) = document.get_mut("responses") else {
+        return;
+    };
+    responses.retain(|name, _| !unused_response(name, used, options) || !options.responses);
+}
+
+/// Reports the shared responses of `swagger` no `$ref` points at, without serializing it.
+/// They are kept, [`responses`] drops them.
+pub(crate) fn report_responses(swagger: &spec::Swagger, options: PruneOptions) {
+    let Some(responses) = &swagger.responses else {
+        return;
+    };
+    let used = response_refs(swagger);
+    let default = responses.default.as_ref().map(|_| "default");
+    let names: BTreeSet<&str> = responses
+        .responses
+        .keys()
+        .map(String::as_str)
+        .chain(default)
+        .collect();
+    for name in names {
+        unused_response(name, &used, options);
+    }
+}
+
+/// Reports the shared response `name` if no location of `used` points at it, returning
+/// whether it is unused.
+fn unused_response(name: &str, used: &BTreeSet<String>, options: PruneOptions) -> bool {
+    let pointer = format!("/responses/{}", diagnostics::escape(name));
+    if used.contains(&format!("#{pointer}")) {
+        return false;
+    }
+    diagnostics::at(&pointer, || {
+        if options.responses {
+            diagnostics::warn(format!(
+                "dropped shared response `{name}`, no `$ref` points at it"
+            ));
+        } else {
+            diagnostics::warn(format!(
+                "shared response `{name}` is not used by any `$ref`"
+            ));
+        }
+    });
+    true
+}
+
+/// Locations of the `$ref`s of `swagger` which may point at shared responses: those of the
+/// responses of its operations and webhooks, within their `x-callbacks`, and of the shared
+/// responses themselves.
+fn response_refs(swagger: &spec::Swagger) -> BTreeSet<String> {
+    fn add(used: &mut BTreeSet<String>, responses: &spec::Responses) {
+        for response in responses.responses.values().chain(&responses.default) {
+            if let spec::RefOr::Ref(reference) = response {
+                used.insert(reference.ref_location.clone());
+            }
+        }
+    }
+
+    let mut used = BTreeSet::new();
+    let webhooks = swagger
+        .webhooks
+        .iter()
+        .flat_map(|webhooks| webhooks.values());
+    let path_items = swagger.paths.paths.values().chain(webhooks);
+    for operation in path_items.flat_map(|path_item| path_item.operations.values()) {
+        add(&mut used, &operation.responses);
+        if let Some(callbacks) = operation.extensions.get("x-callbacks") {
+            let mut refs = Vec::new();
+            let callbacks = serde_json::json!({"x-callbacks": callbacks});
+            spec::local_refs(&callbacks, &mut String::new(), &mut refs);
+            used.extend(refs.into_iter().map(|(_, location)| location));
+        }
+    }
+    if let Some(responses) = &swagger.responses {
+        add(&mut used, responses);
+    }
+    used
+}
+
+/// Reports the unused components of `openapi`, dropping them as `options` ask.
+pub(crate) fn unused(openapi: &mut OpenApi, options: PruneOptions) {
+    let Ok(document) = serde_json::to_value(&*openapi) else {
+        return;
+    };
+    let Some(components) = &mut openapi.components else {
+        return;
+    };
+
+    let required = required_schemes(&document);
+    components.security_schemes.retain(|name, _| {
+        if required.contains(name.as_str()) {
+            return true;
+        }
+        let pointer = format!("/securityDefinitions/{}", diagnostics::escape(name));
+        diagnostics::at(&pointer, || {
+            if options.security_schemes {
+                diagnostics::warn(format!(
+                    "dropped security definition `{name}`, no security requirement uses it"
+                ));
+            } else {
+                diagnostics::warn(format!(
+                    "security definition `{name}` is not used by any security requirement"
+                ));
+            }
+        });
+        !options.security_schemes
+    });
+}
+
+/// Names of the security schemes required by the document or any of its operations.
+fn required_schemes(document: &Value) -> BTreeSet<&str> {
+    let operations = document["paths"]
+        .as_object()
+        .into_iter()
+        .flat_map(|paths| paths.values())
+        .filter_map(Value::as_object)
+        .flat_map(|item| item.values());
+    std::iter::once(document)
+        .chain(operations)
+        .filter_map(|holder| holder["security"].as_array())
+        .flatten()
+        .filter_map(Value::as_object)
+        .flat_map(|requirement| requirement.keys())
+        .map(String::as_str)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::ConvertOptions;
+
+    #[test]
+    fn unused_security_definitions() {
+        let swagger = json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "securityDefinitions": {
+                "key": {"type": "apiKey", "name": "X-Key", "in": "header"},
+                "basic": {"type": "basic"},
+                "legacy": {"type": "apiKey", "name": "token", "in": "query"}
+            },
+            "security": [{"key": []}],
+            "paths": {
+                "/pets": {"get": {"security": [{"basic": []}], "responses": {}}}
+            }
+        });
+        let convert = |prune| {
+            let options = ConvertOptions {
+                prune,
+                ..Default::default()
+            };
+            let swagger = serde_json::from_value(swagger.clone()).unwrap();
+            crate::convert_with(swagger, &options)
+        };
+
+        let kept = convert(PruneOptions::default());
+        let pruned = convert(PruneOptions {
+            security_schemes: true,
+            ..Default::default()
+        });
+
+        let schemes = |conversion: &crate::Conversion| {
+            let document = conversion.to_value();
+            let schemes = document["components"]["securitySchemes"]
+                .as_object()
+                .cloned();
+            schemes
+                .unwrap_or_default()
+                .keys()
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(schemes(&kept), ["basic", "key", "legacy"]);
+        assert_eq!(schemes(&pruned), ["basic", "key"]);
+        assert_eq!(
+            kept.diagnostics
+                .iter()
+                .map(|d| (d.pointer.as_str(), d.message.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(
+                "/securityDefinitions/legacy",
+                "security definition `legacy` is not used by any security requirement"
+            )]
+        );
+        assert_eq!(pruned.diagnostics.len(), 1);
+    }
+
+    #[test]
+    fn orphan_responses() {
+        let swagger = json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "responses": {
+                "NotFound": {"description": "No such pet"},
+                "Gone": {"description": "The pet moved out"},
+                "Received": {"description": "Callback received"}
+            },
+            "paths": {
+                "/pets/{id}": {
+                    "get": {
+                        "parameters": [
+                            {"name": "id", "in": "path", "required": true, "type": "string"}
+                        ],
+                        "responses": {"404": {"$ref": "#/responses/NotFound"}},
+                        "x-callbacks": {
+                            "onMoved": {
+                                "{$request.query.callback}": {
+                                    "post": {
+                                        "responses": {"200": {"$ref": "#/responses/Received"}}
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        });
+        let options = ConvertOptions {
+            prune: PruneOptions {
+                responses: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let kept = crate::convert(serde_json::from_value(swagger.clone()).unwrap());
+        let pruned = crate::convert_with(serde_json::from_value(swagger).unwrap(), &options);
+
+        assert!(kept.to_value()["components"]["responses"]["Gone"].is_object());
+        assert_eq!(
+            kept.diagnostics
+                .iter()
+                .map(|d| (d.pointer.as_str(), d.message.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(
+                "/responses/Gone",
+                "shared response `Gone` is not used by any `$ref`"
+            )]
+        );
+        let responses = pruned.to_value()["components"]["responses"].clone();
+        assert_eq!(
+            responses.as_object().unwrap().keys().collect::<Vec<_>>(),
+            ["NotFound", "Received"]
+        );
+    }
+}
@@ -0,0 +1,95 @@
+//! Python bindings, enabled with the `python` feature and built with maturin.
+//!
+//! maturin builds the extension module as `cdylib` and enables `pyo3/extension-module`, see
+//! `pyproject.toml`. The feature is not enabled here, so `cargo test --features python` can
+//! link against libpython.
+
+use pyo3::{
+    exceptions::PyValueError,
+    prelude::*,
+    types::{PyList, PyString},
+};
+
+use crate::{ConvertOptions, Error};
+
+/// Converts a Swagger 2.0 document given as `dict` or JSON `str` into an OpenAPI 3.0 `dict`.
+#[pyfunction]
+fn convert<'py>(py: Python<'py>, swagger: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    let (openapi, _) = convert_with_diagnostics(py, swagger)?;
+    Ok(openapi)
+}
+
+/// Like `convert`, but additionally returns the list of conversion diagnostics as `dict`s
+/// with `severity` and `message` keys.
+#[pyfunction]
+fn convert_with_diagnostics<'py>(
+    py: Python<'py>,
+    swagger: &Bound<'py, PyAny>,
+) -> PyResult<(Bound<'py, PyAny>, Bound<'py, PyList>)> {
+    let json = py.import_bound("json")?;
+    let swagger: String = if swagger.is_instance_of::<PyString>() {
+        swagger.extract()?
+    } else {
+        json.call_method1("dumps", (swagger,))?.extract()?
+    };
+
+    let (openapi, diagnostics) =
+        convert_document(&swagger).map_err(|err| PyValueError::new_err(err.to_string()))?;
+
+    let openapi = json.call_method1("loads", (openapi,))?;
+    let diagnostics = json
+        .call_method1("loads", (diagnostics,))?
+        .downcast_into::<PyList>()?;
+    Ok((openapi, diagnostics))
+}
+
+/// Converts the JSON document `swagger` like [`crate::convert_json`], returning the written
+/// document and the diagnostics, including the ones reported while parsing, as JSON.
+fn convert_document(swagger: &str) -> Result<(String, String), Error> {
+    let conversion = crate::convert_str(swagger, &ConvertOptions::default())?;
+    let openapi = serde_json::to_string(&conversion.to_value()).map_err(Error::Serialize)?;
+    let diagnostics = serde_json::to_string(&conversion.diagnostics).map_err(Error::Serialize)?;
+    Ok((openapi, diagnostics))
+}
+
+#[pymodule]
+fn swagger_convert(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(convert, m)?)?;
+    m.add_function(wrap_pyfunction!(convert_with_diagnostics, m)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn convert_document_like_convert_str() {
+        let swagger = json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "paths": {
+                "/pets": {
+                    "get": {"responses": {"200": {"$ref": "#/responses/Missing"}}}
+                }
+            }
+        })
+        .to_string();
+
+        let (openapi, diagnostics) = convert_document(&swagger).unwrap();
+
+        let conversion = crate::convert_str(&swagger, &ConvertOptions::default()).unwrap();
+        assert!(!conversion.diagnostics.is_empty());
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&diagnostics).unwrap(),
+            serde_json::to_value(&conversion.diagnostics).unwrap()
+        );
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&openapi).unwrap(),
+            conversion.to_value()
+        );
+        assert!(convert_document("{}").is_err());
+    }
+}
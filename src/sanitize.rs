@@ -0,0 +1,196 @@
+//! Normalization of description texts during conversion.
+
+use crate::options;
+
+/// How HTML markup in descriptions is handled.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum HtmlHandling {
+    /// Leave markup untouched.
+    #[default]
+    Keep,
+    /// Remove all tags, keeping only their text.
+    Strip,
+    /// Rewrite common tags into their CommonMark equivalent and remove the rest.
+    CommonMark,
+}
+
+/// Normalizations applied to every description, all disabled by default.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SanitizeOptions {
+    pub html: HtmlHandling,
+    /// Replace `\r\n` and lone `\r` line endings with `\n`.
+    pub normalize_line_endings: bool,
+    /// Truncate descriptions to at most this many characters, ending with `…`.
+    pub max_length: Option<usize>,
+}
+
+impl SanitizeOptions {
+    pub fn sanitize(&self, text: String) -> String {
+        let mut text = if self.normalize_line_endings {
+            text.replace("\r\n", "\n").replace('\r', "\n")
+        } else {
+            text
+        };
+
+        text = match self.html {
+            HtmlHandling::Keep => text,
+            HtmlHandling::Strip => convert_html(&text, false),
+            HtmlHandling::CommonMark => convert_html(&text, true),
+        };
+
+        match self.max_length {
+            Some(max_length) if text.chars().count() > max_length => {
+                let mut truncated: String =
+                    text.chars().take(max_length.saturating_sub(1)).collect();
+                truncated.push('…');
+                truncated
+            }
+            _ => text,
+        }
+    }
+}
+
+/// Sanitizes a description with the options of the current conversion.
+pub(crate) fn description(description: Option<String>) -> Option<String> {
+    description.map(text)
+}
+
+/// Sanitizes a mandatory description with the options of the current conversion.
+pub(crate) fn text(text: String) -> String {
+    options::current().sanitize.sanitize(text)
+}
+
+fn convert_html(html: &str, markdown: bool) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut links: Vec<Option<String>> = Vec::new();
+    let mut rest = html;
+
+    while let Some(start) = rest.find('<') {
+        out.push_str(&rest[..start]);
+        let tag = &rest[start + 1..];
+        let Some(end) = tag.find('>').filter(|_| is_tag(tag)) else {
+            // a plain `<`, e.g. in `a < b`
+            out.push('<');
+            rest = tag;
+            continue;
+        };
+        let (name, closing, href) = parse_tag(&tag[..end]);
+        rest = &tag[end + 1..];
+
+        match (name.as_str(), closing) {
+            ("br", _) => out.push('\n'),
+            ("p" | "div", true) => out.push_str("\n\n"),
+            ("ul" | "ol", _) => out.push('\n'),
+            ("li", false) => out.push_str(if markdown { "\n- " } else { "\n" }),
+            _ if !markdown => {}
+            ("b" | "strong", _) => out.push_str("**"),
+            ("i" | "em", _) => out.push('_'),
+            ("code", _) => out.push('`'),
+            ("pre", _) => out.push_str("\n```\n"),
+            ("h1" | "h2" | "h3" | "h4" | "h5" | "h6", false) => {
+                let level = name[1..].parse().unwrap_or(1);
+                out.push('\n');
+                out.push_str(&"#".repeat(level));
+                out.push(' ');
+            }
+            ("h1" | "h2" | "h3" | "h4" | "h5" | "h6", true) => out.push('\n'),
+            ("a", false) => {
+                out.push('[');
+                links.push(href);
+            }
+            ("a", true) => match links.pop().flatten() {
+                Some(href) => out.push_str(&format!("]({href})")),
+                None => out.push(']'),
+            },
+            _ => {}
+        }
+    }
+    out.push_str(rest);
+
+    let out = decode_entities(&out);
+    collapse_blank_lines(out.trim())
+}
+
+fn is_tag(tag: &str) -> bool {
+    let tag = tag.strip_prefix('/').unwrap_or(tag);
+    tag.starts_with(|c: char| c.is_ascii_alphabetic())
+}
+
+fn parse_tag(tag: &str) -> (String, bool, Option<String>) {
+    let closing = tag.starts_with('/');
+    let tag = tag.trim_start_matches('/').trim_end_matches('/');
+    let name = tag
+        .split(|c: char| c.is_whitespace())
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let href = tag.find("href=").and_then(|i| {
+        let value = &tag[i + 5..];
+        let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+        value[1..].split(quote).next().map(str::to_string)
+    });
+    (name, closing, href)
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+fn collapse_blank_lines(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut newlines = 0;
+    for c in text.chars() {
+        if c == '\n' {
+            newlines += 1;
+            if newlines > 2 {
+                continue;
+            }
+        } else {
+            newlines = 0;
+        }
+        out.push(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn html_to_commonmark() {
+        let options = SanitizeOptions {
+            html: HtmlHandling::CommonMark,
+            normalize_line_endings: true,
+            max_length: None,
+        };
+
+        assert_eq!(
+            options.sanitize(
+                "<p>Returns a <b>user</b> &amp; its <a href=\"/docs\">docs</a>.</p>\r\n<ul><li>one</li><li>two</li></ul>"
+                    .to_string()
+            ),
+            "Returns a **user** & its [docs](/docs).\n\n- one\n- two"
+        );
+    }
+
+    #[test]
+    fn strip_and_truncate() {
+        let options = SanitizeOptions {
+            html: HtmlHandling::Strip,
+            normalize_line_endings: false,
+            max_length: Some(10),
+        };
+
+        assert_eq!(options.sanitize("a < b".to_string()), "a < b");
+        assert_eq!(
+            options.sanitize("<i>Returns</i> the user".to_string()),
+            "Returns t…"
+        );
+    }
+}
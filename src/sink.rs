@@ -0,0 +1,100 @@
+//! Destinations converted documents are written to, in a selectable [`Syntax`].
+//!
+//! [`Conversion::to_writer`](crate::Conversion::to_writer) writes through a [`Sink`], other
+//! documents like the [`crate::export`]s can be written the same way.
+
+use std::io::{self, Write};
+
+use serde::Serialize;
+
+use crate::{output::Syntax, trace, Error};
+
+/// Writes documents to `W` in a [`Syntax`].
+pub struct Sink<W> {
+    writer: W,
+    syntax: Syntax,
+}
+
+impl<W: io::Write> Sink<W> {
+    pub fn new(writer: W, syntax: Syntax) -> Self {
+        Self { writer, syntax }
+    }
+
+    /// Serializes `document` into the writer and flushes it.
+    pub fn write(&mut self, document: &impl Serialize) -> Result<(), Error> {
+        trace::phase("serialize", || match self.syntax {
+            Syntax::Json => {
+                serde_json::to_writer_pretty(&mut self.writer, document).map_err(Error::Serialize)
+            }
+            Syntax::CompactJson => {
+                serde_json::to_writer(&mut self.writer, document).map_err(Error::Serialize)
+            }
+            #[cfg(feature = "yaml")]
+            Syntax::Yaml => {
+                serde_yaml::to_writer(&mut self.writer, document).map_err(Error::SerializeYaml)
+            }
+        })?;
+        self.writer.flush().map_err(Error::Write)
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// `document` serialized in `syntax`.
+pub fn to_vec(document: &impl Serialize, syntax: Syntax) -> Result<Vec<u8>, Error> {
+    let mut sink = Sink::new(Vec::new(), syntax);
+    sink.write(document)?;
+    Ok(sink.into_inner())
+}
+
+/// Like [`Sink`], but writes to a tokio [`AsyncWrite`](tokio::io::AsyncWrite).
+///
+/// Documents are serialized in memory first, serde has no asynchronous serializers.
+#[cfg(feature = "tokio")]
+pub struct AsyncSink<W> {
+    writer: W,
+    syntax: Syntax,
+}
+
+#[cfg(feature = "tokio")]
+impl<W: tokio::io::AsyncWrite + Unpin> AsyncSink<W> {
+    pub fn new(writer: W, syntax: Syntax) -> Self {
+        Self { writer, syntax }
+    }
+
+    /// Serializes `document` into the writer and flushes it.
+    pub async fn write(&mut self, document: &impl Serialize) -> Result<(), Error> {
+        use tokio::io::AsyncWriteExt;
+
+        let bytes = to_vec(document, self.syntax)?;
+        self.writer.write_all(&bytes).await.map_err(Error::Write)?;
+        self.writer.flush().await.map_err(Error::Write)
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn write_syntax() {
+        let document = json!({"openapi": "3.0.3", "paths": {}});
+
+        assert_eq!(
+            to_vec(&document, Syntax::CompactJson).unwrap(),
+            br#"{"openapi":"3.0.3","paths":{}}"#
+        );
+        assert_eq!(
+            to_vec(&document, Syntax::Json).unwrap(),
+            b"{\n  \"openapi\": \"3.0.3\",\n  \"paths\": {}\n}"
+        );
+    }
+}
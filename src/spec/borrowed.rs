@@ -0,0 +1,325 @@
+//! Borrowed view of a Swagger 2.0 document, see [`Swagger::from_slice`].
+//!
+//! Parsing a large document into [`Swagger`] copies every string of it. The view only checks
+//! that the input is JSON and keeps the members of the document as slices of it, so it
+//! allocates little more than the index of paths and definitions. Names are only copied if
+//! they contain escapes. Members are parsed into the owned spec types on demand.
+
+use std::{borrow::Cow, collections::BTreeMap, fmt};
+
+use serde::{
+    de::{
+        self,
+        value::{BorrowedStrDeserializer, MapDeserializer},
+        IntoDeserializer, MapAccess, Visitor,
+    },
+    ser::SerializeMap,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use serde_json::value::RawValue;
+
+use crate::Error;
+
+use super::{PathItem, RefOr, Response, Schema, Swagger};
+
+/// Members of a JSON object by name, borrowed from the input.
+pub type RawMembers<'a> = BTreeMap<Cow<'a, str>, &'a RawValue>;
+
+/// A Swagger 2.0 document borrowing its members from the input, see the [module
+/// documentation](self).
+#[derive(Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct SwaggerRef<'a> {
+    /// The `swagger` version, which is not checked until converting to [`Swagger`].
+    pub swagger: Cow<'a, str>,
+    /// Path items by path.
+    pub paths: RawMembers<'a>,
+    /// Schemas by definition name.
+    pub definitions: RawMembers<'a>,
+    /// Responses by name.
+    pub responses: RawMembers<'a>,
+    /// The other members of the root, like `info` and extensions.
+    pub rest: RawMembers<'a>,
+}
+
+impl Swagger {
+    /// Parses a Swagger 2.0 JSON document into a view borrowing from `slice`, see
+    /// [`SwaggerRef`].
+    pub fn from_slice(slice: &[u8]) -> Result<SwaggerRef<'_>, Error> {
+        serde_json::from_slice(slice).map_err(Error::Parse)
+    }
+}
+
+impl SwaggerRef<'_> {
+    /// Parses the path item of `path`, if there is one.
+    pub fn path_item(&self, path: &str) -> Option<Result<PathItem, Error>> {
+        self.paths.get(path).map(|path_item| parse(path_item))
+    }
+
+    /// Parses the definition `name`, if there is one.
+    pub fn definition(&self, name: &str) -> Option<Result<RefOr<Schema>, Error>> {
+        self.definitions.get(name).map(|schema| parse(schema))
+    }
+
+    /// Parses the shared response `name`, if there is one.
+    pub fn response(&self, name: &str) -> Option<Result<Response, Error>> {
+        self.responses.get(name).map(|response| parse(response))
+    }
+
+    /// Parses the whole document into the owned [`Swagger`], e.g. to convert it. The members
+    /// are parsed from the slices of the input, the document is not written again.
+    pub fn to_swagger(&self) -> Result<Swagger, Error> {
+        let indexed = [
+            ("definitions", &self.definitions),
+            ("responses", &self.responses),
+        ];
+        let members = [
+            ("swagger", Member::Str(&self.swagger)),
+            ("paths", Member::Members(&self.paths)),
+        ]
+        .into_iter()
+        .chain(
+            indexed
+                .into_iter()
+                .filter(|(_, members)| !members.is_empty())
+                .map(|(key, members)| (key, Member::Members(members))),
+        )
+        .chain(
+            self.rest
+                .iter()
+                .map(|(key, value)| (key.as_ref(), Member::Raw(*value))),
+        );
+        Swagger::deserialize(MapDeserializer::new(members)).map_err(Error::Parse)
+    }
+}
+
+fn parse<'a, T: Deserialize<'a>>(value: &'a RawValue) -> Result<T, Error> {
+    serde_json::from_str(value.get()).map_err(Error::Parse)
+}
+
+/// A member of [`SwaggerRef`] to parse the owned types from.
+enum Member<'a> {
+    Str(&'a str),
+    Raw(&'a RawValue),
+    Members(&'a RawMembers<'a>),
+}
+
+impl<'de> IntoDeserializer<'de, serde_json::Error> for Member<'de> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+macro_rules! forward_member {
+    ($($method:ident($($arg:ident: $ty:ty),*);)*) => {$(
+        fn $method<V: Visitor<'de>>(
+            self,
+            $($arg: $ty,)*
+            visitor: V,
+        ) -> Result<V::Value, serde_json::Error> {
+            match self {
+                Member::Str(value) => {
+                    BorrowedStrDeserializer::new(value).$method($($arg,)* visitor)
+                }
+                Member::Raw(value) => {
+                    let mut deserializer = serde_json::Deserializer::from_str(value.get());
+                    (&mut deserializer).$method($($arg,)* visitor)
+                }
+                Member::Members(members) => {
+                    let members = members
+                        .iter()
+                        .map(|(name, value)| (name.as_ref(), Member::Raw(*value)));
+                    MapDeserializer::new(members).$method($($arg,)* visitor)
+                }
+            }
+        }
+    )*};
+}
+
+/// Hands each request to the deserializer of the member, so the owned types parse it like
+/// the input itself.
+impl<'de> Deserializer<'de> for Member<'de> {
+    type Error = serde_json::Error;
+
+    forward_member! {
+        deserialize_any();
+        deserialize_bool();
+        deserialize_i8();
+        deserialize_i16();
+        deserialize_i32();
+        deserialize_i64();
+        deserialize_u8();
+        deserialize_u16();
+        deserialize_u32();
+        deserialize_u64();
+        deserialize_f32();
+        deserialize_f64();
+        deserialize_char();
+        deserialize_str();
+        deserialize_string();
+        deserialize_bytes();
+        deserialize_byte_buf();
+        deserialize_unit();
+        deserialize_unit_struct(name: &'static str);
+        deserialize_newtype_struct(name: &'static str);
+        deserialize_seq();
+        deserialize_tuple(len: usize);
+        deserialize_tuple_struct(name: &'static str, len: usize);
+        deserialize_map();
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]);
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]);
+        deserialize_identifier();
+        deserialize_ignored_any();
+    }
+
+    /// Only JSON values can be `null`, the other members are present.
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self {
+            Member::Raw(value) => {
+                let mut deserializer = serde_json::Deserializer::from_str(value.get());
+                (&mut deserializer).deserialize_option(visitor)
+            }
+            member => visitor.visit_some(member),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SwaggerRef<'de> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(Root)
+    }
+}
+
+/// Writes the members as they were read. `paths` is written even if empty, it is required.
+impl Serialize for SwaggerRef<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("swagger", &self.swagger)?;
+        map.serialize_entry("paths", &self.paths)?;
+        for (key, members) in [
+            ("definitions", &self.definitions),
+            ("responses", &self.responses),
+        ] {
+            if !members.is_empty() {
+                map.serialize_entry(key, members)?;
+            }
+        }
+        for (key, value) in &self.rest {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+/// A name or string borrowed from the input unless it contains escapes.
+#[derive(Deserialize)]
+struct Borrowed<'a>(#[serde(borrow)] Cow<'a, str>);
+
+/// Members of an object borrowed from the input.
+struct Members<'a>(RawMembers<'a>);
+
+impl<'de> Deserialize<'de> for Members<'de> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(Object)
+    }
+}
+
+struct Object;
+
+impl<'de> Visitor<'de> for Object {
+    type Value = Members<'de>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut members = BTreeMap::new();
+        while let Some(Borrowed(name)) = map.next_key()? {
+            members.insert(name, map.next_value()?);
+        }
+        Ok(Members(members))
+    }
+}
+
+struct Root;
+
+impl<'de> Visitor<'de> for Root {
+    type Value = SwaggerRef<'de>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a swagger document")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut swagger = None;
+        let mut document = SwaggerRef {
+            swagger: Cow::Borrowed(""),
+            paths: BTreeMap::new(),
+            definitions: BTreeMap::new(),
+            responses: BTreeMap::new(),
+            rest: BTreeMap::new(),
+        };
+        while let Some(Borrowed(name)) = map.next_key()? {
+            let members = match name.as_ref() {
+                "swagger" => {
+                    let Borrowed(version) = map.next_value()?;
+                    swagger = Some(version);
+                    continue;
+                }
+                "paths" => &mut document.paths,
+                "definitions" => &mut document.definitions,
+                "responses" => &mut document.responses,
+                _ => {
+                    document.rest.insert(name, map.next_value()?);
+                    continue;
+                }
+            };
+            let Members(read) = map.next_value()?;
+            *members = read;
+        }
+        document.swagger = swagger.ok_or_else(|| de::Error::missing_field("swagger"))?;
+        Ok(document)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn borrow_from_slice() {
+        let source = json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "paths": {"/pets": {"get": {"responses": {"204": {"description": "none"}}}}},
+            "definitions": {"Pet": {"type": "object"}},
+            "responses": {"NotFound": {"description": "No such pet"}},
+            "host": null,
+            "x-logo": {"url": "logo.png"}
+        })
+        .to_string();
+
+        let document = Swagger::from_slice(source.as_bytes()).unwrap();
+
+        assert!(matches!(document.swagger, Cow::Borrowed("2.0")));
+        assert!(matches!(
+            document.paths.keys().next(),
+            Some(Cow::Borrowed("/pets"))
+        ));
+        assert_eq!(
+            document.rest.keys().collect::<Vec<_>>(),
+            ["host", "info", "x-logo"]
+        );
+        let pet: RefOr<Schema> = serde_json::from_value(json!({"type": "object"})).unwrap();
+        assert!(document.definition("Pet").unwrap().unwrap() == pet);
+        assert!(document.path_item("/pets").unwrap().is_ok());
+        assert!(document.path_item("/owners").is_none());
+        assert!(document.to_swagger().unwrap() == serde_json::from_str(&source).unwrap());
+        assert!(Swagger::from_slice(br#"{"paths": {}}"#).is_err());
+    }
+}
@@ -1,15 +1,28 @@
-use std::collections::{BTreeMap, HashMap};
-
+use std::{
+    cell::{Cell, RefCell},
+    collections::{hash_map::DefaultHasher, BTreeMap, BTreeSet, HashMap},
+    hash::Hasher,
+    io,
+};
+
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 use utoipa::openapi::{self};
 
+use crate::{
+    cycle::{Recursion, RefStack},
+    diagnostics::{self, Diagnostic},
+    options, parallel, sanitize,
+};
+
 use super::{AdditionalProperties, Extensions, RefOr};
 
 /// https://swagger.io/specification/v2/#definitions-object
 #[skip_serializing_none]
 #[derive(Serialize, Deserialize, Clone, PartialEq)]
 #[cfg_attr(feature = "debug", derive(Debug))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct Definitions {
     #[serde(flatten)]
@@ -18,17 +31,89 @@ pub struct Definitions {
 
 impl From<Definitions> for BTreeMap<String, openapi::RefOr<openapi::Schema>> {
     fn from(value: Definitions) -> Self {
-        value
-            .defintions
-            .into_iter()
-            .map(|(k, v)| (k, v.into_openapi_ref()))
-            .collect()
+        report_circular_all_of(&value.defintions);
+        parallel::convert_map(value.defintions, |k, v| {
+            diagnostics::scope(k, || v.into_openapi_ref())
+        })
+    }
+}
+
+const DEFINITIONS: &str = "#/definitions/";
+
+/// Reports definitions which include themselves through `allOf`, directly or through other
+/// definitions. Tools flattening `allOf` loop on them, so every cycle is reported once as
+/// error at the first of its definitions.
+pub(crate) fn report_circular_all_of(definitions: &BTreeMap<String, RefOr<Schema>>) {
+    let mut reported = BTreeSet::new();
+    for name in definitions.keys() {
+        let location = format!("{DEFINITIONS}{name}");
+        let mut refs = RefStack::new(usize::MAX);
+        let Some(Recursion::Cycle(chain)) = circular_all_of(definitions, &location, &mut refs)
+        else {
+            continue;
+        };
+        let members: BTreeSet<String> = chain.iter().cloned().collect();
+        if chain[0] == location && reported.insert(members) {
+            let cycle = Recursion::Cycle(chain);
+            diagnostics::scope(name, || {
+                diagnostics::scope("allOf", || diagnostics::error(cycle.to_string()))
+            });
+        }
+    }
+}
+
+/// The part of a definition [`report_circular_all_of`] follows, its `$ref` or the `$ref`s of
+/// its `allOf`, so cycles can be found without keeping whole definitions.
+pub(crate) fn all_of_outline(definition: &RefOr<Schema>) -> Option<RefOr<Schema>> {
+    match definition {
+        RefOr::Ref(_) => Some(definition.clone()),
+        RefOr::T(Schema::AllOf(all_of)) => Some(RefOr::T(Schema::AllOf(AllOf {
+            items: all_of
+                .items
+                .iter()
+                .filter(|item| matches!(item, RefOr::Ref(_)))
+                .cloned()
+                .collect(),
+            title: None,
+            description: None,
+            default: None,
+            example: None,
+            discriminator: None,
+            extensions: Extensions::default(),
+        }))),
+        RefOr::T(_) => None,
+    }
+}
+
+/// First cycle of `allOf` references reachable from the definition at `location`.
+fn circular_all_of<'a>(
+    definitions: &'a BTreeMap<String, RefOr<Schema>>,
+    location: &'a str,
+    refs: &mut RefStack,
+) -> Option<Recursion> {
+    if let Err(recursion) = refs.enter(location) {
+        return Some(recursion);
     }
+    let schema = location
+        .strip_prefix(DEFINITIONS)
+        .and_then(|name| definitions.get(name));
+    let found = match schema {
+        // an alias of another definition is included just the same
+        Some(RefOr::Ref(reference)) => circular_all_of(definitions, &reference.ref_location, refs),
+        Some(RefOr::T(Schema::AllOf(all_of))) => all_of.items.iter().find_map(|item| match item {
+            RefOr::Ref(reference) => circular_all_of(definitions, &reference.ref_location, refs),
+            RefOr::T(_) => None,
+        }),
+        _ => None,
+    };
+    refs.leave();
+    found
 }
 
 /// https://swagger.io/specification/v2/#schema-object
-#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Serialize, Clone, PartialEq)]
 #[cfg_attr(feature = "debug", derive(Debug))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged, rename_all = "camelCase")]
 pub enum Schema {
     Array(Array),
@@ -36,14 +121,162 @@ pub enum Schema {
     AllOf(AllOf),
 }
 
+/// Deserialization of [`Schema`] once its `type` is normalized. `allOf` schemas may have a
+/// `type` as well, so they are tried first.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SchemaRepr {
+    AllOf(AllOf),
+    Array(Array),
+    Object(Object),
+}
+
+impl<'de> Deserialize<'de> for Schema {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let mut value = serde_json::Value::deserialize(deserializer)?;
+        normalize_type(&mut value);
+        let schema = SchemaRepr::deserialize(value).map_err(serde::de::Error::custom)?;
+        Ok(match schema {
+            SchemaRepr::Array(array) => Self::Array(array),
+            SchemaRepr::Object(object) => Self::Object(object),
+            SchemaRepr::AllOf(all_of) => Self::AllOf(all_of),
+        })
+    }
+}
+
+/// Collapses a `type` given as array, as some tools emit, into its single type. A `null`
+/// type is turned into `x-nullable`, other combinations of types are left as they are.
+fn normalize_type(value: &mut serde_json::Value) {
+    let Some(serde_json::Value::Array(types)) = value.get("type") else {
+        return;
+    };
+    let nullable = types.iter().any(|t| t == "null");
+    let mut types: Vec<serde_json::Value> =
+        types.iter().filter(|t| *t != "null").cloned().collect();
+    if types.len() != 1 {
+        return;
+    }
+
+    value["type"] = types.remove(0);
+    if nullable && value.get("x-nullable").is_none() {
+        value["x-nullable"] = serde_json::Value::Bool(true);
+    }
+}
+
+thread_local! {
+    /// Converted schemas of the current conversion keyed by [`structural_hash`], together
+    /// with the schema they were converted from and the diagnostics reported while converting
+    /// them relative to the schema.
+    static SCHEMA_CACHE: RefCell<HashMap<u64, Vec<CachedSchema>>> =
+        RefCell::new(HashMap::new());
+    /// Set while converting a schema which will be cached, nested schemas are part of it.
+    static CACHING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Schema converted while caching, see [`SCHEMA_CACHE`].
+struct CachedSchema {
+    source: Schema,
+    schema: openapi::Schema,
+    reported: Vec<Diagnostic>,
+}
+
+/// Hash of the serialized form of `schema`, which is streamed into the hasher rather than
+/// kept, so equal schemas are found without building a key the size of the schema.
+fn structural_hash(schema: &Schema) -> Option<u64> {
+    struct HashWriter(DefaultHasher);
+
+    impl io::Write for HashWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.write(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut writer = HashWriter(DefaultHasher::new());
+    serde_json::to_writer(&mut writer, schema).ok()?;
+    Some(writer.0.finish())
+}
+
+pub(super) fn clear_schema_cache() {
+    SCHEMA_CACHE.with(|cache| cache.borrow_mut().clear());
+}
+
 impl From<Schema> for openapi::Schema {
     fn from(value: Schema) -> Self {
-        match value {
+        if !options::current().cache_schemas || CACHING.get() {
+            return value.into_openapi_schema();
+        }
+        let Some(key) = structural_hash(&value) else {
+            return value.into_openapi_schema();
+        };
+
+        let pointer = diagnostics::pointer();
+        let cached = SCHEMA_CACHE.with(|cache| {
+            cache.borrow().get(&key).and_then(|cached| {
+                cached
+                    .iter()
+                    .find(|cached| cached.source == value)
+                    .map(|cached| (cached.schema.clone(), cached.reported.clone()))
+            })
+        });
+        if let Some((schema, reported)) = cached {
+            diagnostics::extend(
+                reported
+                    .into_iter()
+                    .map(|mut diagnostic| {
+                        diagnostic.pointer.insert_str(0, &pointer);
+                        diagnostic
+                    })
+                    .collect(),
+            );
+            return schema;
+        }
+
+        let source = value.clone();
+        CACHING.set(true);
+        let (schema, reported) = diagnostics::collect(|| value.into_openapi_schema());
+        CACHING.set(false);
+
+        let relative = reported
+            .iter()
+            .cloned()
+            .map(|mut diagnostic| {
+                diagnostic.pointer = diagnostic
+                    .pointer
+                    .strip_prefix(&pointer)
+                    .map(str::to_string)
+                    .unwrap_or_default();
+                diagnostic
+            })
+            .collect();
+        diagnostics::extend(reported);
+        let cached = CachedSchema {
+            source,
+            schema: schema.clone(),
+            reported: relative,
+        };
+        SCHEMA_CACHE.with(|cache| cache.borrow_mut().entry(key).or_default().push(cached));
+        schema
+    }
+}
+
+impl Schema {
+    fn into_openapi_schema(self) -> openapi::Schema {
+        match self {
             Schema::Array(array) => {
                 let openapi_array = openapi::ArrayBuilder::new()
                     .title(array.title)
-                    .items(array.items.into_openapi_ref())
-                    .description(array.description)
+                    .items(diagnostics::scope("items", || {
+                        array.items.into_openapi_ref()
+                    }))
+                    .description(sanitize::description(array.description))
                     .default(array.default)
                     .example(array.example)
                     .xml(array.xml)
@@ -51,66 +284,100 @@ impl From<Schema> for openapi::Schema {
                     .min_items(array.min_items)
                     .unique_items(array.unique_items)
                     .nullable(array.extensions.nullable())
-                    .extensions(array.extensions.into_openapi_extensions())
+                    .extensions(schema_extensions(array.extensions))
                     .build();
 
-                Self::Array(openapi_array)
+                openapi::Schema::Array(openapi_array)
             }
-            Schema::Object(object) => {
+            Schema::Object(mut object) => {
+                convert_pattern_properties(&mut object.extensions);
+                let (minimum, exclusive_minimum) =
+                    exclusive_bound(object.minimum, object.exclusive_minimum, "Minimum");
+                let (maximum, exclusive_maximum) =
+                    exclusive_bound(object.maximum, object.exclusive_maximum, "Maximum");
+                let enum_values = enum_values(
+                    &object.schema_type,
+                    object.enum_values,
+                    object.extensions.nullable(),
+                    object.min_length,
+                    object.max_length,
+                    object.pattern.as_deref(),
+                );
                 let mut openapi_object = openapi::ObjectBuilder::new()
                     .schema_type(object.schema_type)
                     .title(object.title)
                     .format(object.format)
-                    .description(object.description)
+                    .description(sanitize::description(object.description))
                     .default(object.default)
-                    .enum_values(object.enum_values)
+                    .enum_values(enum_values)
                     .example(object.example)
                     .read_only(object.read_only)
                     .xml(object.xml)
                     .nullable(object.extensions.nullable())
                     .multiple_of(object.multiple_of)
-                    .maximum(object.maximum)
-                    .minimum(object.minimum)
-                    .exclusive_maximum(object.exclusive_maximum)
-                    .exclusive_minimum(object.exclusive_minimum)
+                    .maximum(maximum)
+                    .minimum(minimum)
+                    .exclusive_maximum(exclusive_maximum)
+                    .exclusive_minimum(exclusive_minimum)
                     .max_length(object.max_length)
                     .min_length(object.min_length)
                     .pattern(object.pattern)
                     .max_properties(object.max_properties)
                     .min_properties(object.min_properties)
-                    .extensions(object.extensions.into_openapi_extensions())
+                    .extensions(schema_extensions(object.extensions))
                     .build();
 
+                if let Some(property_name) = object.discriminator {
+                    // utoipa has no discriminator field for objects, like the keywords of
+                    // `schema_extensions`
+                    openapi_object
+                        .extensions
+                        .get_or_insert_with(HashMap::new)
+                        .insert(
+                            "discriminator".to_string(),
+                            serde_json::json!({ "propertyName": property_name }),
+                        );
+                }
                 openapi_object.required = object.required;
-                openapi_object.properties = object
-                    .properties
-                    .into_iter()
-                    .map(|(k, v)| (k, v.into_openapi_ref()))
-                    .collect();
-                openapi_object.additional_properties = object
-                    .additional_properties
-                    .map(|p| Box::new(p.into_openapi_additional_properties()));
-
-                Self::Object(openapi_object)
+                openapi_object.properties = diagnostics::scope("properties", || {
+                    object
+                        .properties
+                        .into_iter()
+                        .map(|(k, v)| {
+                            let v = diagnostics::scope(&k, || v.into_openapi_ref());
+                            (k, v)
+                        })
+                        .collect()
+                });
+                openapi_object.additional_properties = object.additional_properties.map(|p| {
+                    diagnostics::scope("additionalProperties", || {
+                        Box::new(p.into_openapi_additional_properties())
+                    })
+                });
+
+                openapi::Schema::Object(openapi_object)
             }
             Schema::AllOf(all_of) => {
                 let mut openapi_all_of = openapi::AllOfBuilder::new()
                     .title(all_of.title)
-                    .description(all_of.description)
+                    .description(sanitize::description(all_of.description))
                     .default(all_of.default)
                     .example(all_of.example)
                     .discriminator(all_of.discriminator.map(openapi::Discriminator::new))
                     .nullable(all_of.extensions.nullable())
-                    .extensions(all_of.extensions.into_openapi_extensions())
+                    .extensions(schema_extensions(all_of.extensions))
                     .build();
 
-                openapi_all_of.items = all_of
-                    .items
-                    .into_iter()
-                    .map(|i| i.into_openapi_ref())
-                    .collect();
+                openapi_all_of.items = diagnostics::scope("allOf", || {
+                    all_of
+                        .items
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, item)| diagnostics::scope(i, || item.into_openapi_ref()))
+                        .collect()
+                });
 
-                Self::AllOf(openapi_all_of)
+                openapi::Schema::AllOf(openapi_all_of)
             }
         }
     }
@@ -119,9 +386,11 @@ impl From<Schema> for openapi::Schema {
 #[skip_serializing_none]
 #[derive(Serialize, Deserialize, Clone, PartialEq)]
 #[cfg_attr(feature = "debug", derive(Debug))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct Array {
     #[serde(rename = "type")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
     pub schema_type: openapi::SchemaType,
     pub title: Option<String>,
     pub items: Box<RefOr<Schema>>,
@@ -132,16 +401,273 @@ pub struct Array {
     pub min_items: Option<usize>,
     #[serde(default, skip_serializing_if = "is_false")]
     pub unique_items: bool,
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<serde_json::Value>"))]
     pub xml: Option<openapi::xml::Xml>,
 
     #[serde(
         flatten,
-        skip_serializing_if = "HashMap::is_empty",
-        default = "HashMap::new"
+        skip_serializing_if = "BTreeMap::is_empty",
+        default = "BTreeMap::new"
     )]
     pub extensions: Extensions,
 }
 
+/// `exclusiveMinimum` or `exclusiveMaximum` of a schema. Swagger 2.0 defines them as flags
+/// of `minimum` and `maximum`, but documents written against newer JSON Schema drafts give
+/// the bound itself.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(untagged)]
+pub enum ExclusiveBound {
+    Flag(bool),
+    Value(f64),
+}
+
+/// Inclusive and exclusive bound of the converted schema, which like JSON Schema draft 6
+/// holds an exclusive bound as value. The written document gets the form of its target
+/// version, see [`crate::output`].
+pub(crate) fn exclusive_bound(
+    bound: Option<f64>,
+    exclusive: Option<ExclusiveBound>,
+    name: &str,
+) -> (Option<f64>, Option<f64>) {
+    match (bound, exclusive) {
+        (Some(bound), Some(ExclusiveBound::Flag(true))) => (None, Some(bound)),
+        (None, Some(ExclusiveBound::Flag(true))) => {
+            diagnostics::warn(format!(
+                "dropped `exclusive{name}` without `{}`",
+                name.to_lowercase()
+            ));
+            (None, None)
+        }
+        (bound, Some(ExclusiveBound::Value(exclusive))) => (bound, Some(exclusive)),
+        (bound, _) => (bound, None),
+    }
+}
+
+/// Enum values matching the schema type, e.g. only strings for `type: string`. Values of
+/// another type are coerced where that is obvious, like `"1"` for `type: integer`, when
+/// converting leniently, and dropped with a warning otherwise. Repeated values are dropped
+/// as well, see [`repeated_enum_value`].
+pub(crate) fn enum_values(
+    schema_type: &openapi::SchemaType,
+    values: Option<Vec<serde_json::Value>>,
+    nullable: bool,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    pattern: Option<&str>,
+) -> Option<Vec<serde_json::Value>> {
+    use serde_json::Value;
+
+    let values = values?;
+    let pattern = pattern.and_then(|pattern| match Regex::new(pattern) {
+        Ok(pattern) => Some(pattern),
+        Err(_) => {
+            diagnostics::scope("pattern", || {
+                diagnostics::warn(format!(
+                    "enum values are not checked against `pattern` {pattern:?}, which is not a \
+                     supported regular expression"
+                ))
+            });
+            None
+        }
+    });
+    let lenient = options::current().lenient;
+    let matches = |value: &Value| match (schema_type, value) {
+        (openapi::SchemaType::Value, _) | (_, Value::Null) => {
+            nullable || matches!(schema_type, openapi::SchemaType::Value)
+        }
+        (openapi::SchemaType::String, value) => value.is_string(),
+        (openapi::SchemaType::Integer, value) => value.as_f64().is_some_and(|n| n.fract() == 0.0),
+        (openapi::SchemaType::Number, value) => value.is_number(),
+        (openapi::SchemaType::Boolean, value) => value.is_boolean(),
+        (openapi::SchemaType::Object, value) => value.is_object(),
+        (openapi::SchemaType::Array, value) => value.is_array(),
+    };
+    let coerce = |value: &Value| -> Option<Value> {
+        let coerced = match (schema_type, value) {
+            (openapi::SchemaType::String, Value::Number(_) | Value::Bool(_)) => {
+                Value::String(value.to_string())
+            }
+            (
+                openapi::SchemaType::Integer
+                | openapi::SchemaType::Number
+                | openapi::SchemaType::Boolean,
+                Value::String(text),
+            ) => serde_json::from_str(text.trim()).ok()?,
+            _ => return None,
+        };
+        matches(&coerced).then_some(coerced)
+    };
+
+    let checked: Vec<Value> = diagnostics::scope("enum", || {
+        let typed = values.into_iter().enumerate().filter_map(|(i, value)| {
+            if matches(&value) {
+                return Some((i, value));
+            }
+            let coerced = coerce(&value);
+            if lenient && coerced.is_some() {
+                return coerced.map(|coerced| (i, coerced));
+            }
+            let hint = match coerced {
+                Some(_) => ", convert leniently to coerce it",
+                None => "",
+            };
+            diagnostics::scope(i, || {
+                diagnostics::warn(format!(
+                    "dropped enum value {value}, which does not match the schema type{hint}"
+                ))
+            });
+            None
+        });
+
+        let mut checked = Vec::new();
+        for (i, value) in typed {
+            let repeated = diagnostics::scope(i, || {
+                repeated_enum_value(&value, &checked, min_length, max_length, pattern.as_ref())
+            });
+            if !repeated {
+                checked.push(value);
+            }
+        }
+        checked
+    });
+    (!checked.is_empty()).then_some(checked)
+}
+
+/// Reports an enum value which repeats one of the `previous` values, also if only its case
+/// differs, or which violates the length or pattern of its schema. Returns whether it
+/// repeats a value exactly, which is dropped as generated enums cannot hold it twice.
+fn repeated_enum_value(
+    value: &serde_json::Value,
+    previous: &[serde_json::Value],
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    pattern: Option<&Regex>,
+) -> bool {
+    if previous.contains(value) {
+        diagnostics::warn(format!(
+            "dropped enum value {value}, which is listed before"
+        ));
+        return true;
+    }
+    let Some(text) = value.as_str() else {
+        return false;
+    };
+    let lowercase = text.to_lowercase();
+    let same_but_case = previous.iter().find(|other| {
+        other
+            .as_str()
+            .is_some_and(|o| o.to_lowercase() == lowercase)
+    });
+    if let Some(other) = same_but_case {
+        diagnostics::warn(format!(
+            "enum value {value} differs from {other} only in case, generated enums may clash"
+        ));
+    }
+    let len = text.chars().count();
+    if min_length.is_some_and(|min| len < min) {
+        diagnostics::warn(format!("enum value {value} is shorter than `minLength`"));
+    }
+    if max_length.is_some_and(|max| len > max) {
+        diagnostics::warn(format!("enum value {value} is longer than `maxLength`"));
+    }
+    if pattern.is_some_and(|pattern| !pattern.is_match(text)) {
+        diagnostics::warn(format!("enum value {value} does not match `pattern`"));
+    }
+    false
+}
+
+/// Extensions generators put the keywords Swagger 2.0 lacks into, with the keyword and
+/// whether it takes a list of schemas.
+const KEYWORD_EXTENSIONS: [(&str, &str, bool); 3] = [
+    ("x-not", "not", false),
+    ("x-oneOf", "oneOf", true),
+    ("x-anyOf", "anyOf", true),
+];
+
+/// Extensions of a converted schema, with the schemas of [`KEYWORD_EXTENSIONS`] converted
+/// into their keyword. utoipa has no field for these keywords, so they are emitted among the
+/// extensions.
+fn schema_extensions(mut extensions: Extensions) -> Option<HashMap<String, serde_json::Value>> {
+    let keywords: Vec<(&str, serde_json::Value)> = KEYWORD_EXTENSIONS
+        .iter()
+        .filter_map(|&(extension, keyword, list)| {
+            let value = extensions.0.remove(extension)?;
+            diagnostics::scope(extension, || match convert_schemas(&value, list) {
+                Ok(converted) => Some((keyword, converted)),
+                Err(err) => {
+                    diagnostics::warn(format!(
+                        "`{extension}` holds no valid schemas, passed through unchanged: {err}"
+                    ));
+                    extensions.0.insert(extension.to_string(), value);
+                    None
+                }
+            })
+        })
+        .collect();
+    let mut extensions = extensions.into_openapi_extensions();
+    for (keyword, value) in keywords {
+        extensions
+            .get_or_insert_with(HashMap::new)
+            .insert(keyword.to_string(), value);
+    }
+    extensions
+}
+
+/// Converts the Swagger schema `value`, or each schema of the list `value` if `list` is set.
+fn convert_schemas(
+    value: &serde_json::Value,
+    list: bool,
+) -> Result<serde_json::Value, serde_json::Error> {
+    if list {
+        let schemas: Vec<RefOr<Schema>> = serde_json::from_value(value.clone())?;
+        let schemas: Vec<openapi::RefOr<openapi::Schema>> = schemas
+            .into_iter()
+            .enumerate()
+            .map(|(i, schema)| diagnostics::scope(i, || schema.into_openapi_ref()))
+            .collect();
+        serde_json::to_value(schemas)
+    } else {
+        let schema: RefOr<Schema> = serde_json::from_value(value.clone())?;
+        serde_json::to_value(schema.into_openapi_ref::<openapi::Schema>())
+    }
+}
+
+/// Converts the schemas of the common `x-patternProperties` extension like nested schemas.
+/// OpenAPI 3.1 output turns the extension into `patternProperties`, see [`crate::output`].
+fn convert_pattern_properties(extensions: &mut Extensions) {
+    let Some(value) = extensions.0.get_mut("x-patternProperties") else {
+        return;
+    };
+    diagnostics::scope("x-patternProperties", || {
+        match serde_json::from_value::<BTreeMap<String, RefOr<Schema>>>(value.clone()) {
+            Ok(schemas) => {
+                let schemas: BTreeMap<_, openapi::RefOr<openapi::Schema>> = schemas
+                    .into_iter()
+                    .map(|(pattern, schema)| {
+                        let schema = diagnostics::scope(&pattern, || schema.into_openapi_ref());
+                        (pattern, schema)
+                    })
+                    .collect();
+                *value = serde_json::to_value(schemas).unwrap_or_default();
+            }
+            Err(err) => diagnostics::warn(format!(
+                "x-patternProperties is not a map of schemas, passed through unchanged: {err}"
+            )),
+        }
+    });
+}
+
+fn any_type() -> openapi::SchemaType {
+    openapi::SchemaType::Value
+}
+
+fn is_any_type(schema_type: &openapi::SchemaType) -> bool {
+    *schema_type == openapi::SchemaType::Value
+}
+
 fn is_false(value: &bool) -> bool {
     !*value
 }
@@ -149,8 +675,10 @@ fn is_false(value: &bool) -> bool {
 #[skip_serializing_none]
 #[derive(Serialize, Deserialize, Clone, PartialEq)]
 #[cfg_attr(feature = "debug", derive(Debug))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct Object {
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
     pub format: Option<openapi::SchemaFormat>,
     pub title: Option<String>,
     pub description: Option<String>,
@@ -158,9 +686,9 @@ pub struct Object {
 
     pub multiple_of: Option<f64>,
     pub maximum: Option<f64>,
-    pub exclusive_maximum: Option<f64>,
+    pub exclusive_maximum: Option<ExclusiveBound>,
     pub minimum: Option<f64>,
-    pub exclusive_minimum: Option<f64>,
+    pub exclusive_minimum: Option<ExclusiveBound>,
     pub max_length: Option<usize>,
     pub min_length: Option<usize>,
     pub pattern: Option<String>,
@@ -171,7 +699,13 @@ pub struct Object {
     pub required: Vec<String>,
     #[serde(rename = "enum")]
     pub enum_values: Option<Vec<serde_json::Value>>,
-    #[serde(rename = "type")]
+    /// Any type if missing, e.g. for the empty schema `{}`.
+    #[serde(
+        rename = "type",
+        default = "any_type",
+        skip_serializing_if = "is_any_type"
+    )]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
     pub schema_type: openapi::SchemaType,
 
     #[serde(skip_serializing_if = "BTreeMap::is_empty", default = "BTreeMap::new")]
@@ -179,13 +713,16 @@ pub struct Object {
     pub additional_properties: Option<Box<AdditionalProperties<Schema>>>,
 
     pub read_only: Option<bool>,
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<serde_json::Value>"))]
     pub xml: Option<openapi::xml::Xml>,
     pub example: Option<serde_json::Value>,
+    /// Name of the property telling the schemas extending this one apart.
+    pub discriminator: Option<String>,
 
     #[serde(
         flatten,
-        skip_serializing_if = "HashMap::is_empty",
-        default = "HashMap::new"
+        skip_serializing_if = "BTreeMap::is_empty",
+        default = "BTreeMap::new"
     )]
     pub extensions: Extensions,
 }
@@ -193,8 +730,10 @@ pub struct Object {
 #[skip_serializing_none]
 #[derive(Serialize, Deserialize, Clone, PartialEq)]
 #[cfg_attr(feature = "debug", derive(Debug))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct AllOf {
+    #[serde(rename = "allOf")]
     pub items: Vec<RefOr<Schema>>,
     pub title: Option<String>,
     pub description: Option<String>,
@@ -204,8 +743,8 @@ pub struct AllOf {
 
     #[serde(
         flatten,
-        skip_serializing_if = "HashMap::is_empty",
-        default = "HashMap::new"
+        skip_serializing_if = "BTreeMap::is_empty",
+        default = "BTreeMap::new"
     )]
     pub extensions: Extensions,
 }
@@ -245,4 +784,238 @@ mod tests {
 
         assert_json_eq!(schemas, serde_json::to_value(openapi_schemas).unwrap());
     }
+
+    #[test]
+    fn circular_all_of() {
+        let definitions: Definitions = serde_json::from_value(serde_json::json!({
+            "A": {"allOf": [{"$ref": "#/definitions/B"}, {"type": "object"}]},
+            "B": {"allOf": [{"$ref": "#/definitions/A"}]},
+            "C": {"allOf": [{"$ref": "#/definitions/C"}]},
+            "D": {"allOf": [{"$ref": "#/definitions/A"}]}
+        }))
+        .unwrap();
+
+        let (_, diagnostics) = diagnostics::collect(|| {
+            BTreeMap::<_, openapi::RefOr<openapi::Schema>>::from(definitions)
+        });
+
+        let reported: Vec<(&str, &str)> = diagnostics
+            .iter()
+            .map(|d| (d.pointer.as_str(), d.message.as_str()))
+            .collect();
+        assert_eq!(
+            reported,
+            vec![
+                (
+                    "/A/allOf",
+                    "circular reference #/definitions/A -> #/definitions/B -> #/definitions/A"
+                ),
+                (
+                    "/C/allOf",
+                    "circular reference #/definitions/C -> #/definitions/C"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn exclusive_bounds() {
+        let schemas: Vec<Schema> = serde_json::from_value(serde_json::json!([
+            {"type": "integer", "minimum": 0, "exclusiveMinimum": true, "maximum": 9, "exclusiveMaximum": false},
+            {"type": "number", "exclusiveMaximum": 1.5}
+        ]))
+        .unwrap();
+
+        let schemas: Vec<openapi::Schema> = schemas.into_iter().map(Into::into).collect();
+
+        assert_json_eq!(
+            serde_json::to_value(schemas).unwrap(),
+            serde_json::json!([
+                {"type": "integer", "exclusiveMinimum": 0.0, "maximum": 9.0},
+                {"type": "number", "exclusiveMaximum": 1.5}
+            ])
+        );
+    }
+
+    #[test]
+    fn type_array() {
+        let schemas: Vec<Schema> = serde_json::from_value(serde_json::json!([
+            {"type": ["integer"]},
+            {"type": ["string", "null"], "format": "date"}
+        ]))
+        .unwrap();
+
+        let schemas: Vec<openapi::Schema> = schemas.into_iter().map(Into::into).collect();
+
+        assert_json_eq!(
+            serde_json::to_value(schemas).unwrap(),
+            serde_json::json!([
+                {"type": "integer"},
+                {"type": "string", "format": "date", "nullable": true}
+            ])
+        );
+    }
+
+    #[test]
+    fn cached_schema_diagnostics() {
+        let schema = serde_json::json!({
+            "type": "object",
+            "additionalProperties": {"type": 5}
+        });
+        let definitions: Definitions = serde_json::from_value(serde_json::json!({
+            "A": {"type": "object", "properties": {"p": schema}},
+            "B": {"type": "object", "properties": {"p": schema}}
+        }))
+        .unwrap();
+
+        let options = crate::ConvertOptions {
+            cache_schemas: true,
+            ..Default::default()
+        };
+        let (schemas, reported) = options::scope(std::sync::Arc::new(options), || {
+            diagnostics::collect(|| {
+                BTreeMap::<_, openapi::RefOr<openapi::Schema>>::from(definitions)
+            })
+        });
+
+        assert_eq!(
+            serde_json::to_value(&schemas["A"]).unwrap(),
+            serde_json::to_value(&schemas["B"]).unwrap()
+        );
+        let pointers: Vec<_> = reported.iter().map(|d| d.pointer.as_str()).collect();
+        assert_eq!(
+            pointers,
+            [
+                "/A/properties/p/additionalProperties",
+                "/B/properties/p/additionalProperties"
+            ]
+        );
+    }
+
+    #[test]
+    fn mixed_type_enum() {
+        let definitions: Definitions = serde_json::from_value(serde_json::json!({
+            "Level": {"type": "integer", "enum": [1, "2", "high", {"level": 3}]}
+        }))
+        .unwrap();
+        let convert = |lenient| {
+            let options = crate::ConvertOptions {
+                lenient,
+                ..Default::default()
+            };
+            options::scope(std::sync::Arc::new(options), || {
+                diagnostics::collect(|| {
+                    BTreeMap::<_, openapi::RefOr<openapi::Schema>>::from(definitions.clone())
+                })
+            })
+        };
+
+        let (strict, strict_reported) = convert(false);
+        let (lenient, lenient_reported) = convert(true);
+
+        let strict = serde_json::to_value(&strict["Level"]).unwrap();
+        assert_eq!(strict["enum"], serde_json::json!([1]));
+        let pointers: Vec<_> = strict_reported.iter().map(|d| d.pointer.as_str()).collect();
+        assert_eq!(
+            pointers,
+            ["/Level/enum/1", "/Level/enum/2", "/Level/enum/3"]
+        );
+        let lenient = serde_json::to_value(&lenient["Level"]).unwrap();
+        assert_eq!(lenient["enum"], serde_json::json!([1, 2]));
+        assert_eq!(lenient_reported.len(), 2);
+    }
+
+    #[test]
+    fn repeated_enum_values() {
+        let definitions: Definitions = serde_json::from_value(serde_json::json!({
+            "Kind": {
+                "type": "string",
+                "maxLength": 5,
+                "pattern": "^[a-z]",
+                "enum": ["cat", "dog", "cat", "Cat", "hamster"]
+            }
+        }))
+        .unwrap();
+
+        let (schemas, reported) = diagnostics::collect(|| {
+            BTreeMap::<_, openapi::RefOr<openapi::Schema>>::from(definitions)
+        });
+
+        let kind = serde_json::to_value(&schemas["Kind"]).unwrap();
+        assert_eq!(
+            kind["enum"],
+            serde_json::json!(["cat", "dog", "Cat", "hamster"])
+        );
+        let reported: Vec<_> = reported
+            .iter()
+            .map(|d| (d.pointer.as_str(), d.message.as_str()))
+            .collect();
+        assert_eq!(
+            reported,
+            [
+                (
+                    "/Kind/enum/2",
+                    "dropped enum value \"cat\", which is listed before"
+                ),
+                (
+                    "/Kind/enum/3",
+                    "enum value \"Cat\" differs from \"cat\" only in case, generated enums may \
+                     clash"
+                ),
+                (
+                    "/Kind/enum/3",
+                    "enum value \"Cat\" does not match `pattern`"
+                ),
+                (
+                    "/Kind/enum/4",
+                    "enum value \"hamster\" is longer than `maxLength`"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_schema() {
+        let definitions: Definitions = serde_json::from_value(serde_json::json!({
+            "Any": {},
+            "Described": {"description": "anything goes"}
+        }))
+        .unwrap();
+
+        let schemas = BTreeMap::<_, openapi::RefOr<openapi::Schema>>::from(definitions);
+
+        assert_eq!(
+            serde_json::to_value(&schemas["Any"]).unwrap(),
+            serde_json::json!({})
+        );
+        assert_eq!(
+            serde_json::to_value(&schemas["Described"]).unwrap(),
+            serde_json::json!({"description": "anything goes"})
+        );
+    }
+
+    #[test]
+    fn keyword_extensions() {
+        let definitions: Definitions = serde_json::from_value(serde_json::json!({
+            "Name": {"type": "string", "x-not": {"enum": ["admin"]}},
+            "Id": {"x-oneOf": [{"type": "string"}, {"$ref": "#/definitions/Name"}]},
+            "Broken": {"type": "string", "x-not": {"type": 5}}
+        }))
+        .unwrap();
+
+        let (schemas, reported) = diagnostics::collect(|| {
+            BTreeMap::<_, openapi::RefOr<openapi::Schema>>::from(definitions)
+        });
+
+        assert_eq!(
+            serde_json::to_value(&schemas["Name"]).unwrap(),
+            serde_json::json!({"type": "string", "not": {"enum": ["admin"]}})
+        );
+        assert_eq!(
+            serde_json::to_value(&schemas["Id"]).unwrap(),
+            serde_json::json!({"oneOf": [{"type": "string"}, {"$ref": "#/components/schemas/Name"}]})
+        );
+        let pointers: Vec<_> = reported.iter().map(|d| d.pointer.as_str()).collect();
+        assert_eq!(pointers, ["/Broken/x-not"]);
+    }
 }
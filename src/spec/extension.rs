@@ -0,0 +1,167 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    sync::Arc,
+};
+
+/// What happens to a vendor extension when it is converted.
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub enum ExtensionAction {
+    /// Drop the extension, e.g. because it was mapped onto a regular keyword.
+    Consume,
+    /// Emit the extension unchanged.
+    PassThrough,
+    /// Emit the extension under the given name with the given value.
+    Transform(String, serde_json::Value),
+}
+
+/// Handler deciding the [`ExtensionAction`] for an extension, see [`ExtensionRegistry`].
+pub trait ExtensionMapper: Send + Sync {
+    fn map(&self, name: &str, value: &serde_json::Value) -> ExtensionAction;
+}
+
+impl<F> ExtensionMapper for F
+where
+    F: Fn(&str, &serde_json::Value) -> ExtensionAction + Send + Sync,
+{
+    fn map(&self, name: &str, value: &serde_json::Value) -> ExtensionAction {
+        self(name, value)
+    }
+}
+
+impl ExtensionMapper for ExtensionAction {
+    fn map(&self, _: &str, _: &serde_json::Value) -> ExtensionAction {
+        self.clone()
+    }
+}
+
+/// Handlers keyed by extension name. Extensions without a registered handler are passed
+/// through unchanged, unless [`ExtensionRegistry::keep_only`] restricts which extensions
+/// survive.
+///
+/// The default registry consumes `x-nullable`, which is mapped onto `nullable` instead.
+#[derive(Clone)]
+pub struct ExtensionRegistry {
+    mappers: HashMap<String, Arc<dyn ExtensionMapper>>,
+    /// Names of the only extensions which are emitted, all if unset.
+    allowed: Option<BTreeSet<String>>,
+}
+
+impl Default for ExtensionRegistry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.register("x-nullable", ExtensionAction::Consume);
+        registry
+    }
+}
+
+impl ExtensionRegistry {
+    /// Registry without any handlers, passing every extension through.
+    pub fn empty() -> Self {
+        Self {
+            mappers: HashMap::new(),
+            allowed: None,
+        }
+    }
+
+    /// Drops every extension but `names` on every object, before any handler is applied.
+    /// Calling it again adds to the allowed names.
+    pub fn keep_only<I, N>(&mut self, names: I) -> &mut Self
+    where
+        I: IntoIterator<Item = N>,
+        N: Into<String>,
+    {
+        self.allowed
+            .get_or_insert_with(BTreeSet::new)
+            .extend(names.into_iter().map(Into::into));
+        self
+    }
+
+    /// Registers `mapper` for the extension `name`, replacing any previous handler.
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        mapper: impl ExtensionMapper + 'static,
+    ) -> &mut Self {
+        self.mappers.insert(name.into(), Arc::new(mapper));
+        self
+    }
+
+    /// Removes the handler for the extension `name`, so it is passed through again.
+    pub fn unregister(&mut self, name: &str) -> &mut Self {
+        self.mappers.remove(name);
+        self
+    }
+
+    fn allows(&self, name: &str) -> bool {
+        match &self.allowed {
+            Some(allowed) => allowed.contains(name),
+            None => true,
+        }
+    }
+
+    pub(crate) fn apply(
+        &self,
+        extensions: BTreeMap<String, serde_json::Value>,
+    ) -> BTreeMap<String, serde_json::Value> {
+        extensions
+            .into_iter()
+            .filter(|(name, _)| self.allows(name))
+            .filter_map(|(name, value)| {
+                let Some(mapper) = self.mappers.get(&name) else {
+                    return Some((name, value));
+                };
+                match mapper.map(&name, &value) {
+                    ExtensionAction::Consume => None,
+                    ExtensionAction::PassThrough => Some((name, value)),
+                    ExtensionAction::Transform(name, value) => Some((name, value)),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn apply_registered_mappers() {
+        let mut registry = ExtensionRegistry::default();
+        registry.register("x-ms-enum", |_: &str, value: &serde_json::Value| {
+            ExtensionAction::Transform("x-enum-varnames".to_string(), value["values"].clone())
+        });
+
+        let extensions = BTreeMap::from([
+            ("x-nullable".to_string(), json!(true)),
+            ("x-ms-enum".to_string(), json!({"values": ["A", "B"]})),
+            ("x-internal".to_string(), json!(true)),
+        ]);
+
+        assert_eq!(
+            registry.apply(extensions),
+            BTreeMap::from([
+                ("x-enum-varnames".to_string(), json!(["A", "B"])),
+                ("x-internal".to_string(), json!(true)),
+            ])
+        );
+    }
+
+    #[test]
+    fn keep_only_allowed() {
+        let mut registry = ExtensionRegistry::default();
+        registry.keep_only(["x-logo", "x-tagGroups"]);
+
+        let extensions = BTreeMap::from([
+            ("x-logo".to_string(), json!({"url": "logo.png"})),
+            ("x-internal".to_string(), json!(true)),
+        ]);
+
+        assert_eq!(
+            registry.apply(extensions),
+            BTreeMap::from([("x-logo".to_string(), json!({"url": "logo.png"}))])
+        );
+    }
+}
@@ -2,27 +2,72 @@ use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 use std::{
+    cell::RefCell,
     collections::{BTreeMap, HashMap},
     ops::Deref,
+    rc::Rc,
 };
 use utoipa::openapi::{self, OpenApiBuilder};
 
+use crate::{diagnostics, discriminator, options, output, sanitize, trace, walk};
+
+mod borrowed;
 mod definition;
+mod extension;
+mod operation_id;
 mod path;
+mod redoc;
 mod response;
 mod security;
 mod server;
+pub mod v1;
 
+pub use borrowed::*;
 pub use definition::*;
+pub use extension::*;
+pub use operation_id::*;
 pub use path::*;
 pub use response::*;
 pub use security::*;
 pub use server::*;
 pub use utoipa::openapi::Info;
 
-#[derive(Default, Clone, PartialEq)]
+thread_local! {
+    /// Rewritten ref locations of the current conversion. Specs usually reference the same
+    /// definitions many times, so each location is only rewritten once and shared from then
+    /// on. The only copy made per reference is the one the utoipa [`openapi::Ref`] owns.
+    ///
+    /// Property names are not interned. The utoipa schemas own them as `String` keys, so each
+    /// property needs its own copy whether or not the names are shared, and the names parsed
+    /// from the document are moved into them already.
+    static REF_LOCATIONS: RefCell<HashMap<Rc<str>, Rc<str>>> = RefCell::new(HashMap::new());
+}
+
+/// Drops the caches of the current conversion on this thread.
+pub(crate) fn clear_caches() {
+    REF_LOCATIONS.with(|cache| cache.borrow_mut().clear());
+    definition::clear_schema_cache();
+}
+
+#[derive(Default, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "debug", derive(Debug))]
-pub struct Extensions(pub HashMap<String, serde_json::Value>);
+pub struct Extensions(pub BTreeMap<String, serde_json::Value>);
+
+/// Extensions are flattened into the schema of the object holding them.
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for Extensions {
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn schema_name() -> String {
+        "Extensions".to_string()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        <BTreeMap<String, serde_json::Value>>::json_schema(gen)
+    }
+}
 
 impl Extensions {
     pub fn nullable(&self) -> bool {
@@ -32,24 +77,28 @@ impl Extensions {
             .unwrap_or(false)
     }
 
-    pub fn into_openapi_extensions(mut self) -> Option<HashMap<String, serde_json::Value>> {
+    /// Maps the extensions through the [`ExtensionRegistry`] of the current conversion.
+    ///
+    /// The extensions are applied in name order, so transformed names clash the same way on
+    /// every run.
+    pub fn into_openapi_extensions(self) -> Option<HashMap<String, serde_json::Value>> {
         if self.0.is_empty() {
             return None;
         }
-        self.0.remove("x-nullable");
-        Some(self.0)
+        let extensions = options::current().extensions.apply(self.0);
+        (!extensions.is_empty()).then(|| extensions.into_iter().collect())
     }
 }
 
 impl Deref for Extensions {
-    type Target = HashMap<String, serde_json::Value>;
+    type Target = BTreeMap<String, serde_json::Value>;
 
     fn deref(&self) -> &Self::Target {
         &self.0
     }
 }
 
-impl From<Extensions> for HashMap<String, serde_json::Value> {
+impl From<Extensions> for BTreeMap<String, serde_json::Value> {
     fn from(value: Extensions) -> Self {
         value.0
     }
@@ -69,7 +118,7 @@ impl<'de> serde::de::Deserialize<'de> for Extensions {
     where
         D: serde::Deserializer<'de>,
     {
-        let map: HashMap<String, serde_json::Value> = HashMap::deserialize(deserializer)?;
+        let map: BTreeMap<String, serde_json::Value> = BTreeMap::deserialize(deserializer)?;
         let map = map
             .into_iter()
             .filter(|(k, _)| k.starts_with("x-"))
@@ -78,21 +127,165 @@ impl<'de> serde::de::Deserialize<'de> for Extensions {
     }
 }
 
+/// Where rewritten references point, see [`ConvertOptions::refs`](crate::ConvertOptions::refs).
+///
+/// By default `#/definitions/Pet` becomes `#/components/schemas/Pet`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RefNamespace {
+    /// Document holding the components, e.g. a shared `components.json`. Empty for the
+    /// converted document itself.
+    pub document: String,
+    /// Prefix of the component names, e.g. `Billing_` to keep them apart when merging the
+    /// converted document with others. The components of the converted document are renamed
+    /// accordingly.
+    pub name_prefix: String,
+    /// Prefixes removed from the definition names, e.g. `io.company.model.`. Only the first
+    /// matching prefix is removed.
+    pub strip_prefixes: Vec<String>,
+    /// Casing the definition names are rewritten to, e.g. [`Casing::Pascal`] for
+    /// `user_account` to become `UserAccount`.
+    pub name_case: Option<Casing>,
+}
+
+impl RefNamespace {
+    /// Name of the component converted from the definition `name`.
+    pub fn component_name(&self, name: &str) -> String {
+        let name = self
+            .strip_prefixes
+            .iter()
+            .find_map(|prefix| name.strip_prefix(prefix.as_str()))
+            .unwrap_or(name);
+        match self.name_case {
+            Some(casing) => format!("{}{}", self.name_prefix, casing.apply(name)),
+            None => format!("{}{name}", self.name_prefix),
+        }
+    }
+
+    fn renames(&self) -> bool {
+        !self.name_prefix.is_empty() || !self.strip_prefixes.is_empty() || self.name_case.is_some()
+    }
+}
+
+/// Renames converted components to the name the references of the current conversion use.
+///
+/// Components ending up with the same name are reported, only the last of them is kept.
+pub(crate) fn namespaced<V>(components: BTreeMap<String, V>) -> BTreeMap<String, V> {
+    let options = options::current();
+    let namespace = &options.refs;
+    if !namespace.renames() {
+        return components;
+    }
+    trace::phase("refs", || {
+        let mut renamed = BTreeMap::new();
+        for (name, component) in components {
+            let component_name = namespace.component_name(&name);
+            if renamed.insert(component_name.clone(), component).is_some() {
+                diagnostics::scope(&name, || {
+                    diagnostics::warn(format!(
+                        "renamed to `{component_name}` like another component, which it replaces"
+                    ))
+                });
+            }
+        }
+        renamed
+    })
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "debug", derive(Debug))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum RefOr<T> {
-    Ref(openapi::Ref),
+    Ref(Ref),
     T(T),
 }
 
+/// https://swagger.io/specification/v2/#reference-object
+///
+/// Swagger ignores the members next to `$ref`, but specs commonly put a description, an
+/// example or extensions like `x-nullable` there.
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Ref {
+    #[serde(rename = "$ref")]
+    pub ref_location: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub example: Option<serde_json::Value>,
+    #[serde(
+        flatten,
+        skip_serializing_if = "BTreeMap::is_empty",
+        default = "BTreeMap::new"
+    )]
+    pub extensions: Extensions,
+}
+
+impl Ref {
+    /// Names of the members next to `$ref`.
+    fn siblings(&self) -> Vec<&str> {
+        [
+            self.title.as_ref().map(|_| "title"),
+            self.description.as_ref().map(|_| "description"),
+            self.example.as_ref().map(|_| "example"),
+        ]
+        .into_iter()
+        .flatten()
+        .chain(self.extensions.keys().map(String::as_str))
+        .collect()
+    }
+}
+
+/// Converted value a [`RefOr`] can reference.
+pub(crate) trait Referenced: Sized {
+    /// The converted `reference` with the members next to it in Swagger, which a reference
+    /// cannot carry in OpenAPI 3.0. They are reported and dropped by default.
+    fn with_siblings(reference: openapi::Ref, siblings: Ref) -> openapi::RefOr<Self> {
+        for sibling in siblings.siblings() {
+            diagnostics::scope(sibling, || {
+                diagnostics::warn(format!("dropped `{sibling}` next to `$ref`"))
+            });
+        }
+        openapi::RefOr::Ref(reference)
+    }
+}
+
+impl Referenced for openapi::Response {}
+
+impl Referenced for openapi::Schema {
+    /// Wraps a reference with siblings into `allOf`, which can carry them, e.g.
+    /// `{"allOf": [{"$ref": "…"}], "nullable": true}` for `x-nullable`. OpenAPI 3.1 output
+    /// moves them back next to the `$ref`, see [`crate::output`].
+    fn with_siblings(reference: openapi::Ref, siblings: Ref) -> openapi::RefOr<Self> {
+        let nullable = siblings.extensions.nullable();
+        let extensions = siblings.extensions.into_openapi_extensions();
+        let annotated = siblings.title.is_some()
+            || siblings.description.is_some()
+            || siblings.example.is_some();
+        if !annotated && !nullable && extensions.is_none() {
+            return openapi::RefOr::Ref(reference);
+        }
+        let mut all_of = openapi::AllOfBuilder::new()
+            .title(siblings.title)
+            .description(sanitize::description(siblings.description))
+            .example(siblings.example)
+            .nullable(nullable)
+            .extensions(extensions)
+            .build();
+        all_of.items = vec![openapi::RefOr::Ref(reference)];
+        openapi::RefOr::T(openapi::Schema::AllOf(all_of))
+    }
+}
+
 impl<T> RefOr<T> {
-    fn into_openapi_ref<V: From<T>>(self) -> openapi::RefOr<V> {
+    pub(crate) fn into_openapi_ref<V: From<T> + Referenced>(self) -> openapi::RefOr<V> {
         match self {
             RefOr::T(v) => openapi::RefOr::T(v.into()),
-            RefOr::Ref(openapi::Ref { ref_location, .. }) => {
-                let ref_location = openapi::Ref::new(Self::openapi_ref_location(&ref_location));
-                openapi::RefOr::Ref(ref_location)
+            RefOr::Ref(siblings) => {
+                let reference =
+                    openapi::Ref::new(&*Self::openapi_ref_location(&siblings.ref_location));
+                V::with_siblings(reference, siblings)
             }
         }
     }
@@ -101,28 +294,48 @@ impl<T> RefOr<T> {
     fn try_into_openapi_ref<V: TryFrom<T>>(self) -> Result<openapi::RefOr<V>, V::Error> {
         match self {
             RefOr::T(v) => Ok(openapi::RefOr::T(v.try_into()?)),
-            RefOr::Ref(openapi::Ref { ref_location, .. }) => {
-                let ref_location = openapi::Ref::new(Self::openapi_ref_location(&ref_location));
+            RefOr::Ref(Ref { ref_location, .. }) => {
+                let ref_location = openapi::Ref::new(&*Self::openapi_ref_location(&ref_location));
                 Ok(openapi::RefOr::Ref(ref_location))
             }
         }
     }
 
-    fn openapi_ref_location(ref_location: &str) -> String {
-        let prefix = ["#", "components"].into_iter();
-        let ref_location = ref_location
-            .split('/')
-            .skip(1)
-            .map(|element| match element {
-                "definitions" => "schemas",
-                _ => element,
-            });
-        Itertools::intersperse(prefix.chain(ref_location), "/").collect()
+    fn openapi_ref_location(ref_location: &str) -> Rc<str> {
+        REF_LOCATIONS.with(|cache| {
+            if let Some(rewritten) = cache.borrow().get(ref_location) {
+                return Rc::clone(rewritten);
+            }
+
+            let options = options::current();
+            let namespace = &options.refs;
+            let elements = ref_location
+                .split('/')
+                .skip(1)
+                .enumerate()
+                .map(|(i, element)| match (i, element) {
+                    (0, "definitions") => "schemas".to_string(),
+                    (1, name) => namespace.component_name(name),
+                    (_, element) => element.to_string(),
+                });
+            let rewritten: Rc<str> = format!(
+                "{}#/components/{}",
+                namespace.document,
+                elements.format("/")
+            )
+            .into();
+
+            cache
+                .borrow_mut()
+                .insert(ref_location.into(), Rc::clone(&rewritten));
+            rewritten
+        })
     }
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "debug", derive(Debug))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum AdditionalProperties<T> {
     RefOr(RefOr<T>),
@@ -131,7 +344,7 @@ pub enum AdditionalProperties<T> {
 }
 
 impl<T> AdditionalProperties<T> {
-    fn into_openapi_additional_properties<V: From<T> + Default>(
+    fn into_openapi_additional_properties<V: From<T> + Default + Referenced>(
         self,
     ) -> openapi::schema::AdditionalProperties<V> {
         use openapi::schema;
@@ -139,13 +352,17 @@ impl<T> AdditionalProperties<T> {
             Self::RefOr(r) => schema::AdditionalProperties::RefOr(r.into_openapi_ref()),
             Self::FreeForm(f) => schema::AdditionalProperties::FreeForm(f),
             // discard any other invalid properties
-            Self::Any(_) => schema::AdditionalProperties::RefOr(openapi::RefOr::T(V::default())),
+            Self::Any(_) => {
+                diagnostics::warn("dropped invalid additionalProperties");
+                schema::AdditionalProperties::RefOr(openapi::RefOr::T(V::default()))
+            }
         }
     }
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq)]
 #[cfg_attr(feature = "debug", derive(Debug))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum SwaggerVersion {
     #[serde(rename = "2.0")]
     Version2,
@@ -154,55 +371,837 @@ pub enum SwaggerVersion {
 #[skip_serializing_none]
 #[derive(Serialize, Deserialize, Clone, PartialEq)]
 #[cfg_attr(feature = "debug", derive(Debug))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct Swagger {
     pub swagger: SwaggerVersion,
+    #[cfg_attr(feature = "schemars", schemars(with = "serde_json::Value"))]
     pub info: Info,
     pub host: Option<String>,
     pub base_path: Option<String>,
     pub schemes: Option<Vec<ProtocolSchemes>>,
     pub consumes: Option<Vec<String>>,
     pub produces: Option<Vec<String>>,
+    /// The `x-servers` extension, which replaces the servers generated from `host`.
+    #[serde(rename = "x-servers")]
+    pub servers: Option<serde_json::Value>,
+    /// The `x-hosts` extension, listing the hosts of the environments of the API. Each of
+    /// them gets servers like `host`, which it replaces.
+    #[serde(rename = "x-hosts")]
+    pub hosts: Option<serde_json::Value>,
+    /// The `x-server-variables` extension, declaring the placeholders of a templated `host`
+    /// or `basePath` by name.
+    #[serde(rename = "x-server-variables")]
+    pub server_variables: Option<serde_json::Value>,
+    /// The path items of the `x-webhooks` extension, see [`webhooks`].
+    #[serde(rename = "x-webhooks")]
+    pub webhooks: Option<BTreeMap<String, PathItem>>,
+    /// ReDoc's `x-tagGroups`, grouping the `tags` in its navigation.
+    #[serde(rename = "x-tagGroups")]
+    pub tag_groups: Option<serde_json::Value>,
+    /// ReDoc's `x-logo` put at the root rather than into `info`, where it is moved to.
+    #[serde(rename = "x-logo")]
+    pub logo: Option<serde_json::Value>,
     pub paths: Paths,
     pub definitions: Option<Definitions>,
     pub responses: Option<Responses>,
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub security_definitions: BTreeMap<String, SecurityScheme>,
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<serde_json::Value>"))]
     pub security: Option<Vec<openapi::SecurityRequirement>>,
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<serde_json::Value>"))]
     pub tags: Option<Vec<openapi::Tag>>,
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<serde_json::Value>"))]
     pub external_docs: Option<openapi::ExternalDocs>,
 }
 
+/// Converts the document into the utoipa model alone, which is lossy: the passes of
+/// [`crate::convert`] are skipped, like checking dangling references and applying the
+/// [`ConvertOptions`](crate::ConvertOptions), and members the model has no place for, like
+/// `x-webhooks`, `x-tagGroups` and `x-logo`, are dropped. Use [`crate::convert`] and
+/// [`crate::Conversion::to_value`] for the complete document.
 impl From<Swagger> for openapi::OpenApi {
-    fn from(swagger: Swagger) -> Self {
+    fn from(mut swagger: Swagger) -> Self {
+        let dropped = security::dropped_schemes(&swagger.security_definitions);
+        security::drop_requirements(&mut swagger, &dropped);
         let responses: openapi::Responses = if swagger.responses.is_some() {
-            swagger.responses.unwrap().into()
+            diagnostics::scope("responses", || swagger.responses.unwrap().into())
         } else {
             openapi::Responses::new()
         };
 
         let mut components = openapi::Components::new();
         components.schemas = if swagger.definitions.is_some() {
-            swagger.definitions.unwrap().into()
+            diagnostics::scope("definitions", || {
+                namespaced(trace::phase("definitions", || {
+                    swagger.definitions.unwrap().into()
+                }))
+            })
         } else {
             BTreeMap::new()
         };
-        components.responses = responses.responses;
-        components.security_schemes = swagger
-            .security_definitions
-            .into_iter()
-            .map(|(k, v)| (k, v.into()))
-            .collect();
-        let servers =
-            server::openapi_servers_from_host(swagger.schemes, swagger.host, swagger.base_path);
+        components.responses = diagnostics::scope("responses", || namespaced(responses.responses));
+        components.security_schemes = diagnostics::scope("securityDefinitions", || {
+            swagger
+                .security_definitions
+                .into_iter()
+                .filter_map(|(k, v)| {
+                    let v: Option<openapi::security::SecurityScheme> =
+                        diagnostics::scope(&k, || v.into());
+                    Some((k, v?))
+                })
+                .collect()
+        });
+        let options = options::current();
+        let base_path = match &options.base_path {
+            Some(base_path) => Some(base_path.apply(swagger.base_path)),
+            None => swagger.base_path,
+        };
+        let overridden = options.host.is_some() || options.base_path.is_some();
+        let servers = match server::x_servers(swagger.servers) {
+            Some(servers) if !overridden => Some(servers),
+            _ => {
+                let hosts = match server::x_hosts(swagger.hosts) {
+                    Some(hosts) if options.host.is_none() => hosts,
+                    _ => options
+                        .host
+                        .clone()
+                        .or(swagger.host)
+                        .map(Host::Name)
+                        .into_iter()
+                        .collect(),
+                };
+                let servers = server::openapi_servers_from_hosts(swagger.schemes, hosts, base_path);
+                server::variables(servers, swagger.server_variables)
+            }
+        };
+        let mut info = swagger.info;
+        info.description = sanitize::description(info.description);
+        if let Some(license) = &options.license {
+            info.license = Some(license.clone());
+        }
+        if let Some(contact) = &options.contact {
+            info.contact = Some(contact.clone());
+        }
+        if let Some(terms_of_service) = &options.terms_of_service {
+            info.terms_of_service = Some(terms_of_service.clone());
+        }
+
+        let paths: openapi::Paths =
+            diagnostics::scope("paths", || trace::phase("paths", || swagger.paths.into()));
+
+        let tags = tags_with_external_docs(swagger.tags, &options.tag_external_docs);
+        let external_docs = options.external_docs.clone().or(swagger.external_docs);
 
         OpenApiBuilder::new()
-            .info(swagger.info)
-            .paths(swagger.paths)
+            .info(info)
+            .paths(paths)
             .servers(servers)
             .components(Some(components))
             .security(swagger.security)
-            .external_docs(swagger.external_docs)
+            .tags(tags)
+            .external_docs(external_docs)
             .build()
     }
 }
+
+/// Members of the converted document the utoipa model has no place for, which are added when
+/// it is serialized, see [`crate::Conversion::to_value`].
+#[derive(Default, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub(crate) struct Additions {
+    /// Converted `x-webhooks`, only kept for OpenAPI 3.1 output.
+    pub webhooks: Option<serde_json::Value>,
+    /// Extensions of the root, like ReDoc's `x-tagGroups`.
+    pub extensions: BTreeMap<String, serde_json::Value>,
+    /// Extensions of `info` found at the root, like ReDoc's `x-logo`.
+    pub info_extensions: BTreeMap<String, serde_json::Value>,
+}
+
+impl Additions {
+    /// Takes the members of `swagger` which are added to the converted document. Extensions
+    /// are mapped through the [`ExtensionRegistry`] like any other.
+    pub(crate) fn take(swagger: &mut Swagger) -> Self {
+        let webhooks = webhooks(swagger.webhooks.take());
+        let mut extensions = BTreeMap::new();
+        if let Some(tag_groups) = swagger.tag_groups.take() {
+            diagnostics::scope("x-tagGroups", || redoc::tag_groups(&tag_groups, swagger));
+            extensions.insert("x-tagGroups".to_string(), tag_groups);
+        }
+        let mut info_extensions = BTreeMap::new();
+        if let Some(logo) = swagger.logo.take() {
+            diagnostics::scope("x-logo", || {
+                diagnostics::warn("moved `x-logo` into `info`, where ReDoc expects it")
+            });
+            info_extensions.insert("x-logo".to_string(), logo);
+        }
+        let mapped = |extensions| {
+            Extensions(extensions)
+                .into_openapi_extensions()
+                .unwrap_or_default()
+                .into_iter()
+                .collect()
+        };
+        Self {
+            webhooks,
+            extensions: mapped(extensions),
+            info_extensions: mapped(info_extensions),
+        }
+    }
+
+    /// Adds the members to the serialized converted `document`. Extensions `info` already
+    /// has are kept.
+    pub(crate) fn apply(&self, document: &mut serde_json::Value) {
+        if let Some(webhooks) = &self.webhooks {
+            document["webhooks"] = webhooks.clone();
+        }
+        for (name, value) in &self.extensions {
+            document[name] = value.clone();
+        }
+        if let Some(serde_json::Value::Object(info)) = document.get_mut("info") {
+            for (name, value) in &self.info_extensions {
+                info.entry(name.clone()).or_insert_with(|| value.clone());
+            }
+        }
+    }
+}
+
+/// Converts the path items of an `x-webhooks` extension into the `webhooks` of OpenAPI 3.1
+/// output. OpenAPI 3.0 has no webhooks, so they are reported and dropped otherwise.
+fn webhooks(webhooks: Option<BTreeMap<String, PathItem>>) -> Option<serde_json::Value> {
+    let webhooks = webhooks?;
+    diagnostics::scope("x-webhooks", || {
+        if options::current().output.target != output::Target::V3_1 {
+            diagnostics::warn("dropped `x-webhooks`, webhooks need OpenAPI 3.1 output");
+            return None;
+        }
+        let converted: BTreeMap<String, openapi::PathItem> = webhooks
+            .into_iter()
+            .map(|(name, path_item)| {
+                let path_item = diagnostics::scope(&name, || path_item.into());
+                (name, path_item)
+            })
+            .collect();
+        serde_json::to_value(converted).ok()
+    })
+}
+
+/// Top level `tags` with the `external_docs` by tag name set, declaring the tags missing.
+fn tags_with_external_docs(
+    tags: Option<Vec<openapi::Tag>>,
+    external_docs: &BTreeMap<String, openapi::ExternalDocs>,
+) -> Option<Vec<openapi::Tag>> {
+    let mut tags = tags.unwrap_or_default();
+    for tag in &mut tags {
+        tag.description = sanitize::description(tag.description.take());
+    }
+    for (name, docs) in external_docs {
+        match tags.iter_mut().find(|tag| tag.name == *name) {
+            Some(tag) => tag.external_docs = Some(docs.clone()),
+            None => {
+                let mut tag = openapi::Tag::new(name);
+                tag.external_docs = Some(docs.clone());
+                tags.push(tag);
+            }
+        }
+    }
+    (!tags.is_empty()).then_some(tags)
+}
+
+/// Converts `swagger` like its `From` conversion and reports every local `$ref` whose
+/// rewritten target is missing from the converted document, at the pointer of the `$ref`.
+/// References rewritten into another document are not checked.
+///
+/// Discriminators which would break polymorphic deserialization are reported as well, see
+/// [`crate::discriminator`].
+pub(crate) fn convert_checked(swagger: Swagger) -> openapi::OpenApi {
+    let mut refs = Vec::new();
+    if let Ok(document) = serde_json::to_value(&swagger) {
+        local_refs(&document, &mut String::new(), &mut refs);
+        discriminator::verify(&document);
+    }
+
+    let openapi: openapi::OpenApi = swagger.into();
+    count_components(&openapi);
+    dangling_refs(&openapi, refs);
+    openapi
+}
+
+/// Reports the local `refs` collected with [`local_refs`] whose target is missing from the
+/// converted `openapi`.
+pub(crate) fn dangling_refs(openapi: &openapi::OpenApi, refs: Vec<(String, String)>) {
+    trace::phase("refs", || {
+        let converted = serde_json::to_value(openapi).unwrap_or_default();
+        for (pointer, location) in refs {
+            // path items keep their `$ref`s, see `PathItem::reference`
+            let target = match location.starts_with("#/paths/") {
+                true => location.as_str().into(),
+                false => RefOr::<Schema>::openapi_ref_location(&location),
+            };
+            let Some(target) = target.strip_prefix('#') else {
+                continue;
+            };
+            if converted.pointer(target).is_none() {
+                diagnostics::at(&pointer, || {
+                    diagnostics::error(format!("dangling reference `{location}`"))
+                });
+            }
+        }
+    });
+}
+
+/// Reports the size of a converted document, see [`trace::count`].
+pub(crate) fn count_components(openapi: &openapi::OpenApi) {
+    trace::count("paths", openapi.paths.paths.len());
+    if let Some(components) = &openapi.components {
+        trace::count("schemas", components.schemas.len());
+        trace::count("responses", components.responses.len());
+    }
+}
+
+/// Collects the local `$ref`s within `value` together with the pointer of their object.
+pub(crate) fn local_refs(
+    value: &serde_json::Value,
+    pointer: &mut String,
+    refs: &mut Vec<(String, String)>,
+) {
+    walk::objects(value, pointer, &mut |object, pointer| {
+        if let Some(location) = object.get("$ref").and_then(|r| r.as_str()) {
+            if location.starts_with('#') {
+                refs.push((pointer.to_string(), location.to_string()));
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn deterministic_output() {
+        let extensions: serde_json::Map<_, _> = (0..32)
+            .map(|i| (format!("x-extension-{i:02}"), json!(i)))
+            .collect();
+        let mut swagger = json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "paths": {"/pets": {"get": {"responses": {"200": {"description": "ok"}}}}}
+        });
+        swagger["paths"]["/pets"]["get"]
+            .as_object_mut()
+            .unwrap()
+            .extend(extensions);
+        let swagger = swagger.to_string();
+
+        let first = crate::convert_json(&swagger).unwrap();
+        let second = crate::convert_json(&swagger).unwrap();
+
+        assert_eq!(first, second);
+        assert!(first.find("x-extension-00") < first.find("x-extension-31"));
+    }
+
+    #[test]
+    fn dangling_refs() {
+        let swagger: Swagger = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "responses": {
+                            "200": {"description": "ok", "schema": {"$ref": "#/definitions/Pets"}},
+                            "404": {"$ref": "#/responses/NotFound"}
+                        }
+                    }
+                }
+            },
+            "definitions": {
+                "Pet": {"type": "object", "properties": {"tag": {"$ref": "#/definitions/Tag"}}},
+                "Tag": {"type": "string"}
+            }
+        }))
+        .unwrap();
+
+        let diagnostics = crate::convert(swagger).diagnostics;
+
+        let reported: Vec<(&str, &str)> = diagnostics
+            .iter()
+            .map(|d| (d.pointer.as_str(), d.message.as_str()))
+            .collect();
+        assert_eq!(
+            reported,
+            vec![
+                (
+                    "/paths/~1pets/get/responses/200/schema",
+                    "dangling reference `#/definitions/Pets`"
+                ),
+                (
+                    "/paths/~1pets/get/responses/404",
+                    "dangling reference `#/responses/NotFound`"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn server_overrides() {
+        let swagger: Swagger = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "host": "legacy.example.com",
+            "basePath": "/v1",
+            "schemes": ["https"],
+            "paths": {}
+        }))
+        .unwrap();
+        let options = crate::ConvertOptions {
+            host: Some("api.example.com".to_string()),
+            base_path: Some(BasePath::Prefix("/pets".to_string())),
+            ..Default::default()
+        };
+
+        let conversion = crate::convert_with(swagger, &options);
+
+        assert_eq!(
+            conversion.to_value()["servers"],
+            json!([{"url": "https://api.example.com/pets/v1"}])
+        );
+    }
+
+    #[test]
+    fn x_servers() {
+        let swagger: Swagger = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "host": "legacy.example.com",
+            "schemes": ["https"],
+            "x-servers": [{"url": "https://api.example.com", "description": "Production"}],
+            "paths": {
+                "/pets": {
+                    "x-servers": [{"url": "https://pets.example.com"}],
+                    "get": {"x-servers": [{"url": "https://cache.example.com"}], "responses": {}},
+                    "post": {"x-servers": "https://write.example.com", "responses": {}}
+                }
+            }
+        }))
+        .unwrap();
+
+        let conversion = crate::convert(swagger);
+
+        let document = conversion.to_value();
+        assert_eq!(
+            document["servers"],
+            json!([{"url": "https://api.example.com", "description": "Production"}])
+        );
+        let path_item = &document["paths"]["/pets"];
+        assert_eq!(
+            path_item["servers"],
+            json!([{"url": "https://pets.example.com"}])
+        );
+        assert_eq!(
+            path_item["get"]["servers"],
+            json!([{"url": "https://cache.example.com"}])
+        );
+        assert!(path_item.get("x-servers").is_none());
+        assert!(path_item["post"].get("x-servers").is_none());
+        assert_eq!(
+            conversion
+                .diagnostics
+                .iter()
+                .map(|d| d.pointer.as_str())
+                .collect::<Vec<_>>(),
+            ["/paths/~1pets/post/x-servers"]
+        );
+    }
+
+    #[test]
+    fn x_hosts() {
+        let swagger: Swagger = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "host": "legacy.example.com",
+            "basePath": "/v1",
+            "schemes": ["https", "http"],
+            "x-hosts": [
+                "api.example.com",
+                {"host": "staging.example.com", "description": "Staging"}
+            ],
+            "paths": {}
+        }))
+        .unwrap();
+
+        let conversion = crate::convert(swagger);
+
+        assert!(conversion.diagnostics.is_empty());
+        assert_eq!(
+            conversion.to_value()["servers"],
+            json!([
+                {"url": "https://api.example.com/v1"},
+                {"url": "http://api.example.com/v1"},
+                {"url": "https://staging.example.com/v1", "description": "Staging"},
+                {"url": "http://staging.example.com/v1", "description": "Staging"}
+            ])
+        );
+    }
+
+    #[test]
+    fn server_variables() {
+        let swagger: Swagger = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "host": "{region}.api.example.com",
+            "basePath": "/{version}",
+            "schemes": ["https"],
+            "x-server-variables": {
+                "region": {"default": "eu", "enum": ["eu", "us"]}
+            },
+            "paths": {}
+        }))
+        .unwrap();
+
+        let conversion = crate::convert(swagger);
+
+        assert_eq!(
+            conversion.to_value()["servers"],
+            json!([{
+                "url": "https://{region}.api.example.com/{version}",
+                "variables": {
+                    "region": {"default": "eu", "enum": ["eu", "us"]},
+                    "version": {"default": "version"}
+                }
+            }])
+        );
+        assert_eq!(
+            conversion
+                .diagnostics
+                .iter()
+                .map(|d| d.message.as_str())
+                .collect::<Vec<_>>(),
+            [
+                "server variable `version` has no default, using its name; declare it in \
+              `x-server-variables`"
+            ]
+        );
+    }
+
+    #[test]
+    fn x_webhooks() {
+        let swagger = json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "paths": {},
+            "x-webhooks": {
+                "petAdopted": {
+                    "post": {
+                        "parameters": [
+                            {"name": "pet", "in": "body", "schema": {"$ref": "#/definitions/Pet"}}
+                        ],
+                        "responses": {"200": {"description": "Received"}}
+                    }
+                }
+            },
+            "definitions": {"Pet": {"type": "object"}}
+        });
+        let convert = |target| {
+            let options = crate::ConvertOptions {
+                output: crate::output::OutputOptions {
+                    target,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            let swagger = serde_json::from_value(swagger.clone()).unwrap();
+            crate::convert_with(swagger, &options)
+        };
+
+        let conversion_3_0 = convert(crate::output::Target::V3_0);
+        let conversion_3_1 = convert(crate::output::Target::V3_1);
+
+        assert!(conversion_3_0.to_value().get("webhooks").is_none());
+        assert_eq!(
+            conversion_3_0
+                .diagnostics
+                .iter()
+                .map(|d| (d.pointer.as_str(), d.message.as_str()))
+                .collect::<Vec<_>>(),
+            [(
+                "/x-webhooks",
+                "dropped `x-webhooks`, webhooks need OpenAPI 3.1 output"
+            )]
+        );
+        let document = conversion_3_1.to_value();
+        assert_eq!(
+            document["webhooks"]["petAdopted"]["post"]["requestBody"]["content"]
+                ["application/json"]["schema"],
+            json!({"$ref": "#/components/schemas/Pet"})
+        );
+        assert!(conversion_3_1.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn info_overrides() {
+        let swagger: Swagger = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {
+                "title": "Pets",
+                "version": "1.0",
+                "license": {"name": "Proprietary"}
+            },
+            "paths": {}
+        }))
+        .unwrap();
+        let options = crate::ConvertOptions {
+            license: Some(
+                openapi::info::LicenseBuilder::new()
+                    .name("Apache-2.0")
+                    .url(Some("https://www.apache.org/licenses/LICENSE-2.0"))
+                    .build(),
+            ),
+            contact: Some(
+                openapi::info::ContactBuilder::new()
+                    .email(Some("api@example.com"))
+                    .build(),
+            ),
+            terms_of_service: Some("https://example.com/terms".to_string()),
+            ..Default::default()
+        };
+
+        let conversion = crate::convert_with(swagger, &options);
+
+        assert_eq!(
+            conversion.to_value()["info"],
+            json!({
+                "title": "Pets",
+                "version": "1.0",
+                "license": {
+                    "name": "Apache-2.0",
+                    "url": "https://www.apache.org/licenses/LICENSE-2.0"
+                },
+                "contact": {"email": "api@example.com"},
+                "termsOfService": "https://example.com/terms"
+            })
+        );
+    }
+
+    #[test]
+    fn external_docs_overrides() {
+        let swagger: Swagger = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "paths": {},
+            "tags": [{"name": "pets", "description": "Pets"}],
+            "externalDocs": {"url": "https://legacy.example.com"}
+        }))
+        .unwrap();
+        let portal =
+            |path: &str| openapi::ExternalDocs::new(format!("https://dev.example.com{path}"));
+        let options = crate::ConvertOptions {
+            external_docs: Some(portal("/")),
+            tag_external_docs: BTreeMap::from([
+                ("pets".to_string(), portal("/pets")),
+                ("stores".to_string(), portal("/stores")),
+            ]),
+            ..Default::default()
+        };
+
+        let document = crate::convert_with(swagger, &options).to_value();
+
+        assert_eq!(
+            document["externalDocs"],
+            json!({"url": "https://dev.example.com/"})
+        );
+        assert_eq!(
+            document["tags"],
+            json!([
+                {
+                    "name": "pets",
+                    "description": "Pets",
+                    "externalDocs": {"url": "https://dev.example.com/pets"}
+                },
+                {"name": "stores", "externalDocs": {"url": "https://dev.example.com/stores"}}
+            ])
+        );
+    }
+
+    #[test]
+    fn rename_definitions() {
+        let swagger: Swagger = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {"title": "Accounts", "version": "1.0"},
+            "paths": {},
+            "definitions": {
+                "io.company.model.user_account": {
+                    "type": "object",
+                    "properties": {"role": {"$ref": "#/definitions/io.company.model.role"}}
+                },
+                "io.company.model.role": {"type": "string"}
+            }
+        }))
+        .unwrap();
+        let options = crate::ConvertOptions {
+            refs: RefNamespace {
+                strip_prefixes: vec!["io.company.model.".to_string()],
+                name_case: Some(Casing::Pascal),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let conversion = crate::convert_with(swagger, &options);
+
+        assert!(conversion.diagnostics.is_empty());
+        let schemas = &conversion.to_value()["components"]["schemas"];
+        assert_eq!(
+            schemas["UserAccount"]["properties"]["role"]["$ref"],
+            "#/components/schemas/Role"
+        );
+        assert!(schemas.get("Role").is_some());
+    }
+
+    #[test]
+    fn nullable_ref() {
+        let swagger: Swagger = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "paths": {},
+            "definitions": {
+                "Pet": {
+                    "type": "object",
+                    "properties": {"owner": {"$ref": "#/definitions/Owner", "x-nullable": true}}
+                },
+                "Owner": {"type": "object"}
+            }
+        }))
+        .unwrap();
+
+        let conversion = crate::convert(swagger);
+
+        assert!(conversion.diagnostics.is_empty());
+        assert_eq!(
+            conversion.to_value()["components"]["schemas"]["Pet"]["properties"]["owner"],
+            json!({"allOf": [{"$ref": "#/components/schemas/Owner"}], "nullable": true})
+        );
+    }
+
+    #[test]
+    fn ref_siblings() {
+        let swagger: Swagger = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "responses": {
+                            "200": {"$ref": "#/responses/Pets", "description": "all pets"}
+                        }
+                    }
+                }
+            },
+            "responses": {"Pets": {"description": "ok"}},
+            "definitions": {
+                "Pet": {
+                    "type": "object",
+                    "properties": {
+                        "owner": {"$ref": "#/definitions/Owner", "description": "current owner"}
+                    }
+                },
+                "Owner": {"type": "object"}
+            }
+        }))
+        .unwrap();
+        let target_3_1 = crate::ConvertOptions {
+            output: crate::output::OutputOptions {
+                target: crate::output::Target::V3_1,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let conversion = crate::convert(swagger.clone());
+        let conversion_3_1 = crate::convert_with(swagger, &target_3_1);
+
+        let pointers: Vec<_> = conversion
+            .diagnostics
+            .iter()
+            .map(|d| d.pointer.as_str())
+            .collect();
+        assert_eq!(pointers, ["/paths/~1pets/get/responses/200/description"]);
+        let owner = "/components/schemas/Pet/properties/owner";
+        assert_eq!(
+            conversion.to_value().pointer(owner).unwrap(),
+            &json!({"allOf": [{"$ref": "#/components/schemas/Owner"}], "description": "current owner"})
+        );
+        assert_eq!(
+            conversion_3_1.to_value().pointer(owner).unwrap(),
+            &json!({"$ref": "#/components/schemas/Owner", "description": "current owner"})
+        );
+    }
+
+    #[test]
+    fn ref_namespace() {
+        let swagger: Swagger = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {"title": "Billing", "version": "1.0"},
+            "paths": {},
+            "definitions": {
+                "Invoice": {"type": "object", "properties": {"lines": {"$ref": "#/definitions/Line"}}},
+                "Line": {"type": "object"}
+            }
+        }))
+        .unwrap();
+        let convert = |refs| {
+            let options = crate::ConvertOptions {
+                refs,
+                ..Default::default()
+            };
+            crate::convert_with(swagger.clone(), &options)
+        };
+
+        let merged = convert(RefNamespace {
+            name_prefix: "Billing_".to_string(),
+            ..Default::default()
+        });
+        let shared = convert(RefNamespace {
+            document: "components.json".to_string(),
+            ..Default::default()
+        });
+
+        assert!(merged.diagnostics.is_empty());
+        let schemas = &merged.to_value()["components"]["schemas"];
+        assert_eq!(
+            schemas["Billing_Invoice"]["properties"]["lines"]["$ref"],
+            "#/components/schemas/Billing_Line"
+        );
+        assert!(schemas.get("Billing_Line").is_some());
+        assert!(shared.diagnostics.is_empty());
+        assert_eq!(
+            shared.to_value()["components"]["schemas"]["Invoice"]["properties"]["lines"]["$ref"],
+            "components.json#/components/schemas/Line"
+        );
+    }
+
+    #[test]
+    fn rewrite_ref_locations() {
+        for _ in 0..2 {
+            assert_eq!(
+                &*RefOr::<Schema>::openapi_ref_location("#/definitions/User"),
+                "#/components/schemas/User"
+            );
+            assert_eq!(
+                &*RefOr::<Response>::openapi_ref_location("#/responses/NotFound"),
+                "#/components/responses/NotFound"
+            );
+        }
+        REF_LOCATIONS.with(|cache| assert_eq!(cache.borrow().len(), 2));
+        assert!(Rc::ptr_eq(
+            &RefOr::<Schema>::openapi_ref_location("#/definitions/User"),
+            &RefOr::<Schema>::openapi_ref_location("#/definitions/User")
+        ));
+
+        clear_caches();
+        REF_LOCATIONS.with(|cache| assert!(cache.borrow().is_empty()));
+    }
+}
@@ -0,0 +1,207 @@
+use std::str::FromStr;
+
+use crate::{codegen::words, options};
+
+use super::{method_name, Operation, PathItem};
+
+#[derive(Debug, thiserror::Error)]
+#[error("invalid operationId template: {0}")]
+pub struct InvalidTemplate(String);
+
+/// Template the `operationId` of every operation is rewritten with, e.g.
+/// `{tag:snake}_{method}_{path:snake}`.
+///
+/// Placeholders are `{tag}` for the first tag, `{method}`, `{path}` for the words of the path
+/// joined by `_` and `{operationId}` for the original `operationId`. Each placeholder takes an
+/// optional casing of `camel`, `pascal`, `snake`, `kebab`, `lower` or `upper`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperationIdTemplate {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Text(String),
+    Field(Field, Option<Casing>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Tag,
+    Method,
+    Path,
+    OperationId,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("unknown casing `{0}`")]
+pub struct UnknownCasing(String);
+
+/// Casing of a name rewritten from its words, see [`OperationIdTemplate`] and
+/// [`RefNamespace::name_case`](super::RefNamespace::name_case).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Casing {
+    Camel,
+    Pascal,
+    Snake,
+    Kebab,
+    Lower,
+    Upper,
+}
+
+impl FromStr for OperationIdTemplate {
+    type Err = InvalidTemplate;
+
+    fn from_str(template: &str) -> Result<Self, Self::Err> {
+        let mut segments = Vec::new();
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            if start > 0 {
+                segments.push(Segment::Text(rest[..start].to_string()));
+            }
+            let end = rest[start..]
+                .find('}')
+                .ok_or_else(|| InvalidTemplate(format!("unclosed `{{` in `{template}`")))?;
+            let placeholder = &rest[start + 1..start + end];
+            let (field, casing) = match placeholder.split_once(':') {
+                Some((field, casing)) => (field, Some(casing)),
+                None => (placeholder, None),
+            };
+            let field = match field {
+                "tag" => Field::Tag,
+                "method" => Field::Method,
+                "path" => Field::Path,
+                "operationId" => Field::OperationId,
+                _ => return Err(InvalidTemplate(format!("unknown placeholder `{field}`"))),
+            };
+            let casing = casing
+                .map(|casing| casing.parse::<Casing>())
+                .transpose()
+                .map_err(|e| InvalidTemplate(e.to_string()))?;
+            segments.push(Segment::Field(field, casing));
+            rest = &rest[start + end + 1..];
+        }
+        if !rest.is_empty() {
+            segments.push(Segment::Text(rest.to_string()));
+        }
+        Ok(Self { segments })
+    }
+}
+
+impl FromStr for Casing {
+    type Err = UnknownCasing;
+
+    fn from_str(casing: &str) -> Result<Self, Self::Err> {
+        match casing {
+            "camel" => Ok(Casing::Camel),
+            "pascal" => Ok(Casing::Pascal),
+            "snake" => Ok(Casing::Snake),
+            "kebab" => Ok(Casing::Kebab),
+            "lower" => Ok(Casing::Lower),
+            "upper" => Ok(Casing::Upper),
+            _ => Err(UnknownCasing(casing.to_string())),
+        }
+    }
+}
+
+impl OperationIdTemplate {
+    /// `operationId` of the operation at `path` and `method`.
+    pub fn render(&self, path: &str, method: &str, operation: &Operation) -> String {
+        self.segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Text(text) => text.clone(),
+                Segment::Field(field, casing) => {
+                    let value = match field {
+                        Field::Tag => operation
+                            .tags
+                            .iter()
+                            .flatten()
+                            .next()
+                            .map_or("default".to_string(), Clone::clone),
+                        Field::Method => method.to_string(),
+                        Field::Path => words(path).join("_"),
+                        Field::OperationId => match &operation.operation_id {
+                            Some(operation_id) => operation_id.clone(),
+                            None => format!("{method}_{}", words(path).join("_")),
+                        },
+                    };
+                    match casing {
+                        Some(casing) => casing.apply(&value),
+                        None => value,
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+impl Casing {
+    pub(crate) fn apply(self, value: &str) -> String {
+        let words = words(value);
+        let capitalize = |word: &String| {
+            let mut chars = word.chars();
+            let first = chars.next().map(|c| c.to_uppercase().to_string());
+            first.unwrap_or_default() + &chars.as_str().to_lowercase()
+        };
+        match self {
+            Casing::Camel => words
+                .iter()
+                .enumerate()
+                .map(|(i, word)| match i {
+                    0 => word.to_lowercase(),
+                    _ => capitalize(word),
+                })
+                .collect(),
+            Casing::Pascal => words.iter().map(capitalize).collect(),
+            Casing::Snake => words.join("_").to_lowercase(),
+            Casing::Kebab => words.join("-").to_lowercase(),
+            Casing::Lower => value.to_lowercase(),
+            Casing::Upper => value.to_uppercase(),
+        }
+    }
+}
+
+/// Rewrites the `operationId`s of a path item with the template of the current conversion.
+pub(crate) fn rewrite_operation_ids(path: &str, path_item: &mut PathItem) {
+    let options = options::current();
+    let Some(template) = &options.operation_ids else {
+        return;
+    };
+    for (method, operation) in &mut path_item.operations {
+        let operation_id = template.render(path, method_name(method), operation);
+        operation.operation_id = Some(operation_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn render_template() {
+        let operation: Operation = serde_json::from_value(json!({
+            "tags": ["PetStore"],
+            "operationId": "findPetsByStatus_1",
+            "responses": {}
+        }))
+        .unwrap();
+        let render = |template: &str| {
+            template.parse::<OperationIdTemplate>().unwrap().render(
+                "/pets/{petId}/owners",
+                "get",
+                &operation,
+            )
+        };
+
+        assert_eq!(
+            render("{tag:snake}_{method}_{path:snake}"),
+            "pet_store_get_pets_pet_id_owners"
+        );
+        assert_eq!(render("{method}{path:pascal}"), "getPetsPetIdOwners");
+        assert_eq!(render("{operationId:camel}"), "findPetsByStatus1");
+        assert!("{verb}".parse::<OperationIdTemplate>().is_err());
+    }
+}
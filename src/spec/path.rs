@@ -1,10 +1,19 @@
-use std::collections::{BTreeMap, HashMap};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    marker::PhantomData,
+};
 
-use serde::{Deserialize, Serialize};
+use serde::{de, Deserialize, Deserializer, Serialize};
 use serde_with::skip_serializing_none;
 use utoipa::openapi::{self};
 
-use super::{Extensions, RefOr, Responses, Schema};
+use crate::{diagnostics, parallel, sanitize};
+
+use super::{
+    enum_values, exclusive_bound, redoc, rewrite_operation_ids, x_servers, ExclusiveBound,
+    Extensions, RefOr, Responses, Schema,
+};
 
 #[derive(Debug, thiserror::Error)]
 #[error("invalid path parameter type")]
@@ -14,69 +23,431 @@ pub struct InvalidPathParameter;
 #[skip_serializing_none]
 #[derive(Serialize, Deserialize, Default, Clone, PartialEq)]
 #[cfg_attr(feature = "debug", derive(Debug))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Paths {
     #[serde(flatten)]
     pub paths: BTreeMap<String, PathItem>,
     #[serde(
         flatten,
-        skip_serializing_if = "HashMap::is_empty",
-        default = "HashMap::new"
+        skip_serializing_if = "BTreeMap::is_empty",
+        default = "BTreeMap::new"
     )]
     pub extensions: Extensions,
 }
 
 impl From<Paths> for openapi::Paths {
     fn from(value: Paths) -> Self {
+        ambiguous_paths(value.paths.keys().map(String::as_str));
         let mut openapi_paths = openapi::PathsBuilder::new()
             .extensions(value.extensions.into_openapi_extensions())
             .build();
-        openapi_paths.paths = value
-            .paths
-            .into_iter()
-            .map(|(k, v)| (k, v.into()))
-            .collect();
+        openapi_paths.paths = parallel::convert_map(value.paths, |k, mut v| {
+            rewrite_operation_ids(k, &mut v);
+            diagnostics::scope(k, || v.into())
+        });
         openapi_paths
     }
 }
 
+/// Reports paths a request could match more than one of, which gateways importing the
+/// document reject or route arbitrarily: paths differing only in the names of their
+/// templates, like `/users/{id}` and `/users/{userId}`, and paths whose literal segments
+/// are matched by a template of the other, like `/users/me` and `/users/{id}`.
+pub(crate) fn ambiguous_paths<'a>(paths: impl Iterator<Item = &'a str>) {
+    let templated: Vec<(&str, Vec<String>)> = paths
+        .map(|path| (path, path.split('/').map(template_shape).collect()))
+        .collect();
+    for (i, (path, segments)) in templated.iter().enumerate() {
+        for (other, other_segments) in &templated[..i] {
+            if segments.len() != other_segments.len() {
+                continue;
+            }
+            let mut literal = false;
+            let mut other_literal = false;
+            let overlaps = segments.iter().zip(other_segments).all(|(a, b)| {
+                literal |= a != b && b == "{}";
+                other_literal |= a != b && a == "{}";
+                a == b || a == "{}" || b == "{}"
+            });
+            if !overlaps {
+                continue;
+            }
+            let (at, message) = match (literal, other_literal) {
+                (false, false) => (
+                    path,
+                    format!("path `{path}` matches the same requests as `{other}`"),
+                ),
+                (true, false) => (
+                    path,
+                    format!("path `{path}` is shadowed by the template of `{other}`"),
+                ),
+                (false, true) => (
+                    other,
+                    format!("path `{other}` is shadowed by the template of `{path}`"),
+                ),
+                (true, true) => (path, format!("path `{path}` overlaps `{other}`")),
+            };
+            diagnostics::scope(at, || diagnostics::warn(message));
+        }
+    }
+}
+
+/// The segment of a path with its template names left out, e.g. `{}.json` for `{id}.json`.
+fn template_shape(segment: &str) -> String {
+    let mut shape = String::with_capacity(segment.len());
+    let mut in_template = false;
+    for c in segment.chars() {
+        match c {
+            '{' => {
+                in_template = true;
+                shape.push(c);
+            }
+            '}' => {
+                in_template = false;
+                shape.push(c);
+            }
+            _ if !in_template => shape.push(c),
+            _ => {}
+        }
+    }
+    shape
+}
+
 #[skip_serializing_none]
 #[derive(Serialize, Deserialize, Default, Clone, PartialEq)]
 #[cfg_attr(feature = "debug", derive(Debug))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct PathItem {
-    #[serde(flatten)]
+    /// A path item to use in place of or together with this one, kept as written.
+    #[serde(rename = "$ref")]
+    pub reference: Option<String>,
+    #[serde(flatten, deserialize_with = "operations")]
+    #[cfg_attr(feature = "schemars", schemars(with = "BTreeMap<String, Operation>"))]
     pub operations: BTreeMap<openapi::PathItemType, Operation>,
     pub parameters: Option<Vec<Parameter>>,
+    /// Members which are neither operations, `parameters` nor extensions. They are reported
+    /// and dropped when converting.
+    #[serde(flatten, deserialize_with = "unknown_members")]
+    pub unknown: BTreeMap<String, serde_json::Value>,
+    #[serde(
+        flatten,
+        skip_serializing_if = "BTreeMap::is_empty",
+        default = "BTreeMap::new"
+    )]
+    pub extensions: Extensions,
+}
+
+/// Methods a Swagger path item can hold operations for.
+const METHODS: [openapi::PathItemType; 7] = [
+    openapi::PathItemType::Get,
+    openapi::PathItemType::Put,
+    openapi::PathItemType::Post,
+    openapi::PathItemType::Delete,
+    openapi::PathItemType::Options,
+    openapi::PathItemType::Head,
+    openapi::PathItemType::Patch,
+];
+
+fn method(key: &str) -> Option<openapi::PathItemType> {
+    METHODS
+        .iter()
+        .find(|method| method_name(method) == key)
+        .cloned()
+}
+
+fn operations<'de, D>(
+    deserializer: D,
+) -> Result<BTreeMap<openapi::PathItemType, Operation>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    select_members(deserializer, method)
+}
+
+fn unknown_members<'de, D>(deserializer: D) -> Result<BTreeMap<String, serde_json::Value>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    select_members(deserializer, |key| {
+        (method(key).is_none() && key != "$ref" && !key.starts_with("x-")).then(|| key.to_string())
+    })
+}
+
+/// Deserializes the members of a map which `select` returns a key for, skipping the others
+/// without deserializing their values.
+fn select_members<'de, D, K, V>(
+    deserializer: D,
+    select: fn(&str) -> Option<K>,
+) -> Result<BTreeMap<K, V>, D::Error>
+where
+    D: Deserializer<'de>,
+    K: Ord,
+    V: Deserialize<'de>,
+{
+    struct Visitor<K, V> {
+        select: fn(&str) -> Option<K>,
+        value: PhantomData<V>,
+    }
+
+    impl<'de, K: Ord, V: Deserialize<'de>> de::Visitor<'de> for Visitor<K, V> {
+        type Value = BTreeMap<K, V>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a map")
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+        where
+            A: de::MapAccess<'de>,
+        {
+            let mut members = BTreeMap::new();
+            while let Some(key) = map.next_key::<String>()? {
+                match (self.select)(&key) {
+                    Some(key) => {
+                        members.insert(key, map.next_value()?);
+                    }
+                    None => {
+                        map.next_value::<de::IgnoredAny>()?;
+                    }
+                }
+            }
+            Ok(members)
+        }
+    }
+
+    deserializer.deserialize_map(Visitor {
+        select,
+        value: PhantomData,
+    })
 }
 
 impl From<PathItem> for openapi::PathItem {
-    fn from(value: PathItem) -> Self {
-        let openapi_params: Option<Vec<openapi::path::Parameter>> = value
-            .parameters
-            .map(|p| p.into_iter().filter_map(|p| p.try_into().ok()).collect());
+    fn from(mut value: PathItem) -> Self {
+        report_overridden_parameters(&value);
+        inherit_request_body_parameters(&mut value);
+        redoc::code_samples(&mut value);
+        for key in value.unknown.keys() {
+            diagnostics::scope(key, || {
+                diagnostics::warn(format!("dropped unknown path item member `{key}`"))
+            });
+        }
+        let openapi_params: Option<Vec<openapi::path::Parameter>> = value.parameters.map(|p| {
+            diagnostics::scope("parameters", || {
+                dedup_parameters(p)
+                    .into_iter()
+                    .filter_map(|(i, p)| {
+                        let name = p.name.clone();
+                        diagnostics::scope(i, || match p.try_into() {
+                            Ok(p) => Some(p),
+                            Err(err) => {
+                                diagnostics::warn(format!(
+                                    "dropped path level parameter `{name}`: {err}"
+                                ));
+                                None
+                            }
+                        })
+                    })
+                    .collect()
+            })
+        });
+        let servers = x_servers(value.extensions.0.remove("x-servers"));
+        // utoipa does not model the `$ref` of path items, it is written among the extensions
+        let mut extensions = value.extensions.into_openapi_extensions();
+        if let Some(reference) = value.reference {
+            extensions
+                .get_or_insert_with(Default::default)
+                .insert("$ref".to_string(), reference.into());
+        }
         let mut openapi_path_item = openapi::path::PathItemBuilder::new()
             .parameters(openapi_params)
+            .extensions(extensions)
             .build();
+        openapi_path_item.servers = servers;
 
         openapi_path_item.operations = value
             .operations
             .into_iter()
-            .map(|(k, v)| (k, v.into()))
+            .map(|(k, v)| {
+                let v: openapi::path::Operation = diagnostics::scope(method_name(&k), || v.into());
+                (k, v)
+            })
             .collect();
 
         openapi_path_item
     }
 }
 
+/// Converts the `x-callbacks` extension of an operation, which holds path items by runtime
+/// expression by callback name like the callbacks of OpenAPI 3.x. The extension is reported
+/// and dropped unless it has that form.
+fn x_callbacks(value: Option<serde_json::Value>) -> Option<serde_json::Value> {
+    let value = value?;
+    diagnostics::scope("x-callbacks", || {
+        let callbacks: BTreeMap<String, BTreeMap<String, PathItem>> =
+            match serde_json::from_value(value) {
+                Ok(callbacks) => callbacks,
+                Err(err) => {
+                    diagnostics::warn(format!("dropped invalid `x-callbacks`: {err}"));
+                    return None;
+                }
+            };
+        let converted: BTreeMap<String, BTreeMap<String, openapi::PathItem>> = callbacks
+            .into_iter()
+            .map(|(name, expressions)| {
+                let expressions = diagnostics::scope(&name, || {
+                    expressions
+                        .into_iter()
+                        .map(|(expression, path_item)| {
+                            let path_item = diagnostics::scope(&expression, || path_item.into());
+                            (expression, path_item)
+                        })
+                        .collect()
+                });
+                (name, expressions)
+            })
+            .collect();
+        serde_json::to_value(converted).ok()
+    })
+}
+
+/// Moves the path level `body` and `formData` parameters into the operations of the path
+/// item, where they become the request body, unless an operation overrides them. An operation
+/// level `body` parameter overrides the path level one whatever its name.
+///
+/// OpenAPI 3.0 path items cannot hold a request body, so the parameters are left in place
+/// and reported when there is no operation to move them to.
+fn inherit_request_body_parameters(path_item: &mut PathItem) {
+    if path_item.operations.is_empty() {
+        return;
+    }
+    let Some(parameters) = path_item.parameters.take() else {
+        return;
+    };
+    let (shared, rest): (Vec<_>, Vec<_>) = parameters.into_iter().partition(|parameter| {
+        matches!(
+            parameter.parameter_in,
+            ParameterIn::Body(_) | ParameterIn::FormData(_)
+        )
+    });
+    path_item.parameters = Some(rest);
+
+    for operation in path_item.operations.values_mut() {
+        let own = operation.parameters.as_deref().unwrap_or_default();
+        let inherited: Vec<Parameter> = shared
+            .iter()
+            .filter(|parameter| !own.iter().any(|own| overrides(own, parameter)))
+            .cloned()
+            .collect();
+        if !inherited.is_empty() {
+            operation
+                .parameters
+                .get_or_insert_with(Vec::new)
+                .extend(inherited);
+        }
+    }
+}
+
+/// Request body of the `formData` parameters of an operation, collected into the properties
+/// of the `form` object. It is required if any of them is.
+fn form_request_body(form: openapi::Object) -> openapi::request_body::RequestBody {
+    let required = !form.required.is_empty();
+    let content = openapi::content::Content::new(openapi::RefOr::T(openapi::Schema::Object(form)));
+    openapi::request_body::RequestBodyBuilder::new()
+        .required(Some(is_required(required)))
+        .content("application/x-www-form-urlencoded", content)
+        .build()
+}
+
+/// Whether the operation level parameter `own` overrides the path level parameter `shared`,
+/// by repeating its `name` and `in`. Any `body` parameter overrides another one.
+fn overrides(own: &Parameter, shared: &Parameter) -> bool {
+    match (&own.parameter_in, &shared.parameter_in) {
+        (ParameterIn::Body(_), ParameterIn::Body(_)) => true,
+        (own_in, shared_in) => own.name == shared.name && own_in.location() == shared_in.location(),
+    }
+}
+
+/// Reports the operation level parameters overriding a path level one. OpenAPI 3.0 resolves
+/// them the same way, but the path level parameter is easily missed when editing either.
+fn report_overridden_parameters(path_item: &PathItem) {
+    let Some(shared) = &path_item.parameters else {
+        return;
+    };
+    for (method, operation) in &path_item.operations {
+        let own = operation.parameters.iter().flatten().enumerate();
+        for (i, own) in own {
+            let Some(shared) = shared.iter().find(|shared| overrides(own, shared)) else {
+                continue;
+            };
+            diagnostics::scope(method_name(method), || {
+                diagnostics::scope("parameters", || {
+                    diagnostics::scope(i, || {
+                        diagnostics::warn(format!(
+                            "{} parameter `{}` overrides the path level parameter `{}`",
+                            own.parameter_in.location(),
+                            own.name,
+                            shared.name
+                        ))
+                    })
+                })
+            });
+        }
+    }
+}
+
+/// Drops parameters repeating the `name` and `in` of an earlier one, which Swagger forbids
+/// within a single list, keeping the first. Returns the others with their index.
+///
+/// Operation level parameters override path level ones with the same `name` and `in`, as in
+/// OpenAPI 3.0, see [`report_overridden_parameters`].
+fn dedup_parameters(parameters: Vec<Parameter>) -> Vec<(usize, Parameter)> {
+    let mut seen = BTreeSet::new();
+    parameters
+        .into_iter()
+        .enumerate()
+        .filter(|(i, parameter)| {
+            let location = parameter.parameter_in.location();
+            let unique = seen.insert((parameter.name.clone(), location));
+            if !unique {
+                diagnostics::scope(i, || {
+                    diagnostics::warn(format!(
+                        "dropped duplicate {location} parameter `{}`",
+                        parameter.name
+                    ))
+                });
+            }
+            unique
+        })
+        .collect()
+}
+
+/// Name of the operation's key within a path item.
+pub(crate) fn method_name(method: &openapi::PathItemType) -> &'static str {
+    match method {
+        openapi::PathItemType::Get => "get",
+        openapi::PathItemType::Post => "post",
+        openapi::PathItemType::Put => "put",
+        openapi::PathItemType::Delete => "delete",
+        openapi::PathItemType::Options => "options",
+        openapi::PathItemType::Head => "head",
+        openapi::PathItemType::Patch => "patch",
+        openapi::PathItemType::Trace => "trace",
+        openapi::PathItemType::Connect => "connect",
+    }
+}
+
 /// https://swagger.io/specification/v2/#operation-object
 #[skip_serializing_none]
 #[derive(Serialize, Deserialize, Clone, PartialEq)]
 #[cfg_attr(feature = "debug", derive(Debug))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct Operation {
     pub tags: Option<Vec<String>>,
     pub summary: Option<String>,
     pub description: Option<String>,
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<serde_json::Value>"))]
     pub external_docs: Option<openapi::ExternalDocs>,
     pub operation_id: Option<String>,
     pub consumes: Option<Vec<String>>,
@@ -84,62 +455,101 @@ pub struct Operation {
     pub parameters: Option<Vec<Parameter>>,
     pub responses: Responses,
     pub schemes: Option<Vec<String>>,
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<serde_json::Value>"))]
     pub deprecated: Option<openapi::Deprecated>,
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<serde_json::Value>"))]
     pub security: Option<Vec<openapi::SecurityRequirement>>,
     #[serde(
         flatten,
-        skip_serializing_if = "HashMap::is_empty",
-        default = "HashMap::new"
+        skip_serializing_if = "BTreeMap::is_empty",
+        default = "BTreeMap::new"
     )]
     pub extensions: Extensions,
 }
 
 impl From<Operation> for openapi::path::Operation {
-    fn from(value: Operation) -> Self {
+    fn from(mut value: Operation) -> Self {
+        let servers = x_servers(value.extensions.0.remove("x-servers"));
+        let callbacks = x_callbacks(value.extensions.0.remove("x-callbacks"));
+        // utoipa has no callbacks, they are emitted next to the extensions
+        let mut extensions = value.extensions.into_openapi_extensions();
+        if let Some(callbacks) = callbacks {
+            extensions
+                .get_or_insert_with(Default::default)
+                .insert("callbacks".to_string(), callbacks);
+        }
         let mut openapi_operation = openapi::path::OperationBuilder::new()
             .tags(value.tags)
             .summary(value.summary)
-            .description(value.description)
+            .description(sanitize::description(value.description))
             .operation_id(value.operation_id)
             .deprecated(value.deprecated)
-            .responses(value.responses)
-            .extensions(value.extensions.into_openapi_extensions())
+            .responses(diagnostics::scope("responses", || {
+                openapi::Responses::from(value.responses)
+            }))
+            .extensions(extensions)
             .build();
 
         openapi_operation.security = value.security;
+        openapi_operation.servers = servers;
 
         if let Some(params) = value.parameters {
             let mut openapi_params: Vec<openapi::path::Parameter> = Vec::with_capacity(10);
-            for param in params {
-                match param.parameter_in {
-                    ParameterIn::FormData(form_body) => {
-                        let openapi_content = openapi::content::Content::new(openapi::RefOr::T(
-                            openapi::Schema::from(form_body),
-                        ));
-                        let openapi_req_body = openapi::request_body::RequestBodyBuilder::new()
-                            .description(param.description)
-                            .required(Some(is_required(param.required)))
-                            .content("application/x-www-form-urlencoded", openapi_content)
-                            .build();
-
-                        openapi_operation.request_body = Some(openapi_req_body);
-                    }
-                    ParameterIn::Body(body) => {
-                        let openapi_content =
-                            openapi::content::Content::new(body.schema.into_openapi_ref());
-                        let openapi_req_body = openapi::request_body::RequestBodyBuilder::new()
-                            .description(param.description)
-                            .required(Some(is_required(param.required)))
-                            .content("application/json", openapi_content)
-                            .build();
-
-                        openapi_operation.request_body = Some(openapi_req_body);
-                    }
-                    _ => {
-                        if let Ok(param) = param.try_into() {
-                            openapi_params.push(param);
+            let mut form = openapi::ObjectBuilder::new()
+                .schema_type(openapi::SchemaType::Object)
+                .build();
+            let params = diagnostics::scope("parameters", || dedup_parameters(params));
+            for (i, param) in params {
+                diagnostics::scope("parameters", || {
+                    diagnostics::scope(i, || match param.parameter_in {
+                        ParameterIn::FormData(form_body) => {
+                            if let Some(collection_format) = &form_body.collection_format {
+                                diagnostics::warn(format!(
+                                    "dropped unsupported collectionFormat `{collection_format}`"
+                                ));
+                            }
+                            let mut schema = openapi::Schema::from(form_body);
+                            let description = sanitize::description(param.description);
+                            match &mut schema {
+                                openapi::Schema::Object(object) => object.description = description,
+                                openapi::Schema::Array(array) => array.description = description,
+                                _ => {}
+                            }
+                            if param.required {
+                                form.required.push(param.name.clone());
+                            }
+                            form.properties
+                                .insert(param.name, openapi::RefOr::T(schema));
                         }
-                    }
+                        ParameterIn::Body(body) => {
+                            let openapi_content =
+                                openapi::content::Content::new(body.schema.into_openapi_ref());
+                            let openapi_req_body = openapi::request_body::RequestBodyBuilder::new()
+                                .description(sanitize::description(param.description))
+                                .required(Some(is_required(param.required)))
+                                .content("application/json", openapi_content)
+                                .build();
+
+                            openapi_operation.request_body = Some(openapi_req_body);
+                        }
+                        _ => {
+                            if let Ok(param) = param.try_into() {
+                                openapi_params.push(param);
+                            }
+                        }
+                    })
+                });
+            }
+            if !form.properties.is_empty() {
+                if openapi_operation.request_body.is_some() {
+                    diagnostics::scope("parameters", || {
+                        diagnostics::warn(
+                            "dropped the `formData` parameters of an operation with a `body` \
+                             parameter",
+                        )
+                    });
+                } else {
+                    openapi_operation.request_body = Some(form_request_body(form));
                 }
             }
 
@@ -153,6 +563,7 @@ impl From<Operation> for openapi::path::Operation {
 #[skip_serializing_none]
 #[derive(Serialize, Deserialize, Clone, PartialEq)]
 #[cfg_attr(feature = "debug", derive(Debug))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct Parameter {
     pub name: String,
@@ -163,8 +574,8 @@ pub struct Parameter {
     pub parameter_in: ParameterIn,
     #[serde(
         flatten,
-        skip_serializing_if = "HashMap::is_empty",
-        default = "HashMap::new"
+        skip_serializing_if = "BTreeMap::is_empty",
+        default = "BTreeMap::new"
     )]
     pub extensions: Extensions,
 }
@@ -173,33 +584,77 @@ impl TryFrom<Parameter> for openapi::path::Parameter {
     type Error = InvalidPathParameter;
 
     fn try_from(value: Parameter) -> Result<Self, Self::Error> {
-        let (openapi_param_in, openapi_schema) = match value.parameter_in {
-            ParameterIn::Query(query) => (
-                openapi::path::ParameterIn::Query,
-                openapi::Schema::from(query),
-            ),
-            ParameterIn::Header(header) => (
-                openapi::path::ParameterIn::Header,
-                openapi::Schema::from(header),
-            ),
-            ParameterIn::Path(path) => (
-                openapi::path::ParameterIn::Path,
-                openapi::Schema::from(path),
-            ),
+        let location = value.parameter_in.location();
+        let (openapi_param_in, generic) = match value.parameter_in {
+            ParameterIn::Query(query) => (openapi::path::ParameterIn::Query, query),
+            ParameterIn::Header(header) => (openapi::path::ParameterIn::Header, header),
+            ParameterIn::Path(path) => (openapi::path::ParameterIn::Path, path),
             ParameterIn::FormData(_) | ParameterIn::Body(_) => return Err(InvalidPathParameter),
         };
+        let collection_format = match generic.collection_format.as_deref() {
+            // an array without `collectionFormat` is `csv` in Swagger 2.0, which only some
+            // locations default to in OpenAPI 3.0
+            None if matches!(generic.schema_type, openapi::SchemaType::Array) => Some("csv"),
+            collection_format => collection_format,
+        };
+        let (style, explode) = collection_format
+            .and_then(|collection_format| {
+                let style = collection_style(location, collection_format);
+                if style.is_none() {
+                    diagnostics::warn(format!(
+                        "dropped collectionFormat `{collection_format}` of {location} parameter"
+                    ));
+                }
+                style
+            })
+            .unzip();
+        let openapi_schema = openapi::Schema::from(generic);
+        // OpenAPI 3.0 `allowReserved`, which Swagger 2.0 can only carry as an extension
+        let mut extensions = value.extensions;
+        let allow_reserved = match extensions.get("x-allowReserved") {
+            Some(serde_json::Value::Bool(allow_reserved)) if location == "query" => {
+                let allow_reserved = *allow_reserved;
+                extensions.0.remove("x-allowReserved");
+                Some(allow_reserved)
+            }
+            _ => None,
+        };
 
         Ok(openapi::path::ParameterBuilder::new()
             .name(value.name)
-            .description(value.description)
+            .description(sanitize::description(value.description))
             .schema(Some(openapi_schema))
             .parameter_in(openapi_param_in)
             .required(is_required(value.required))
-            .extensions(value.extensions.into_openapi_extensions())
+            .style(style)
+            .explode(explode)
+            .allow_reserved(allow_reserved)
+            .extensions(extensions.into_openapi_extensions())
             .build())
     }
 }
 
+/// `style` and `explode` serializing a parameter in `location` like the Swagger
+/// `collectionFormat`.
+///
+/// Headers and path parameters only take comma separated values, as `style: simple`, while
+/// query parameters take anything but tab separated values.
+fn collection_style(
+    location: &str,
+    collection_format: &str,
+) -> Option<(openapi::path::ParameterStyle, bool)> {
+    use openapi::path::ParameterStyle;
+
+    match (location, collection_format) {
+        ("query", "csv") => Some((ParameterStyle::Form, false)),
+        ("query", "ssv") => Some((ParameterStyle::SpaceDelimited, false)),
+        ("query", "pipes") => Some((ParameterStyle::PipeDelimited, false)),
+        ("query", "multi") => Some((ParameterStyle::Form, true)),
+        ("header" | "path", "csv") => Some((ParameterStyle::Simple, false)),
+        _ => None,
+    }
+}
+
 fn is_required(required: bool) -> openapi::Required {
     if required {
         openapi::Required::True
@@ -210,6 +665,7 @@ fn is_required(required: bool) -> openapi::Required {
 
 #[derive(Serialize, Deserialize, Clone, PartialEq)]
 #[cfg_attr(feature = "debug", derive(Debug))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(tag = "in", rename_all = "camelCase")]
 pub enum ParameterIn {
     Query(ParameterGeneric),
@@ -219,14 +675,30 @@ pub enum ParameterIn {
     Body(ParameterBody),
 }
 
+impl ParameterIn {
+    /// Value of `in` for the parameter.
+    pub fn location(&self) -> &'static str {
+        match self {
+            ParameterIn::Query(_) => "query",
+            ParameterIn::Header(_) => "header",
+            ParameterIn::Path(_) => "path",
+            ParameterIn::FormData(_) => "formData",
+            ParameterIn::Body(_) => "body",
+        }
+    }
+}
+
 /// https://swagger.io/specification/v2/#parameter-object
 #[skip_serializing_none]
 #[derive(Serialize, Deserialize, Clone, PartialEq)]
 #[cfg_attr(feature = "debug", derive(Debug))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct ParameterGeneric {
     #[serde(rename = "type")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
     pub schema_type: openapi::SchemaType,
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
     pub format: Option<openapi::SchemaFormat>,
     pub items: Option<Box<ParameterGeneric>>,
     pub allow_empty_value: Option<bool>,
@@ -234,9 +706,9 @@ pub struct ParameterGeneric {
     pub default: Option<serde_json::Value>,
 
     pub maximum: Option<f64>,
-    pub exclusive_maximum: Option<bool>,
+    pub exclusive_maximum: Option<ExclusiveBound>,
     pub minimum: Option<f64>,
-    pub exclusive_minimum: Option<bool>,
+    pub exclusive_minimum: Option<ExclusiveBound>,
     pub max_length: Option<usize>,
     pub min_length: Option<usize>,
     pub pattern: Option<String>,
@@ -248,8 +720,8 @@ pub struct ParameterGeneric {
     pub multiple_of: Option<f64>,
     #[serde(
         flatten,
-        skip_serializing_if = "HashMap::is_empty",
-        default = "HashMap::new"
+        skip_serializing_if = "BTreeMap::is_empty",
+        default = "BTreeMap::new"
     )]
     pub extensions: Extensions,
 }
@@ -258,11 +730,27 @@ impl From<ParameterGeneric> for openapi::Schema {
     fn from(value: ParameterGeneric) -> Self {
         match value.schema_type {
             openapi::SchemaType::Array => {
+                let items = match value.items {
+                    Some(items) => diagnostics::scope("items", || {
+                        if let Some(collection_format) = &items.collection_format {
+                            diagnostics::warn(format!(
+                                "dropped collectionFormat `{collection_format}` of nested array"
+                            ));
+                        }
+                        openapi::Schema::from(*items)
+                    }),
+                    None => {
+                        diagnostics::warn("array parameter without `items`, items are free-form");
+                        openapi::Schema::Object(
+                            openapi::ObjectBuilder::new()
+                                .schema_type(openapi::SchemaType::Value)
+                                .build(),
+                        )
+                    }
+                };
                 let openapi_array = openapi::ArrayBuilder::new()
                     //.title(value.title)
-                    .items(openapi::RefOr::T(openapi::Schema::from(
-                        *value.items.unwrap(),
-                    )))
+                    .items(openapi::RefOr::T(items))
                     //.description(value.description)
                     .default(value.default)
                     //.example(value.example)
@@ -277,22 +765,34 @@ impl From<ParameterGeneric> for openapi::Schema {
                 Self::Array(openapi_array)
             }
             _ => {
+                let enum_values = enum_values(
+                    &value.schema_type,
+                    value.enum_values,
+                    value.extensions.nullable(),
+                    value.min_length,
+                    value.max_length,
+                    value.pattern.as_deref(),
+                );
+                let (minimum, exclusive_minimum) =
+                    exclusive_bound(value.minimum, value.exclusive_minimum, "Minimum");
+                let (maximum, exclusive_maximum) =
+                    exclusive_bound(value.maximum, value.exclusive_maximum, "Maximum");
                 let openapi_object = openapi::ObjectBuilder::new()
                     .schema_type(value.schema_type)
                     //.title(value.title)
                     .format(value.format)
                     //.description(value.description)
                     .default(value.default)
-                    .enum_values(value.enum_values)
+                    .enum_values(enum_values)
                     //.example(value.example)
                     //.read_only(value.read_only)
                     //.xml(value.xml)
                     .nullable(value.extensions.nullable())
                     .multiple_of(value.multiple_of)
-                    .maximum(value.maximum)
-                    .minimum(value.minimum)
-                    //.exclusive_maximum(value.exclusive_maximum)
-                    //.exclusive_minimum(value.exclusive_minimum)
+                    .maximum(maximum)
+                    .minimum(minimum)
+                    .exclusive_maximum(exclusive_maximum)
+                    .exclusive_minimum(exclusive_minimum)
                     .max_length(value.max_length)
                     .min_length(value.min_length)
                     .pattern(value.pattern)
@@ -310,6 +810,7 @@ impl From<ParameterGeneric> for openapi::Schema {
 #[skip_serializing_none]
 #[derive(Serialize, Deserialize, Clone, PartialEq)]
 #[cfg_attr(feature = "debug", derive(Debug))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct ParameterBody {
     pub schema: RefOr<Schema>,
@@ -354,4 +855,360 @@ mod tests {
             serde_json::to_value(openapi_paths).unwrap(),
         );
     }
+
+    #[test]
+    fn ambiguous_path_templates() {
+        let paths: Paths = serde_json::from_value(json!({
+            "/users/{id}": {},
+            "/users/{userId}": {},
+            "/users/me": {},
+            "/users/{id}/pets": {},
+            "/{tenant}/me/pets": {},
+            "/files/{name}.json": {},
+            "/files/{id}": {}
+        }))
+        .unwrap();
+
+        let (_, diagnostics) = diagnostics::collect(|| openapi::Paths::from(paths));
+
+        let reported: Vec<_> = diagnostics
+            .iter()
+            .map(|d| (d.pointer.as_str(), d.message.as_str()))
+            .collect();
+        assert_eq!(
+            reported,
+            [
+                (
+                    "/~1files~1{name}.json",
+                    "path `/files/{name}.json` is shadowed by the template of `/files/{id}`"
+                ),
+                (
+                    "/~1users~1me",
+                    "path `/users/me` is shadowed by the template of `/users/{id}`"
+                ),
+                (
+                    "/~1users~1me",
+                    "path `/users/me` is shadowed by the template of `/users/{userId}`"
+                ),
+                (
+                    "/~1users~1{userId}",
+                    "path `/users/{userId}` matches the same requests as `/users/{id}`"
+                ),
+                (
+                    "/~1{tenant}~1me~1pets",
+                    "path `/{tenant}/me/pets` overlaps `/users/{id}/pets`"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn duplicate_parameters() {
+        let operation: Operation = serde_json::from_value(json!({
+            "parameters": [
+                {"name": "id", "in": "query", "type": "string"},
+                {"name": "id", "in": "header", "type": "string"},
+                {"name": "id", "in": "query", "type": "integer"}
+            ],
+            "responses": {}
+        }))
+        .unwrap();
+
+        let (operation, diagnostics) =
+            diagnostics::collect(|| openapi::path::Operation::from(operation));
+
+        let parameters = serde_json::to_value(operation.parameters).unwrap();
+        assert_eq!(parameters[0]["schema"], json!({"type": "string"}));
+        assert_eq!(parameters.as_array().unwrap().len(), 2);
+        assert_eq!(diagnostics[0].pointer, "/parameters/2");
+    }
+
+    #[test]
+    fn overridden_parameters() {
+        let path_item: PathItem = serde_json::from_value(json!({
+            "parameters": [
+                {"name": "id", "in": "path", "required": true, "type": "string"},
+                {"name": "limit", "in": "query", "type": "integer"}
+            ],
+            "get": {
+                "parameters": [
+                    {"name": "limit", "in": "header", "type": "integer"},
+                    {"name": "id", "in": "path", "required": true, "type": "integer"}
+                ],
+                "responses": {}
+            }
+        }))
+        .unwrap();
+
+        let (path_item, diagnostics) = diagnostics::collect(|| openapi::PathItem::from(path_item));
+
+        let path_item = serde_json::to_value(path_item).unwrap();
+        assert_eq!(
+            path_item["get"]["parameters"][1]["schema"],
+            json!({"type": "integer"})
+        );
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|d| (d.pointer.as_str(), d.message.as_str()))
+                .collect::<Vec<_>>(),
+            [(
+                "/get/parameters/1",
+                "path parameter `id` overrides the path level parameter `id`"
+            )]
+        );
+    }
+
+    #[test]
+    fn unknown_path_item_members() {
+        let path_item: PathItem = serde_json::from_value(json!({
+            "get": {"responses": {}},
+            "gets": {"responses": {}},
+            "connect": {"responses": {}},
+            "x-internal": true
+        }))
+        .unwrap();
+
+        let (path_item, diagnostics) = diagnostics::collect(|| openapi::PathItem::from(path_item));
+
+        assert_eq!(
+            path_item.operations.keys().collect::<Vec<_>>(),
+            vec![&openapi::PathItemType::Get]
+        );
+        let pointers: Vec<_> = diagnostics.iter().map(|d| d.pointer.as_str()).collect();
+        assert_eq!(pointers, vec!["/connect", "/gets"]);
+        assert_eq!(
+            serde_json::to_value(path_item).unwrap()["x-internal"],
+            json!(true)
+        );
+    }
+
+    #[test]
+    fn path_item_refs() {
+        let swagger: crate::Swagger = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "paths": {
+                "/pets": {"get": {"responses": {"200": {"description": "ok"}}}},
+                "/animals": {"$ref": "#/paths/~1pets"},
+                "/owners": {"$ref": "owners.json#/paths/~1owners"}
+            }
+        }))
+        .unwrap();
+
+        let conversion = crate::convert(swagger);
+
+        assert!(conversion.diagnostics.is_empty());
+        let paths = &serde_json::to_value(conversion.openapi).unwrap()["paths"];
+        assert_eq!(paths["/animals"]["$ref"], json!("#/paths/~1pets"));
+        assert_eq!(
+            paths["/owners"]["$ref"],
+            json!("owners.json#/paths/~1owners")
+        );
+    }
+
+    #[test]
+    fn x_callbacks() {
+        let operation: Operation = serde_json::from_value(json!({
+            "responses": {},
+            "x-callbacks": {
+                "onAdopted": {
+                    "{$request.body#/callbackUrl}": {
+                        "post": {
+                            "parameters": [
+                                {
+                                    "name": "pet",
+                                    "in": "body",
+                                    "schema": {"$ref": "#/definitions/Pet"}
+                                }
+                            ],
+                            "responses": {"200": {"description": "Received"}}
+                        }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+
+        let (operation, diagnostics) =
+            diagnostics::collect(|| openapi::path::Operation::from(operation));
+
+        assert!(diagnostics.is_empty());
+        let operation = serde_json::to_value(operation).unwrap();
+        assert!(operation.get("x-callbacks").is_none());
+        let callback = &operation["callbacks"]["onAdopted"]["{$request.body#/callbackUrl}"];
+        assert_eq!(
+            callback["post"]["requestBody"]["content"]["application/json"]["schema"],
+            json!({"$ref": "#/components/schemas/Pet"})
+        );
+    }
+
+    #[test]
+    fn path_level_request_body() {
+        let path_item: PathItem = serde_json::from_value(json!({
+            "parameters": [
+                {"name": "id", "in": "path", "required": true, "type": "string"},
+                {"name": "pet", "in": "body", "schema": {"$ref": "#/definitions/Pet"}}
+            ],
+            "put": {"responses": {}},
+            "patch": {
+                "parameters": [{"name": "patch", "in": "body", "schema": {"type": "object"}}],
+                "responses": {}
+            }
+        }))
+        .unwrap();
+
+        let (path_item, diagnostics) = diagnostics::collect(|| openapi::PathItem::from(path_item));
+
+        let pointers: Vec<_> = diagnostics.iter().map(|d| d.pointer.as_str()).collect();
+        assert_eq!(pointers, ["/patch/parameters/0"]);
+        let path_item = serde_json::to_value(path_item).unwrap();
+        assert_eq!(path_item["parameters"].as_array().unwrap().len(), 1);
+        assert_eq!(
+            path_item["put"]["requestBody"]["content"]["application/json"]["schema"],
+            json!({"$ref": "#/components/schemas/Pet"})
+        );
+        assert_eq!(
+            path_item["patch"]["requestBody"]["content"]["application/json"]["schema"],
+            json!({"type": "object"})
+        );
+    }
+
+    #[test]
+    fn form_data_request_body() {
+        let path_item: PathItem = serde_json::from_value(json!({
+            "parameters": [
+                {"name": "token", "in": "formData", "required": true, "type": "string"},
+                {"name": "tag", "in": "formData", "type": "string"}
+            ],
+            "post": {
+                "parameters": [
+                    {"name": "name", "in": "formData", "type": "string", "description": "Name"},
+                    {"name": "tag", "in": "formData", "type": "integer"}
+                ],
+                "responses": {}
+            }
+        }))
+        .unwrap();
+
+        let (path_item, _) = diagnostics::collect(|| openapi::PathItem::from(path_item));
+
+        let path_item = serde_json::to_value(path_item).unwrap();
+        let request_body = &path_item["post"]["requestBody"];
+        assert_eq!(request_body["required"], json!(true));
+        assert_json_eq!(
+            request_body["content"]["application/x-www-form-urlencoded"]["schema"],
+            json!({
+                "type": "object",
+                "properties": {
+                    "name": {"type": "string", "description": "Name"},
+                    "tag": {"type": "integer"},
+                    "token": {"type": "string"}
+                },
+                "required": ["token"]
+            })
+        );
+    }
+
+    #[test]
+    fn header_collection_format() {
+        let parameters: Vec<Parameter> = serde_json::from_value(json!([
+            {"name": "X-Ids", "in": "header", "type": "array", "items": {"type": "string"}, "collectionFormat": "csv"},
+            {"name": "X-Tags", "in": "header", "type": "array", "items": {"type": "string"}, "collectionFormat": "ssv"}
+        ]))
+        .unwrap();
+
+        let (parameters, diagnostics) = diagnostics::collect(|| {
+            parameters
+                .into_iter()
+                .map(|p| openapi::path::Parameter::try_from(p).unwrap())
+                .collect::<Vec<_>>()
+        });
+
+        let parameters = serde_json::to_value(parameters).unwrap();
+        assert_eq!(parameters[0]["style"], "simple");
+        assert_eq!(parameters[0]["explode"], false);
+        assert!(parameters[1].get("style").is_none());
+        assert_eq!(diagnostics.len(), 1);
+    }
+
+    #[test]
+    fn implicit_csv_collection_format() {
+        let parameters: Vec<Parameter> = serde_json::from_value(json!([
+            {"name": "ids", "in": "query", "type": "array", "items": {"type": "string"}},
+            {"name": "X-Ids", "in": "header", "type": "array", "items": {"type": "string"}},
+            {"name": "id", "in": "query", "type": "string"}
+        ]))
+        .unwrap();
+
+        let (parameters, diagnostics) = diagnostics::collect(|| {
+            parameters
+                .into_iter()
+                .map(|p| openapi::path::Parameter::try_from(p).unwrap())
+                .collect::<Vec<_>>()
+        });
+
+        let parameters = serde_json::to_value(parameters).unwrap();
+        assert_eq!(parameters[0]["style"], "form");
+        assert_eq!(parameters[0]["explode"], false);
+        assert_eq!(parameters[1]["style"], "simple");
+        assert!(parameters[2].get("style").is_none());
+        assert!(parameters[2].get("explode").is_none());
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn allow_reserved_extension() {
+        let parameter: Parameter = serde_json::from_value(json!({
+            "name": "path", "in": "query", "type": "string", "x-allowReserved": true
+        }))
+        .unwrap();
+
+        let parameter = openapi::path::Parameter::try_from(parameter).unwrap();
+
+        let parameter = serde_json::to_value(parameter).unwrap();
+        assert_eq!(parameter["allowReserved"], true);
+        assert!(parameter.get("x-allowReserved").is_none());
+    }
+
+    #[test]
+    fn parameter_exclusive_bounds() {
+        let parameters: Vec<ParameterGeneric> = serde_json::from_value(json!([
+            {"type": "integer", "minimum": 0, "exclusiveMinimum": true, "maximum": 9},
+            {"type": "number", "exclusiveMaximum": true}
+        ]))
+        .unwrap();
+
+        let (schemas, diagnostics) = diagnostics::collect(|| {
+            parameters
+                .into_iter()
+                .map(openapi::Schema::from)
+                .collect::<Vec<_>>()
+        });
+
+        assert_json_eq!(
+            serde_json::to_value(schemas).unwrap(),
+            json!([
+                {"type": "integer", "exclusiveMinimum": 0.0, "maximum": 9.0},
+                {"type": "number"}
+            ])
+        );
+        assert_eq!(
+            diagnostics[0].message,
+            "dropped `exclusiveMaximum` without `maximum`"
+        );
+    }
+
+    #[test]
+    fn array_parameter_without_items() {
+        let parameter: ParameterGeneric = serde_json::from_value(json!({"type": "array"})).unwrap();
+
+        let (schema, diagnostics) = diagnostics::collect(|| openapi::Schema::from(parameter));
+
+        assert_json_eq!(
+            serde_json::to_value(schema).unwrap(),
+            json!({"type": "array", "items": {}})
+        );
+        assert_eq!(diagnostics.len(), 1);
+    }
 }
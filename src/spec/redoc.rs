@@ -0,0 +1,154 @@
+//! Vendor extensions of ReDoc, which renders documentation from OpenAPI documents.
+//!
+//! `x-tagGroups` is kept at the root and checked against the tags of the document,
+//! `x-logo` belongs into `info` and `x-code-samples` or `x-codeSamples` onto operations.
+
+use std::collections::BTreeSet;
+
+use serde_json::Value;
+
+use crate::diagnostics;
+
+use super::{PathItem, Swagger};
+
+/// Names ReDoc reads the code samples of an operation from, the first one is deprecated.
+const CODE_SAMPLES: [&str; 2] = ["x-code-samples", "x-codeSamples"];
+
+/// Reports the tags `x-tagGroups` names which the document neither declares nor uses, and
+/// the tags in no group, which ReDoc does not show.
+pub(crate) fn tag_groups(tag_groups: &Value, swagger: &Swagger) {
+    let Some(groups) = tag_groups.as_array() else {
+        diagnostics::warn("`x-tagGroups` is not a list of tag groups");
+        return;
+    };
+    let declared = swagger.tags.iter().flatten().map(|tag| tag.name.as_str());
+    let used = swagger
+        .paths
+        .paths
+        .values()
+        .flat_map(|path_item| path_item.operations.values())
+        .flat_map(|operation| operation.tags.iter().flatten())
+        .map(String::as_str);
+    let tags: BTreeSet<&str> = declared.chain(used).collect();
+
+    let mut grouped = BTreeSet::new();
+    for (i, group) in groups.iter().enumerate() {
+        let name = group["name"].as_str().unwrap_or_default();
+        let names = group["tags"].as_array().into_iter().flatten();
+        for (j, tag) in names.enumerate() {
+            let Some(tag) = tag.as_str() else {
+                continue;
+            };
+            grouped.insert(tag);
+            if !tags.contains(tag) {
+                diagnostics::scope(i, || {
+                    diagnostics::scope("tags", || {
+                        diagnostics::scope(j, || {
+                            diagnostics::warn(format!(
+                                "tag group `{name}` names the unknown tag `{tag}`"
+                            ))
+                        })
+                    })
+                });
+            }
+        }
+    }
+    for tag in tags.difference(&grouped) {
+        diagnostics::warn(format!(
+            "tag `{tag}` is in no tag group, ReDoc does not show its operations"
+        ));
+    }
+}
+
+/// Moves the code samples of `path_item` onto its operations lacking their own, ReDoc only
+/// reads them from operations.
+pub(crate) fn code_samples(path_item: &mut PathItem) {
+    for name in CODE_SAMPLES {
+        let Some(samples) = path_item.extensions.0.remove(name) else {
+            continue;
+        };
+        if path_item.operations.is_empty() {
+            diagnostics::scope(name, || {
+                diagnostics::warn(format!(
+                    "dropped `{name}` of a path item without operations"
+                ))
+            });
+        }
+        for operation in path_item.operations.values_mut() {
+            if !CODE_SAMPLES
+                .iter()
+                .any(|n| operation.extensions.contains_key(*n))
+            {
+                operation
+                    .extensions
+                    .0
+                    .insert(name.to_string(), samples.clone());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    #[test]
+    fn redoc_extensions() {
+        let swagger = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "x-logo": {"url": "https://example.com/logo.png"},
+            "x-tagGroups": [{"name": "Animals", "tags": ["pets", "birds"]}],
+            "tags": [{"name": "pets"}, {"name": "admin"}],
+            "paths": {
+                "/pets": {
+                    "x-code-samples": [{"lang": "Shell", "source": "curl /pets"}],
+                    "get": {"tags": ["pets"], "responses": {}},
+                    "post": {
+                        "tags": ["pets"],
+                        "x-codeSamples": [{"lang": "Shell", "source": "curl -X POST /pets"}],
+                        "responses": {}
+                    }
+                }
+            }
+        }))
+        .unwrap();
+
+        let conversion = crate::convert(swagger);
+
+        let document = conversion.to_value();
+        assert_eq!(
+            document["info"]["x-logo"],
+            json!({"url": "https://example.com/logo.png"})
+        );
+        assert_eq!(document["x-tagGroups"][0]["name"], "Animals");
+        let path_item = &document["paths"]["/pets"];
+        assert!(path_item.get("x-code-samples").is_none());
+        assert_eq!(
+            path_item["get"]["x-code-samples"][0]["source"],
+            "curl /pets"
+        );
+        assert!(path_item["post"].get("x-code-samples").is_none());
+        assert_eq!(
+            conversion
+                .diagnostics
+                .iter()
+                .map(|d| (d.pointer.as_str(), d.message.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "/x-tagGroups/0/tags/1",
+                    "tag group `Animals` names the unknown tag `birds`"
+                ),
+                (
+                    "/x-tagGroups",
+                    "tag `admin` is in no tag group, ReDoc does not show its operations"
+                ),
+                (
+                    "/x-logo",
+                    "moved `x-logo` into `info`, where ReDoc expects it"
+                ),
+            ]
+        );
+    }
+}
@@ -4,37 +4,95 @@ use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 use utoipa::openapi::{self};
 
+use crate::{diagnostics, options, sanitize};
+
 use super::{Extensions, ParameterGeneric, RefOr, Schema};
 
 /// https://swagger.io/specification/v2/#responses-object
 #[skip_serializing_none]
 #[derive(Serialize, Deserialize, Clone, PartialEq)]
 #[cfg_attr(feature = "debug", derive(Debug))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct Responses {
-    #[serde(flatten)]
+    #[serde(flatten, deserialize_with = "status_codes")]
     pub responses: BTreeMap<String, RefOr<Response>>,
     pub default: Option<RefOr<Response>>,
     #[serde(flatten)]
     pub extensions: Option<Extensions>,
 }
 
+/// Responses by status code, also accepting the integer keys YAML documents have for
+/// unquoted codes. These are converted to strings with a warning.
+fn status_codes<'de, D>(deserializer: D) -> Result<BTreeMap<String, RefOr<Response>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+    #[serde(untagged)]
+    enum StatusCode {
+        Text(String),
+        Number(u64),
+    }
+
+    let responses = BTreeMap::<StatusCode, RefOr<Response>>::deserialize(deserializer)?;
+    Ok(responses
+        .into_iter()
+        .map(|(status, response)| match status {
+            StatusCode::Text(status) => (status, response),
+            StatusCode::Number(status) => {
+                diagnostics::warn(format!(
+                    "status code {status} is not a string, it was converted to \"{status}\""
+                ));
+                (status.to_string(), response)
+            }
+        })
+        .collect())
+}
+
 impl From<Responses> for openapi::Responses {
     fn from(value: Responses) -> Self {
-        let resp_iter = value
-            .responses
-            .into_iter()
-            .map(|(k, v)| (k, v.into_openapi_ref()));
+        let lenient = options::current().lenient;
+        let resp_iter = value.responses.into_iter().filter_map(|(k, v)| {
+            let k = match status_range(&k) {
+                Some(range) if lenient => range,
+                Some(_) => {
+                    diagnostics::scope(&k, || {
+                        diagnostics::warn(format!(
+                            "dropped response for status code range `{k}`, which Swagger 2.0 \
+                             does not allow, convert leniently to keep it"
+                        ))
+                    });
+                    return None;
+                }
+                None => k,
+            };
+            let v = diagnostics::scope(&k, || v.into_openapi_ref());
+            Some((k, v))
+        });
         openapi::ResponsesBuilder::new()
             .responses_from_iter(resp_iter)
             .build()
     }
 }
 
+/// OpenAPI 3.0 status code range like `2XX` for a key like `2XX` or `2xx`.
+fn status_range(status: &str) -> Option<String> {
+    match status.as_bytes() {
+        [class @ b'1'..=b'5', x, y]
+            if x.eq_ignore_ascii_case(&b'x') && y.eq_ignore_ascii_case(&b'x') =>
+        {
+            Some(format!("{}XX", *class as char))
+        }
+        _ => None,
+    }
+}
+
 /// https://swagger.io/specification/v2/#response-object
 #[skip_serializing_none]
 #[derive(Serialize, Deserialize, Clone, PartialEq)]
 #[cfg_attr(feature = "debug", derive(Debug))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct Response {
     pub description: String,
@@ -47,9 +105,19 @@ pub struct Response {
 
 impl From<Response> for openapi::Response {
     fn from(value: Response) -> Self {
+        let mut extensions = value.extensions.unwrap_or_default();
+        let links = x_links(extensions.0.remove("x-links"));
+        // utoipa has no links, they are emitted next to the extensions
+        let mut extensions = extensions.into_openapi_extensions();
+        if let Some(links) = links {
+            extensions
+                .get_or_insert_with(Default::default)
+                .insert("links".to_string(), links);
+        }
+
         let mut content = openapi::Content::default();
         if let Some(schema) = value.schema {
-            content.schema = schema.into_openapi_ref();
+            content.schema = diagnostics::scope("schema", || schema.into_openapi_ref());
         }
 
         if let Some(examples) = value.examples {
@@ -64,26 +132,62 @@ impl From<Response> for openapi::Response {
         }
 
         let mut response = openapi::ResponseBuilder::new()
-            .description(value.description)
+            .description(sanitize::text(value.description))
             .content("application/json", content) // swagger only supports json
-            .extensions(value.extensions.map(Into::into))
+            .extensions(extensions)
             .build();
 
         response.headers = value
             .headers
             .unwrap_or_default()
             .into_iter()
-            .map(|(k, v)| (k, v.into()))
+            .map(|(k, v)| {
+                let v: openapi::header::Header =
+                    diagnostics::scope("headers", || diagnostics::scope(&k, || v.into()));
+                (k, v)
+            })
             .collect();
 
         response
     }
 }
 
+/// Converts the `x-links` extension of a response, which holds link objects by name like the
+/// links of OpenAPI 3.x. Links naming neither or both of `operationId` and `operationRef`
+/// are reported and dropped.
+fn x_links(value: Option<serde_json::Value>) -> Option<serde_json::Value> {
+    let value = value?;
+    diagnostics::scope("x-links", || {
+        let serde_json::Value::Object(links) = value else {
+            diagnostics::warn("dropped `x-links`, which is not an object");
+            return None;
+        };
+        let links: serde_json::Map<String, serde_json::Value> = links
+            .into_iter()
+            .filter(|(name, link)| {
+                let targets = ["operationId", "operationRef"]
+                    .into_iter()
+                    .filter(|key| link.get(key).is_some())
+                    .count();
+                if targets != 1 {
+                    diagnostics::scope(name, || {
+                        diagnostics::warn(
+                            "dropped link, which needs either `operationId` or `operationRef`",
+                        )
+                    });
+                }
+                targets == 1
+            })
+            .collect();
+        (!links.is_empty()).then_some(serde_json::Value::Object(links))
+    })
+}
+
 /// https://swagger.io/specification/v2/#header-object
 #[skip_serializing_none]
 #[derive(Serialize, Deserialize, Clone, PartialEq)]
 #[cfg_attr(feature = "debug", derive(Debug))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct ParameterHeader {
     pub description: String,
@@ -94,7 +198,7 @@ pub struct ParameterHeader {
 impl From<ParameterHeader> for openapi::header::Header {
     fn from(value: ParameterHeader) -> Self {
         let mut header = openapi::header::Header::default();
-        header.description = Some(value.description);
+        header.description = Some(sanitize::text(value.description));
         header.schema = openapi::RefOr::T(value.parameter.into());
         header
     }
@@ -106,7 +210,7 @@ mod tests {
 
     use assert_json_diff::assert_json_eq;
 
-    use crate::include_json;
+    use crate::{include_json, ConvertOptions};
 
     use super::*;
 
@@ -117,6 +221,74 @@ mod tests {
         let _responses: Responses = serde_json::from_str(&responses).unwrap();
     }
 
+    #[test]
+    fn numeric_status_codes() {
+        let deserializer = serde::de::value::MapDeserializer::<_, serde_json::Error>::new(
+            [(200u16, serde_json::json!({"description": "ok"}))].into_iter(),
+        );
+
+        let (responses, diagnostics) =
+            diagnostics::collect(|| Responses::deserialize(deserializer));
+
+        let responses = responses.unwrap();
+        assert_eq!(responses.responses.keys().collect::<Vec<_>>(), vec!["200"]);
+        assert_eq!(diagnostics.len(), 1);
+    }
+
+    #[test]
+    fn status_code_ranges() {
+        let responses: Responses = serde_json::from_value(serde_json::json!({
+            "200": {"description": "ok"},
+            "4xx": {"description": "client error"}
+        }))
+        .unwrap();
+        let lenient_options = ConvertOptions {
+            lenient: true,
+            ..Default::default()
+        };
+
+        let (strict, diagnostics) =
+            diagnostics::collect(|| openapi::Responses::from(responses.clone()));
+        let lenient = options::scope(lenient_options.into(), || {
+            openapi::Responses::from(responses)
+        });
+
+        assert_eq!(strict.responses.keys().collect::<Vec<_>>(), vec!["200"]);
+        assert_eq!(diagnostics[0].pointer, "/4xx");
+        assert_eq!(
+            lenient.responses.keys().collect::<Vec<_>>(),
+            vec!["200", "4XX"]
+        );
+    }
+
+    #[test]
+    fn x_links() {
+        let response: Response = serde_json::from_value(serde_json::json!({
+            "description": "The created pet",
+            "x-links": {
+                "GetPet": {
+                    "operationId": "getPet",
+                    "parameters": {"id": "$response.body#/id"}
+                },
+                "Owner": {"description": "The owner of the pet"}
+            }
+        }))
+        .unwrap();
+
+        let (response, diagnostics) = diagnostics::collect(|| openapi::Response::from(response));
+
+        let response = serde_json::to_value(response).unwrap();
+        assert_eq!(
+            response["links"],
+            serde_json::json!({
+                "GetPet": {"operationId": "getPet", "parameters": {"id": "$response.body#/id"}}
+            })
+        );
+        assert!(response.get("x-links").is_none());
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].pointer, "/x-links/Owner");
+    }
+
     #[test]
     fn into_openapi_responses() {
         let responses_raw = include_json!("../../tests/swagger.json", "/responses").to_string();
@@ -1,21 +1,76 @@
-use std::collections::BTreeMap;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    convert::Infallible,
+    str::FromStr,
+};
 
-use serde::{Deserialize, Serialize};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize};
 use serde_with::skip_serializing_none;
 use utoipa::openapi;
 
-#[derive(Serialize, Deserialize, Clone, PartialEq)]
+use crate::{diagnostics, sanitize};
+
+use super::{path::method_name, Swagger};
+
+/// https://swagger.io/specification/v2/#security-scheme-object
+#[derive(Serialize, Clone, PartialEq)]
 #[cfg_attr(feature = "debug", derive(Debug))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase", tag = "type")]
 pub enum SecurityScheme {
+    Basic(Basic),
+    ApiKey(ApiKey),
+    Oauth2(Oauth2),
+    /// Scheme of a type Swagger 2.0 does not define, kept so the rest of the document can
+    /// still be converted. It is dropped with a warning, see [`drop_requirements`].
+    #[serde(untagged)]
+    Unknown(serde_json::Value),
+}
+
+/// The schemes Swagger 2.0 defines, whose errors are reported rather than turning them into
+/// [`SecurityScheme::Unknown`].
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+enum KnownScheme {
+    Basic(Basic),
+    ApiKey(ApiKey),
     Oauth2(Oauth2),
 }
 
-impl From<SecurityScheme> for openapi::security::SecurityScheme {
+impl<'de> Deserialize<'de> for SecurityScheme {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        match value.get("type").and_then(|t| t.as_str()) {
+            Some("basic" | "apiKey" | "oauth2") => {
+                let scheme = KnownScheme::deserialize(value).map_err(D::Error::custom)?;
+                Ok(match scheme {
+                    KnownScheme::Basic(basic) => Self::Basic(basic),
+                    KnownScheme::ApiKey(api_key) => Self::ApiKey(api_key),
+                    KnownScheme::Oauth2(oauth) => Self::Oauth2(oauth),
+                })
+            }
+            _ => Ok(Self::Unknown(value)),
+        }
+    }
+}
+
+impl From<SecurityScheme> for Option<openapi::security::SecurityScheme> {
     fn from(value: SecurityScheme) -> Self {
+        use openapi::security::SecurityScheme as OpenApiScheme;
         match value {
-            SecurityScheme::Oauth2(oauth) => Self::OAuth2(oauth.into()),
-            //_ => unimplemented!(),
+            SecurityScheme::Basic(basic) => Some(OpenApiScheme::Http(basic.into())),
+            SecurityScheme::ApiKey(api_key) => Some(OpenApiScheme::ApiKey(api_key.into())),
+            SecurityScheme::Oauth2(oauth) => Some(OpenApiScheme::OAuth2(oauth.into())),
+            SecurityScheme::Unknown(scheme) => {
+                let scheme_type = scheme.get("type").and_then(|t| t.as_str());
+                diagnostics::warn(match scheme_type {
+                    Some(scheme_type) => {
+                        format!("dropped security scheme of unsupported type `{scheme_type}`")
+                    }
+                    None => "dropped invalid security scheme".to_string(),
+                });
+                None
+            }
         }
     }
 }
@@ -23,6 +78,60 @@ impl From<SecurityScheme> for openapi::security::SecurityScheme {
 #[skip_serializing_none]
 #[derive(Serialize, Deserialize, Clone, PartialEq)]
 #[cfg_attr(feature = "debug", derive(Debug))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct Basic {
+    pub description: Option<String>,
+}
+
+impl From<Basic> for openapi::security::Http {
+    fn from(value: Basic) -> Self {
+        openapi::security::HttpBuilder::new()
+            .scheme(openapi::security::HttpAuthScheme::Basic)
+            .description(sanitize::description(value.description))
+            .build()
+    }
+}
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKey {
+    pub description: Option<String>,
+    pub name: String,
+    #[serde(rename = "in")]
+    pub api_key_in: ApiKeyIn,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum ApiKeyIn {
+    Query,
+    Header,
+}
+
+impl From<ApiKey> for openapi::security::ApiKey {
+    fn from(value: ApiKey) -> Self {
+        use openapi::security::ApiKeyValue;
+        let key = match sanitize::description(value.description) {
+            Some(description) => ApiKeyValue::with_description(value.name, description),
+            None => ApiKeyValue::new(value.name),
+        };
+        match value.api_key_in {
+            ApiKeyIn::Query => Self::Query(key),
+            ApiKeyIn::Header => Self::Header(key),
+        }
+    }
+}
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct Oauth2 {
     pub description: Option<String>,
@@ -34,13 +143,14 @@ pub struct Oauth2 {
 impl From<Oauth2> for openapi::security::OAuth2 {
     fn from(value: Oauth2) -> Self {
         let mut oauth2 = Self::new([value.flow.into_openapi_flow(value.scopes)]);
-        oauth2.description = value.description;
+        oauth2.description = sanitize::description(value.description);
         oauth2
     }
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq)]
 #[cfg_attr(feature = "debug", derive(Debug))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase", tag = "flow")]
 pub enum Flow {
     #[serde(rename_all = "camelCase")]
@@ -86,12 +196,279 @@ impl Flow {
     }
 }
 
+/// Security requirement added to every converted document, written `scheme[:scope,...]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequiredSecurity {
+    pub scheme: String,
+    pub scopes: Vec<String>,
+}
+
+impl FromStr for RequiredSecurity {
+    type Err = Infallible;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (scheme, scopes) = value.split_once(':').unwrap_or((value, ""));
+        Ok(Self {
+            scheme: scheme.to_string(),
+            scopes: scopes
+                .split(',')
+                .filter(|scope| !scope.is_empty())
+                .map(str::to_string)
+                .collect(),
+        })
+    }
+}
+
+/// Where [`RequiredSecurity`] is added.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SecurityTarget {
+    /// Appended to the top level requirements of the document, replacing an empty
+    /// requirement, which would keep allowing anonymous access.
+    #[default]
+    Document,
+    /// Set on every operation without a `security` of its own. An empty `security` opts the
+    /// operation out and is kept.
+    Operations,
+}
+
+/// Adds the `required` security requirement to `openapi`, warning about schemes the
+/// document does not define.
+pub(crate) fn require_security(
+    openapi: &mut openapi::OpenApi,
+    required: &[RequiredSecurity],
+    target: SecurityTarget,
+) {
+    if required.is_empty() {
+        return;
+    }
+    for security in required {
+        let defined = openapi
+            .components
+            .as_ref()
+            .is_some_and(|components| components.security_schemes.contains_key(&security.scheme));
+        if !defined {
+            diagnostics::warn(format!(
+                "required security scheme `{}` is not defined",
+                security.scheme
+            ));
+        }
+    }
+    let requirements = || {
+        required
+            .iter()
+            .map(|security| {
+                openapi::SecurityRequirement::new(&security.scheme, security.scopes.clone())
+            })
+            .collect::<Vec<_>>()
+    };
+
+    match target {
+        SecurityTarget::Document => {
+            let security = openapi.security.get_or_insert_with(Vec::new);
+            security.retain(|requirement| !is_anonymous(requirement));
+            security.extend(requirements());
+        }
+        SecurityTarget::Operations => {
+            let operations = openapi
+                .paths
+                .paths
+                .values_mut()
+                .flat_map(|path_item| path_item.operations.values_mut());
+            // an empty `security` explicitly opts the operation out of authentication
+            for operation in operations.filter(|operation| operation.security.is_none()) {
+                operation.security = Some(requirements());
+            }
+        }
+    }
+}
+
+/// Names of the `security_definitions` which are not converted, see
+/// [`SecurityScheme::Unknown`].
+pub(crate) fn dropped_schemes(
+    security_definitions: &BTreeMap<String, SecurityScheme>,
+) -> BTreeSet<String> {
+    security_definitions
+        .iter()
+        .filter(|(_, scheme)| matches!(scheme, SecurityScheme::Unknown(_)))
+        .map(|(name, _)| name.clone())
+        .collect()
+}
+
+/// Removes the security requirements of `swagger` naming one of the `dropped` schemes, which
+/// the converted document does not define, warning at each of them. An operation left without
+/// requirements inherits the top level ones rather than allowing anonymous access.
+pub(crate) fn drop_requirements(swagger: &mut Swagger, dropped: &BTreeSet<String>) {
+    if dropped.is_empty() {
+        return;
+    }
+    if let Some(security) = &mut swagger.security {
+        diagnostics::scope("security", || retain_defined(security, dropped));
+    }
+    let operations = swagger
+        .paths
+        .paths
+        .iter_mut()
+        .flat_map(|(path, path_item)| {
+            path_item
+                .operations
+                .iter_mut()
+                .map(move |(method, operation)| (path, method, &mut operation.security))
+        });
+    drop_operation_requirements(operations, dropped);
+}
+
+/// Like [`drop_requirements`] for the operations of `paths` which were converted already,
+/// when the document is streamed.
+pub(crate) fn drop_path_requirements(paths: &mut openapi::Paths, dropped: &BTreeSet<String>) {
+    if dropped.is_empty() {
+        return;
+    }
+    let operations = paths.paths.iter_mut().flat_map(|(path, path_item)| {
+        path_item
+            .operations
+            .iter_mut()
+            .map(move |(method, operation)| (path, method, &mut operation.security))
+    });
+    drop_operation_requirements(operations, dropped);
+}
+
+fn drop_operation_requirements<'a>(
+    operations: impl Iterator<
+        Item = (
+            &'a String,
+            &'a openapi::PathItemType,
+            &'a mut Option<Vec<openapi::SecurityRequirement>>,
+        ),
+    >,
+    dropped: &BTreeSet<String>,
+) {
+    for (path, method, operation_security) in operations {
+        let Some(security) = operation_security.as_mut() else {
+            continue;
+        };
+        let pointer = format!(
+            "/paths/{}/{}/security",
+            diagnostics::escape(path),
+            method_name(method)
+        );
+        if diagnostics::at(&pointer, || retain_defined(security, dropped)) && security.is_empty() {
+            *operation_security = None;
+        }
+    }
+}
+
+/// Removes the requirements naming one of the `dropped` schemes from `security`, returning
+/// whether there were any.
+fn retain_defined(
+    security: &mut Vec<openapi::SecurityRequirement>,
+    dropped: &BTreeSet<String>,
+) -> bool {
+    let len = security.len();
+    let mut i = 0;
+    security.retain(|requirement| {
+        let scheme = schemes(requirement)
+            .into_iter()
+            .find(|scheme| dropped.contains(scheme));
+        if let Some(scheme) = &scheme {
+            diagnostics::scope(i, || {
+                diagnostics::warn(format!(
+                    "dropped security requirement naming the dropped scheme `{scheme}`"
+                ))
+            });
+        }
+        i += 1;
+        scheme.is_none()
+    });
+    security.len() < len
+}
+
+/// Names of the schemes `requirement` requires.
+fn schemes(requirement: &openapi::SecurityRequirement) -> Vec<String> {
+    match serde_json::to_value(requirement) {
+        Ok(serde_json::Value::Object(schemes)) => schemes.keys().cloned().collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Whether `requirement` is the empty requirement `{}`, which is met without credentials.
+fn is_anonymous(requirement: &openapi::SecurityRequirement) -> bool {
+    serde_json::to_value(requirement).is_ok_and(|value| value == serde_json::json!({}))
+}
+
 #[cfg(test)]
 mod tests {
+    use serde_json::json;
+
     use crate::include_json;
 
     use super::*;
 
+    #[test]
+    fn require_security_on_operations() {
+        let swagger: crate::spec::Swagger = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "securityDefinitions": {"oauth": {"type": "oauth2", "flow": "implicit", "authorizationUrl": "https://example.com/auth", "scopes": {}}},
+            "paths": {
+                "/pets": {
+                    "get": {"responses": {}},
+                    "post": {"security": [{"key": []}], "responses": {}},
+                    "delete": {"security": [], "responses": {}}
+                }
+            }
+        }))
+        .unwrap();
+        let mut openapi: openapi::OpenApi = swagger.into();
+        let required = ["oauth:pets.read,pets.write".parse().unwrap()];
+
+        let ((), diagnostics) = diagnostics::collect(|| {
+            require_security(&mut openapi, &required, SecurityTarget::Operations)
+        });
+
+        let paths = serde_json::to_value(&openapi.paths).unwrap();
+        assert_eq!(
+            paths["paths"]["/pets"]["get"]["security"],
+            json!([{"oauth": ["pets.read", "pets.write"]}])
+        );
+        assert_eq!(
+            paths["paths"]["/pets"]["post"]["security"],
+            json!([{"key": []}])
+        );
+        assert_eq!(paths["paths"]["/pets"]["delete"]["security"], json!([]));
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn empty_security_requirements() {
+        let swagger = json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "securityDefinitions": {"key": {"type": "apiKey", "name": "key", "in": "header"}},
+            "security": [{}],
+            "paths": {
+                "/pets": {
+                    "get": {"security": [], "responses": {"200": {"description": "ok"}}}
+                }
+            }
+        })
+        .to_string();
+        let convert = |required_security| {
+            let options = crate::ConvertOptions {
+                required_security,
+                ..Default::default()
+            };
+            crate::convert_str(&swagger, &options).unwrap().to_value()
+        };
+
+        let kept = convert(vec![]);
+        let required = convert(vec!["key".parse().unwrap()]);
+
+        assert_eq!(kept["security"], json!([{}]));
+        assert_eq!(kept["paths"]["/pets"]["get"]["security"], json!([]));
+        assert_eq!(required["security"], json!([{"key": []}]));
+        assert_eq!(required["paths"]["/pets"]["get"]["security"], json!([]));
+    }
+
     #[test]
     fn serde_security() {
         let security_raw = include_json!("../../tests/swagger.json", "/securityDefinitions");
@@ -112,12 +489,83 @@ mod tests {
 
         let security: BTreeMap<String, SecurityScheme> =
             serde_json::from_str(&security_raw).unwrap();
-        let openapi_security: BTreeMap<String, openapi::security::SecurityScheme> =
-            security.into_iter().map(|(k, v)| (k, v.into())).collect();
+        let openapi_security: BTreeMap<String, openapi::security::SecurityScheme> = security
+            .into_iter()
+            .filter_map(|(k, v)| Some((k, Option::from(v)?)))
+            .collect();
 
         assert_eq!(
             security_openapi_raw,
             serde_json::to_value(openapi_security).unwrap()
         );
     }
+
+    #[test]
+    fn basic_api_key_and_unknown_schemes() {
+        let security: BTreeMap<String, SecurityScheme> = serde_json::from_value(json!({
+            "basic": {"type": "basic"},
+            "key": {"type": "apiKey", "name": "X-Api-Key", "in": "header", "description": "Key"},
+            "tls": {"type": "mutualTLS"}
+        }))
+        .unwrap();
+
+        let (openapi_security, diagnostics) = diagnostics::collect(|| {
+            security
+                .into_iter()
+                .filter_map(|(k, v)| Some((k, Option::from(v)?)))
+                .collect::<BTreeMap<String, openapi::security::SecurityScheme>>()
+        });
+
+        assert_eq!(
+            serde_json::to_value(openapi_security).unwrap(),
+            json!({
+                "basic": {"type": "http", "scheme": "basic"},
+                "key": {"type": "apiKey", "name": "X-Api-Key", "in": "header", "description": "Key"}
+            })
+        );
+        assert_eq!(diagnostics.len(), 1);
+    }
+
+    #[test]
+    fn requirements_of_unknown_schemes() {
+        let ok = json!({"200": {"description": "ok"}});
+        let swagger = json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "securityDefinitions": {
+                "key": {"type": "apiKey", "name": "key", "in": "header"},
+                "tls": {"type": "mutualTLS"}
+            },
+            "security": [{"tls": []}, {"key": []}],
+            "paths": {
+                "/pets": {
+                    "get": {"security": [{"tls": []}], "responses": ok.clone()},
+                    "delete": {"security": [], "responses": ok}
+                }
+            }
+        });
+        let mut invalid = swagger.clone();
+        invalid["securityDefinitions"]["key"] = json!({"type": "apiKey", "in": "header"});
+
+        let conversion = crate::convert_str(&swagger.to_string(), &Default::default()).unwrap();
+
+        let document = conversion.to_value();
+        assert_eq!(document["security"], json!([{"key": []}]));
+        assert!(document["paths"]["/pets"]["get"].get("security").is_none());
+        assert_eq!(document["paths"]["/pets"]["delete"]["security"], json!([]));
+        let pointers: Vec<&str> = conversion
+            .diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.pointer.as_str())
+            .collect();
+        assert_eq!(
+            pointers,
+            [
+                "/security/0",
+                "/paths/~1pets/get/security/0",
+                "/securityDefinitions/tls"
+            ]
+        );
+        assert!(crate::convert_str(&invalid.to_string(), &Default::default()).is_err());
+    }
 }
@@ -1,8 +1,14 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use itertools::Itertools;
 use serde::{Deserialize, Serialize};
-use utoipa::openapi;
+use utoipa::openapi::{self, server::ServerVariable};
+
+use crate::{diagnostics, options};
 
 #[derive(Serialize, Deserialize, Clone, PartialEq)]
 #[cfg_attr(feature = "debug", derive(Debug))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub enum ProtocolSchemes {
     Http,
@@ -11,25 +17,217 @@ pub enum ProtocolSchemes {
     Wss,
 }
 
-pub(crate) fn openapi_servers_from_host(
+/// Change of the `basePath` the servers of a document are generated from.
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub enum BasePath {
+    /// Use the given path instead of the `basePath` of the document.
+    Replace(String),
+    /// Prepend the given path to the `basePath` of the document.
+    Prefix(String),
+}
+
+impl BasePath {
+    pub(crate) fn apply(&self, base_path: Option<String>) -> String {
+        let segments = match self {
+            BasePath::Replace(path) => path.split('/').collect::<Vec<_>>(),
+            BasePath::Prefix(prefix) => prefix
+                .split('/')
+                .chain(base_path.as_deref().unwrap_or_default().split('/'))
+                .collect(),
+        };
+        format!(
+            "/{}",
+            segments.into_iter().filter(|s| !s.is_empty()).join("/")
+        )
+    }
+}
+
+/// Host of the API in one of its environments, as listed by the `x-hosts` extension.
+#[derive(Deserialize)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[serde(untagged)]
+pub(crate) enum Host {
+    Name(String),
+    Environment {
+        host: String,
+        description: Option<String>,
+    },
+}
+
+impl Host {
+    fn name(&self) -> &str {
+        match self {
+            Host::Name(host) | Host::Environment { host, .. } => host,
+        }
+    }
+
+    fn description(&self) -> Option<&str> {
+        match self {
+            Host::Name(_) => None,
+            Host::Environment { description, .. } => description.as_deref(),
+        }
+    }
+}
+
+/// Generates a server for every combination of the `hosts` and the `schemes`, ordered by
+/// host. Without `schemes`, Swagger 2.0 uses the scheme the document was fetched with, which
+/// is unknown here, so https is assumed and reported.
+pub(crate) fn openapi_servers_from_hosts(
     schemes: Option<Vec<ProtocolSchemes>>,
-    host: Option<String>,
+    hosts: Vec<Host>,
     base_path: Option<String>,
 ) -> Option<Vec<openapi::Server>> {
-    let host = host?;
-    let servers = schemes?
-        .into_iter()
-        .map(|s| {
-            let prefix = match s {
-                ProtocolSchemes::Http => "http",
-                ProtocolSchemes::Https => "https",
-                ProtocolSchemes::Ws => "ws",
-                ProtocolSchemes::Wss => "wss",
-            };
-            let base_path = base_path.as_deref().unwrap_or("/");
-            let url = format!("{prefix}://{host}{base_path}");
-            openapi::Server::new(url)
+    if hosts.is_empty() {
+        return None;
+    }
+    let schemes = schemes.unwrap_or_else(|| {
+        diagnostics::warn("no `schemes` given for the `host`, assumed https");
+        vec![ProtocolSchemes::Https]
+    });
+    let base_path = base_path.as_deref().unwrap_or("/");
+    let servers = hosts
+        .iter()
+        .flat_map(|host| {
+            schemes.iter().map(move |s| {
+                let prefix = match s {
+                    ProtocolSchemes::Http => "http",
+                    ProtocolSchemes::Https => "https",
+                    ProtocolSchemes::Ws => "ws",
+                    ProtocolSchemes::Wss => "wss",
+                };
+                let url = format!("{prefix}://{}{base_path}", host.name());
+                let mut server = openapi::Server::new(url);
+                server.description = host.description().map(str::to_string);
+                server
+            })
         })
         .collect();
     Some(servers)
 }
+
+/// Hosts of an `x-hosts` extension, which is reported and dropped unless it holds a list of
+/// hosts or of objects with the `host` and `description` of an environment.
+pub(crate) fn x_hosts(value: Option<serde_json::Value>) -> Option<Vec<Host>> {
+    let value = value?;
+    diagnostics::scope("x-hosts", || match serde_json::from_value(value) {
+        Ok(hosts) => Some(hosts),
+        Err(_) => {
+            diagnostics::warn(
+                "dropped invalid `x-hosts`, which needs a list of hosts or of objects with a \
+                 `host`",
+            );
+            None
+        }
+    })
+}
+
+/// Servers of an `x-servers` extension, which is reported and dropped unless it holds a list
+/// of servers.
+pub(crate) fn x_servers(value: Option<serde_json::Value>) -> Option<Vec<openapi::Server>> {
+    let value = value?;
+    diagnostics::scope("x-servers", || match serde_json::from_value(value) {
+        Ok(servers) => Some(servers),
+        Err(err) => {
+            diagnostics::warn(format!("dropped invalid `x-servers`: {err}"));
+            None
+        }
+    })
+}
+
+/// Declares the placeholders in the URLs of generated `servers`, like `{region}` in a
+/// templated `host`, as server variables. Variables come from [`ConvertOptions::server_variables`]
+/// or the `x-server-variables` extension, placeholders neither declares default to their name.
+///
+/// [`ConvertOptions::server_variables`]: crate::ConvertOptions::server_variables
+pub(crate) fn variables(
+    servers: Option<Vec<openapi::Server>>,
+    extension: Option<serde_json::Value>,
+) -> Option<Vec<openapi::Server>> {
+    let mut declared: BTreeMap<String, ServerVariable> = match extension {
+        Some(value) => diagnostics::scope("x-server-variables", || {
+            serde_json::from_value(value).unwrap_or_else(|err| {
+                diagnostics::warn(format!("dropped invalid `x-server-variables`: {err}"));
+                BTreeMap::new()
+            })
+        }),
+        None => BTreeMap::new(),
+    };
+    let options = options::current();
+    for (name, variable) in &options.server_variables {
+        declared.insert(name.clone(), variable.clone());
+    }
+
+    let mut servers = servers?;
+    let mut undeclared = BTreeSet::new();
+    for server in &mut servers {
+        let mut variables = BTreeMap::new();
+        for name in placeholders(&server.url) {
+            let variable = declared.get(name).cloned().unwrap_or_else(|| {
+                undeclared.insert(name.to_string());
+                let mut variable = ServerVariable::default();
+                variable.default_value = name.to_string();
+                variable
+            });
+            variables.insert(name.to_string(), variable);
+        }
+        if !variables.is_empty() {
+            server.variables = Some(variables);
+        }
+    }
+    for name in undeclared {
+        diagnostics::warn(format!(
+            "server variable `{name}` has no default, using its name; declare it in \
+             `x-server-variables`"
+        ));
+    }
+    Some(servers)
+}
+
+/// Names of the `{name}` placeholders in `url`.
+fn placeholders(url: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = url;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let name = &rest[start + 1..start + len];
+        if !name.is_empty() && !names.contains(&name) {
+            names.push(name);
+        }
+        rest = &rest[start + len + 1..];
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base_path_override() {
+        let replace = BasePath::Replace("api/v3/".to_string());
+        assert_eq!(replace.apply(Some("/v1".to_string())), "/api/v3");
+
+        let prefix = BasePath::Prefix("/gateway/".to_string());
+        assert_eq!(prefix.apply(Some("/v1".to_string())), "/gateway/v1");
+        assert_eq!(prefix.apply(None), "/gateway");
+    }
+
+    #[test]
+    fn host_without_schemes() {
+        let hosts = vec![Host::Name("api.example.com".to_string())];
+
+        let (servers, diagnostics) = diagnostics::collect(|| {
+            openapi_servers_from_hosts(None, hosts, Some("/v1".to_string()))
+        });
+
+        let urls: Vec<_> = servers.unwrap().into_iter().map(|s| s.url).collect();
+        assert_eq!(urls, ["https://api.example.com/v1"]);
+        assert_eq!(
+            diagnostics[0].message,
+            "no `schemes` given for the `host`, assumed https"
+        );
+    }
+}
@@ -0,0 +1,754 @@
+//! Upgrade of Swagger 1.2 documents to the Swagger 2.0 model.
+//!
+//! A 1.2 API is described by a resource listing and one API declaration per listed
+//! resource. The declarations are merged into a single [`Swagger`] document which is then
+//! converted like any other Swagger 2.0 input.
+//!
+//! https://github.com/OAI/OpenAPI-Specification/blob/main/versions/1.2.md
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use serde_with::skip_serializing_none;
+
+use crate::diagnostics;
+
+use super::Swagger;
+
+/// https://github.com/OAI/OpenAPI-Specification/blob/main/versions/1.2.md#51-resource-listing
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceListing {
+    pub swagger_version: String,
+    #[serde(default)]
+    pub apis: Vec<Resource>,
+    pub api_version: Option<String>,
+    pub info: Option<Info>,
+    #[serde(default)]
+    pub authorizations: BTreeMap<String, Authorization>,
+}
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[serde(rename_all = "camelCase")]
+pub struct Resource {
+    pub path: String,
+    pub description: Option<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[serde(rename_all = "camelCase")]
+pub struct Info {
+    pub title: String,
+    pub description: Option<String>,
+    pub terms_of_service_url: Option<String>,
+    pub contact: Option<String>,
+    pub license: Option<String>,
+    pub license_url: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum Authorization {
+    BasicAuth,
+    #[serde(rename_all = "camelCase")]
+    ApiKey {
+        pass_as: String,
+        keyname: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    Oauth2 {
+        #[serde(default)]
+        scopes: Vec<Scope>,
+        grant_types: GrantTypes,
+    },
+}
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Scope {
+    pub scope: String,
+    pub description: Option<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[serde(rename_all = "camelCase")]
+pub struct GrantTypes {
+    pub implicit: Option<Implicit>,
+    #[serde(rename = "authorization_code")]
+    pub authorization_code: Option<AuthorizationCode>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[serde(rename_all = "camelCase")]
+pub struct Implicit {
+    pub login_endpoint: Endpoint,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorizationCode {
+    pub token_request_endpoint: Endpoint,
+    pub token_endpoint: Endpoint,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Endpoint {
+    pub url: String,
+}
+
+/// https://github.com/OAI/OpenAPI-Specification/blob/main/versions/1.2.md#52-api-declaration
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[serde(rename_all = "camelCase")]
+pub struct ApiDeclaration {
+    pub swagger_version: String,
+    pub api_version: Option<String>,
+    pub base_path: String,
+    pub resource_path: Option<String>,
+    #[serde(default)]
+    pub apis: Vec<Api>,
+    #[serde(default)]
+    pub models: BTreeMap<String, Model>,
+    pub produces: Option<Vec<String>>,
+    pub consumes: Option<Vec<String>>,
+}
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Api {
+    pub path: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub operations: Vec<Operation>,
+}
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[serde(rename_all = "camelCase")]
+pub struct Operation {
+    pub method: String,
+    pub summary: Option<String>,
+    pub notes: Option<String>,
+    pub nickname: Option<String>,
+    pub authorizations: Option<BTreeMap<String, Vec<Scope>>>,
+    #[serde(default)]
+    pub parameters: Vec<Parameter>,
+    #[serde(default)]
+    pub response_messages: Vec<ResponseMessage>,
+    pub produces: Option<Vec<String>>,
+    pub consumes: Option<Vec<String>>,
+    pub deprecated: Option<String>,
+    #[serde(flatten)]
+    pub data_type: DataType,
+}
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[serde(rename_all = "camelCase")]
+pub struct Parameter {
+    pub param_type: ParamType,
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default)]
+    pub allow_multiple: bool,
+    #[serde(flatten)]
+    pub data_type: DataType,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[serde(rename_all = "camelCase")]
+pub enum ParamType {
+    Path,
+    Query,
+    Body,
+    Header,
+    Form,
+}
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseMessage {
+    pub code: u16,
+    pub message: String,
+    pub response_model: Option<String>,
+}
+
+/// https://github.com/OAI/OpenAPI-Specification/blob/main/versions/1.2.md#527-model-object
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[serde(rename_all = "camelCase")]
+pub struct Model {
+    pub id: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub required: Vec<String>,
+    #[serde(default)]
+    pub properties: BTreeMap<String, Property>,
+    #[serde(default)]
+    pub sub_types: Vec<String>,
+    pub discriminator: Option<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Property {
+    pub description: Option<String>,
+    #[serde(flatten)]
+    pub data_type: DataType,
+}
+
+/// https://github.com/OAI/OpenAPI-Specification/blob/main/versions/1.2.md#433-data-type-fields
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[serde(rename_all = "camelCase")]
+pub struct DataType {
+    #[serde(rename = "type")]
+    pub data_type: Option<String>,
+    #[serde(rename = "$ref")]
+    pub reference: Option<String>,
+    pub format: Option<String>,
+    pub default_value: Option<Value>,
+    #[serde(rename = "enum")]
+    pub enum_values: Option<Vec<String>>,
+    pub minimum: Option<Value>,
+    pub maximum: Option<Value>,
+    pub items: Option<Box<DataType>>,
+    pub unique_items: Option<bool>,
+}
+
+impl DataType {
+    /// Swagger 2.0 schema of the data type, `None` for `void`.
+    fn schema(&self) -> Option<Value> {
+        if let Some(reference) = &self.reference {
+            return Some(definition_ref(reference));
+        }
+
+        let mut schema = match self.data_type.as_deref()? {
+            "void" => return None,
+            "array" => {
+                let items = self
+                    .items
+                    .as_deref()
+                    .and_then(DataType::schema)
+                    .unwrap_or_else(|| json!({"type": "string"}));
+                json!({"type": "array", "items": items})
+            }
+            "File" => json!({"type": "string", "format": "binary"}),
+            // `date` and `date-time` are valid as types in 1.2
+            "date" | "date-time" => json!({"type": "string", "format": self.data_type}),
+            primitive @ ("integer" | "number" | "string" | "boolean") => {
+                json!({"type": primitive})
+            }
+            model => return Some(definition_ref(model)),
+        };
+        self.insert_fields(&mut schema);
+        Some(schema)
+    }
+
+    /// Swagger 2.0 fields of a non-body parameter with the data type.
+    fn parameter_fields(&self) -> Value {
+        let mut fields = match self.data_type.as_deref() {
+            Some("array") => {
+                let items = self
+                    .items
+                    .as_deref()
+                    .map(DataType::parameter_fields)
+                    .unwrap_or_else(|| json!({"type": "string"}));
+                json!({"type": "array", "items": items})
+            }
+            Some("File") => json!({"type": "string", "format": "binary"}),
+            Some(date @ ("date" | "date-time")) => json!({"type": "string", "format": date}),
+            Some(primitive @ ("integer" | "number" | "boolean")) => json!({"type": primitive}),
+            _ => json!({"type": "string"}),
+        };
+        self.insert_fields(&mut fields);
+        fields
+    }
+
+    fn insert_fields(&self, schema: &mut Value) {
+        let Value::Object(schema) = schema else {
+            return;
+        };
+        if let Some(format) = &self.format {
+            schema.insert("format".to_string(), json!(format));
+        }
+        if let Some(default) = &self.default_value {
+            schema.insert("default".to_string(), typed_value(default));
+        }
+        if let Some(enum_values) = &self.enum_values {
+            schema.insert("enum".to_string(), json!(enum_values));
+        }
+        for (key, bound) in [("minimum", &self.minimum), ("maximum", &self.maximum)] {
+            if let Some(bound) = bound.as_ref().and_then(number) {
+                schema.insert(key.to_string(), json!(bound));
+            }
+        }
+        if let Some(unique_items) = self.unique_items {
+            schema.insert("uniqueItems".to_string(), json!(unique_items));
+        }
+    }
+}
+
+fn definition_ref(model: &str) -> Value {
+    json!({"$ref": format!("#/definitions/{model}")})
+}
+
+/// Values of 1.2 documents are mostly written as strings, e.g. `"defaultValue": "10"`.
+fn typed_value(value: &Value) -> Value {
+    match value {
+        Value::String(s) => serde_json::from_str(s)
+            .ok()
+            .filter(|v: &Value| !v.is_object() && !v.is_array())
+            .unwrap_or_else(|| value.clone()),
+        _ => value.clone(),
+    }
+}
+
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::String(s) => s.parse().ok(),
+        _ => value.as_f64(),
+    }
+}
+
+/// Upgrades a Swagger 1.2 resource listing and the API declarations of its resources to a
+/// single Swagger 2.0 document.
+///
+/// OAuth2 authorizations without grant types are dropped with a warning, run within
+/// [`diagnostics::collect`] to receive them.
+pub fn upgrade(
+    listing: ResourceListing,
+    declarations: Vec<ApiDeclaration>,
+) -> Result<Swagger, serde_json::Error> {
+    let mut swagger = Map::new();
+    swagger.insert("swagger".to_string(), json!("2.0"));
+
+    let api_version = listing
+        .api_version
+        .clone()
+        .or_else(|| declarations.iter().find_map(|d| d.api_version.clone()))
+        .unwrap_or_else(|| "1.0".to_string());
+    swagger.insert("info".to_string(), upgrade_info(listing.info, api_version));
+
+    if let Some(declaration) = declarations.first() {
+        if let Some((scheme, host, base_path)) = split_base_path(&declaration.base_path) {
+            swagger.insert("schemes".to_string(), json!([scheme]));
+            swagger.insert("host".to_string(), json!(host));
+            if !base_path.is_empty() {
+                swagger.insert("basePath".to_string(), json!(base_path));
+            }
+        }
+    }
+
+    let security_definitions: Map<String, Value> = diagnostics::scope("authorizations", || {
+        listing
+            .authorizations
+            .into_iter()
+            .filter_map(|(name, authorization)| {
+                let scheme = diagnostics::scope(&name, || upgrade_authorization(authorization))?;
+                Some((name, scheme))
+            })
+            .collect()
+    });
+
+    let tags: Vec<Value> = listing
+        .apis
+        .iter()
+        .map(|resource| {
+            let mut tag = json!({"name": resource_name(&resource.path)});
+            if let Some(description) = &resource.description {
+                tag["description"] = json!(description);
+            }
+            tag
+        })
+        .collect();
+    if !tags.is_empty() {
+        swagger.insert("tags".to_string(), Value::Array(tags));
+    }
+
+    let mut paths = Map::new();
+    let mut definitions = Map::new();
+    for declaration in declarations {
+        let tag = declaration.resource_path.as_deref().map(resource_name);
+        let media_types = (&declaration.consumes, &declaration.produces);
+        for api in declaration.apis {
+            let path_item = paths
+                .entry(api.path)
+                .or_insert_with(|| Value::Object(Map::new()));
+            for operation in api.operations {
+                let method = operation.method.to_ascii_lowercase();
+                path_item[method] = upgrade_operation(
+                    operation,
+                    tag.as_deref(),
+                    media_types,
+                    &security_definitions,
+                );
+            }
+        }
+        upgrade_models(declaration.models, &mut definitions);
+    }
+
+    swagger.insert("paths".to_string(), Value::Object(paths));
+    if !definitions.is_empty() {
+        swagger.insert("definitions".to_string(), Value::Object(definitions));
+    }
+    if !security_definitions.is_empty() {
+        swagger.insert(
+            "securityDefinitions".to_string(),
+            Value::Object(security_definitions),
+        );
+    }
+
+    serde_json::from_value(Value::Object(swagger))
+}
+
+fn upgrade_info(info: Option<Info>, version: String) -> Value {
+    let Some(info) = info else {
+        return json!({"title": "", "version": version});
+    };
+
+    let mut openapi_info = json!({"title": info.title, "version": version});
+    if let Some(description) = info.description {
+        openapi_info["description"] = json!(description);
+    }
+    if let Some(terms_of_service) = info.terms_of_service_url {
+        openapi_info["termsOfService"] = json!(terms_of_service);
+    }
+    if let Some(email) = info.contact {
+        openapi_info["contact"] = json!({"email": email});
+    }
+    if let Some(license) = info.license {
+        openapi_info["license"] = json!({"name": license, "url": info.license_url});
+    }
+    openapi_info
+}
+
+/// Splits an absolute 1.2 `basePath` like `http://example.com/api` into scheme, host and path.
+fn split_base_path(base_path: &str) -> Option<(&str, &str, &str)> {
+    let (scheme, rest) = base_path.split_once("://")?;
+    let (host, path) = rest.find('/').map_or((rest, ""), |i| rest.split_at(i));
+    Some((scheme, host, path.trim_end_matches('/')))
+}
+
+/// Tag name of a resource, `/pet` or `/pet.{format}` become `pet`.
+fn resource_name(path: &str) -> String {
+    let name = path.trim_start_matches('/');
+    name.strip_suffix(".{format}").unwrap_or(name).to_string()
+}
+
+fn upgrade_authorization(authorization: Authorization) -> Option<Value> {
+    let (scopes, grant_types) = match authorization {
+        Authorization::BasicAuth => return Some(json!({"type": "basic"})),
+        Authorization::ApiKey { pass_as, keyname } => {
+            return Some(json!({"type": "apiKey", "name": keyname, "in": pass_as}))
+        }
+        Authorization::Oauth2 {
+            scopes,
+            grant_types,
+        } => (scopes, grant_types),
+    };
+
+    let mut scheme = match (grant_types.implicit, grant_types.authorization_code) {
+        (Some(implicit), _) => json!({
+            "type": "oauth2",
+            "flow": "implicit",
+            "authorizationUrl": implicit.login_endpoint.url,
+        }),
+        (None, Some(code)) => json!({
+            "type": "oauth2",
+            "flow": "accessCode",
+            "authorizationUrl": code.token_request_endpoint.url,
+            "tokenUrl": code.token_endpoint.url,
+        }),
+        (None, None) => {
+            diagnostics::warn("dropped oauth2 authorization without grant types");
+            return None;
+        }
+    };
+    scheme["scopes"] = scopes
+        .into_iter()
+        .map(|s| (s.scope, json!(s.description.unwrap_or_default())))
+        .collect::<Map<_, _>>()
+        .into();
+    Some(scheme)
+}
+
+fn upgrade_operation(
+    operation: Operation,
+    tag: Option<&str>,
+    (consumes, produces): (&Option<Vec<String>>, &Option<Vec<String>>),
+    security_definitions: &Map<String, Value>,
+) -> Value {
+    let mut openapi_operation = Map::new();
+    if let Some(tag) = tag {
+        openapi_operation.insert("tags".to_string(), json!([tag]));
+    }
+    for (key, value) in [
+        ("summary", operation.summary),
+        ("description", operation.notes),
+        ("operationId", operation.nickname),
+    ] {
+        if let Some(value) = value {
+            openapi_operation.insert(key.to_string(), json!(value));
+        }
+    }
+    for (key, value) in [
+        ("consumes", operation.consumes.or_else(|| consumes.clone())),
+        ("produces", operation.produces.or_else(|| produces.clone())),
+    ] {
+        if let Some(value) = value {
+            openapi_operation.insert(key.to_string(), json!(value));
+        }
+    }
+    if operation.deprecated.as_deref() == Some("true") {
+        openapi_operation.insert("deprecated".to_string(), json!(true));
+    }
+
+    if !operation.parameters.is_empty() {
+        let parameters: Vec<Value> = operation
+            .parameters
+            .into_iter()
+            .map(upgrade_parameter)
+            .collect();
+        openapi_operation.insert("parameters".to_string(), Value::Array(parameters));
+    }
+
+    let mut responses = Map::new();
+    let mut success = json!({"description": "successful operation"});
+    if let Some(schema) = operation.data_type.schema() {
+        success["schema"] = schema;
+    }
+    responses.insert("200".to_string(), success);
+    for message in operation.response_messages {
+        let mut response = json!({"description": message.message});
+        if let Some(model) = message.response_model {
+            response["schema"] = definition_ref(&model);
+        }
+        responses.insert(message.code.to_string(), response);
+    }
+    openapi_operation.insert("responses".to_string(), Value::Object(responses));
+
+    if let Some(authorizations) = operation.authorizations {
+        let security: Vec<Value> = authorizations
+            .into_iter()
+            .filter(|(name, _)| security_definitions.contains_key(name))
+            .map(|(name, scopes)| {
+                let scopes: Vec<String> = scopes.into_iter().map(|s| s.scope).collect();
+                Value::Object(Map::from_iter([(name, json!(scopes))]))
+            })
+            .collect();
+        openapi_operation.insert("security".to_string(), Value::Array(security));
+    }
+
+    Value::Object(openapi_operation)
+}
+
+fn upgrade_parameter(parameter: Parameter) -> Value {
+    let param_in = match parameter.param_type {
+        ParamType::Path => "path",
+        ParamType::Query => "query",
+        ParamType::Body => "body",
+        ParamType::Header => "header",
+        ParamType::Form => "formData",
+    };
+
+    let mut openapi_parameter = if parameter.param_type == ParamType::Body {
+        let schema = parameter
+            .data_type
+            .schema()
+            .unwrap_or_else(|| json!({"type": "object"}));
+        json!({"schema": schema})
+    } else if parameter.allow_multiple {
+        json!({
+            "type": "array",
+            "items": parameter.data_type.parameter_fields(),
+            "collectionFormat": "csv",
+        })
+    } else {
+        parameter.data_type.parameter_fields()
+    };
+
+    openapi_parameter["name"] = json!(parameter.name);
+    openapi_parameter["in"] = json!(param_in);
+    openapi_parameter["required"] =
+        json!(parameter.required || parameter.param_type == ParamType::Path);
+    if let Some(description) = parameter.description {
+        openapi_parameter["description"] = json!(description);
+    }
+    openapi_parameter
+}
+
+/// Adds the models of a declaration to `definitions`. Subtypes have no equivalent in the
+/// Swagger 2.0 model of this crate, so they inherit the properties of their parent.
+fn upgrade_models(models: BTreeMap<String, Model>, definitions: &mut Map<String, Value>) {
+    let mut parents: BTreeMap<&str, &Model> = BTreeMap::new();
+    for model in models.values() {
+        for sub_type in &model.sub_types {
+            parents.insert(sub_type, model);
+        }
+    }
+
+    for (name, model) in &models {
+        let mut required = model.required.clone();
+        let mut properties = Map::new();
+        let mut ancestors = Vec::new();
+        let mut parent = parents.get(name.as_str());
+        while let Some(model) = parent.filter(|m| !ancestors.contains(&m.id)) {
+            ancestors.push(model.id.clone());
+            required.extend(model.required.iter().cloned());
+            insert_properties(&mut properties, &model.properties);
+            parent = parents.get(model.id.as_str());
+        }
+        insert_properties(&mut properties, &model.properties);
+
+        let mut schema = json!({"type": "object"});
+        if let Some(description) = &model.description {
+            schema["description"] = json!(description);
+        }
+        if !required.is_empty() {
+            required.sort();
+            required.dedup();
+            schema["required"] = json!(required);
+        }
+        if !properties.is_empty() {
+            schema["properties"] = Value::Object(properties);
+        }
+        definitions.insert(name.clone(), schema);
+    }
+}
+
+fn insert_properties(properties: &mut Map<String, Value>, model: &BTreeMap<String, Property>) {
+    for (name, property) in model {
+        let mut schema = property
+            .data_type
+            .schema()
+            .unwrap_or_else(|| json!({"type": "object"}));
+        if let (Some(description), false) = (&property.description, schema.get("$ref").is_some()) {
+            schema["description"] = json!(description);
+        }
+        properties.insert(name.clone(), schema);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn upgrade_petstore() {
+        let listing: ResourceListing = serde_json::from_value(json!({
+            "swaggerVersion": "1.2",
+            "apiVersion": "1.0.0",
+            "apis": [{"path": "/pet", "description": "Operations about pets"}],
+            "info": {"title": "Petstore", "contact": "apiteam@example.com"}
+        }))
+        .unwrap();
+        let declaration: ApiDeclaration = serde_json::from_value(json!({
+            "swaggerVersion": "1.2",
+            "basePath": "https://petstore.example.com/api",
+            "resourcePath": "/pet",
+            "apis": [{
+                "path": "/pet/{petId}",
+                "operations": [{
+                    "method": "GET",
+                    "nickname": "getPetById",
+                    "type": "Pet",
+                    "parameters": [
+                        {"paramType": "path", "name": "petId", "type": "integer", "format": "int64", "minimum": "1"}
+                    ],
+                    "responseMessages": [{"code": 404, "message": "Pet not found"}]
+                }]
+            }],
+            "models": {
+                "Pet": {
+                    "id": "Pet",
+                    "required": ["name"],
+                    "properties": {
+                        "name": {"type": "string"},
+                        "tags": {"type": "array", "items": {"$ref": "Tag"}}
+                    }
+                }
+            }
+        }))
+        .unwrap();
+
+        let swagger = upgrade(listing, vec![declaration]).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&swagger).unwrap(),
+            json!({
+                "swagger": "2.0",
+                "info": {
+                    "title": "Petstore",
+                    "version": "1.0.0",
+                    "contact": {"email": "apiteam@example.com"}
+                },
+                "host": "petstore.example.com",
+                "basePath": "/api",
+                "schemes": ["https"],
+                "tags": [{"name": "pet", "description": "Operations about pets"}],
+                "paths": {
+                    "/pet/{petId}": {
+                        "get": {
+                            "tags": ["pet"],
+                            "operationId": "getPetById",
+                            "parameters": [{
+                                "name": "petId",
+                                "in": "path",
+                                "required": true,
+                                "type": "integer",
+                                "format": "int64",
+                                "minimum": 1.0
+                            }],
+                            "responses": {
+                                "200": {
+                                    "description": "successful operation",
+                                    "schema": {"$ref": "#/definitions/Pet"}
+                                },
+                                "404": {"description": "Pet not found"}
+                            }
+                        }
+                    }
+                },
+                "definitions": {
+                    "Pet": {
+                        "type": "object",
+                        "required": ["name"],
+                        "properties": {
+                            "name": {"type": "string"},
+                            "tags": {"type": "array", "items": {"$ref": "#/definitions/Tag"}}
+                        }
+                    }
+                }
+            })
+        );
+    }
+}
@@ -0,0 +1,423 @@
+//! Conversion of documents straight from a reader.
+//!
+//! `paths` and `definitions` usually make up almost all of a document. They are converted
+//! entry by entry while deserializing, so only a single Swagger path item or definition is
+//! held in memory at a time next to the converted output.
+//!
+//! The checks over the whole document run on what is kept of them: the local `$ref`s, the
+//! `allOf`s and the discriminators of definitions and the path templates.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt, io,
+    sync::Arc,
+};
+
+use serde::de::{self, DeserializeSeed, MapAccess, Visitor};
+use serde_json::json;
+use utoipa::openapi::{self, OpenApi};
+
+use crate::{
+    diagnostics, discriminator, media_type, options, prune,
+    spec::{self, rewrite_operation_ids, Extensions, PathItem, RefOr, Schema, Swagger},
+    trace, Conversion, ConvertOptions, Error,
+};
+
+/// Reads a Swagger 2.0 JSON document from `reader` and converts it while deserializing.
+///
+/// The options which rewrite the whole document before it is converted fail with
+/// [`Error::Unsupported`]: [`ConvertOptions::partial`], `grpc_gateway`, `go_swagger`,
+/// `dotnet`, `exclude_deprecated`, `normalize_formats`, `validate_defaults`,
+/// `validate_examples` and `anonymize`.
+pub fn convert_reader<R: io::Read>(
+    reader: R,
+    options: &ConvertOptions,
+) -> Result<Conversion, Error> {
+    if let Some(option) = unsupported(options) {
+        return Err(Error::Unsupported(option));
+    }
+    let (converted, diagnostics) = options::scope(Arc::new(options.clone()), || {
+        diagnostics::collect(|| {
+            let document = trace::phase("parse", || {
+                let mut deserializer = serde_json::Deserializer::from_reader(reader);
+                let document = de::Deserializer::deserialize_map(
+                    &mut deserializer,
+                    DocumentVisitor::default(),
+                )?;
+                deserializer.end().map(|()| document)
+            })?;
+            document
+                .into_openapi()
+                .map(|(openapi, additions)| (crate::finish(openapi, options), additions))
+        })
+    });
+    let (openapi, additions) = converted.map_err(Error::Parse)?;
+
+    Ok(Conversion {
+        openapi,
+        diagnostics,
+        output: options.output.clone(),
+        source_order: Default::default(),
+        provenance: options
+            .output
+            .stamp
+            .then(|| crate::output::Provenance::new(None)),
+        additions,
+    })
+}
+
+/// The first option set in `options` which is not supported by [`convert_reader`].
+fn unsupported(options: &ConvertOptions) -> Option<&'static str> {
+    [
+        ("partial", options.partial),
+        ("grpc_gateway", options.grpc_gateway),
+        ("go_swagger", options.go_swagger),
+        ("dotnet", options.dotnet),
+        ("exclude_deprecated", options.exclude_deprecated),
+        ("normalize_formats", options.normalize_formats),
+        ("validate_defaults", options.validate_defaults),
+        ("validate_examples", options.validate_examples),
+        ("anonymize", options.anonymize),
+    ]
+    .into_iter()
+    .find(|(_, set)| *set)
+    .map(|(option, _)| option)
+}
+
+/// What is kept of the streamed paths and definitions for the checks over the whole
+/// document.
+#[derive(Default)]
+struct Outline {
+    /// The local `$ref`s with the pointer of their object, see [`spec::local_refs`].
+    refs: Vec<(String, String)>,
+    /// The `allOf`s of the definitions, see [`spec::all_of_outline`].
+    all_of: BTreeMap<String, RefOr<Schema>>,
+    /// The discriminators of the definitions, see [`discriminator::outline`].
+    discriminators: serde_json::Map<String, serde_json::Value>,
+}
+
+struct Document {
+    rest: serde_json::Map<String, serde_json::Value>,
+    paths: openapi::Paths,
+    definitions: Option<BTreeMap<String, openapi::RefOr<openapi::Schema>>>,
+    outline: Outline,
+}
+
+impl Document {
+    fn into_openapi(mut self) -> Result<(OpenApi, crate::spec::Additions), serde_json::Error> {
+        // the paths were already converted, the remaining document only needs to be valid
+        self.rest.insert(
+            "paths".to_string(),
+            serde_json::Value::Object(Default::default()),
+        );
+        let mut rest = serde_json::Value::Object(self.rest);
+        spec::local_refs(&rest, &mut String::new(), &mut self.outline.refs);
+        if rest.get("responses").is_some() {
+            let used: BTreeSet<String> = self
+                .outline
+                .refs
+                .iter()
+                .map(|(_, location)| location.clone())
+                .collect();
+            prune::unused_responses(&mut rest, &used, options::current().prune);
+        }
+        discriminator::verify(&json!({"definitions": self.outline.discriminators}));
+
+        let swagger: Swagger = serde_json::from_value(rest)?;
+        let dropped = spec::dropped_schemes(&swagger.security_definitions);
+        let mut swagger = media_type::check(swagger);
+        let additions = crate::spec::Additions::take(&mut swagger);
+
+        let mut openapi: OpenApi = swagger.into();
+        openapi.paths = self.paths;
+        spec::drop_path_requirements(&mut openapi.paths, &dropped);
+        if let (Some(components), Some(definitions)) =
+            (openapi.components.as_mut(), self.definitions)
+        {
+            components.schemas =
+                diagnostics::scope("definitions", || crate::spec::namespaced(definitions));
+        }
+        crate::spec::count_components(&openapi);
+        spec::dangling_refs(&openapi, self.outline.refs);
+        Ok((openapi, additions))
+    }
+}
+
+#[derive(Default)]
+struct DocumentVisitor {
+    outline: Outline,
+}
+
+impl<'de> Visitor<'de> for DocumentVisitor {
+    type Value = Document;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a swagger 2.0 document")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(mut self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut rest = serde_json::Map::new();
+        let mut paths = None;
+        let mut definitions = None;
+
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "paths" => {
+                    let seed = PathsSeed(&mut self.outline);
+                    paths = Some(diagnostics::scope("paths", || {
+                        trace::phase("paths", || map.next_value_seed(seed))
+                    })?);
+                }
+                "definitions" => {
+                    let seed = DefinitionsSeed(&mut self.outline);
+                    definitions = Some(diagnostics::scope("definitions", || {
+                        trace::phase("definitions", || map.next_value_seed(seed))
+                    })?);
+                }
+                _ => {
+                    let value = map.next_value()?;
+                    rest.insert(key, value);
+                }
+            }
+        }
+
+        Ok(Document {
+            rest,
+            paths: paths.ok_or_else(|| de::Error::missing_field("paths"))?,
+            definitions,
+            outline: self.outline,
+        })
+    }
+}
+
+struct PathsSeed<'a>(&'a mut Outline);
+
+impl<'de> DeserializeSeed<'de> for PathsSeed<'_> {
+    type Value = openapi::Paths;
+
+    fn deserialize<D: de::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for PathsSeed<'_> {
+    type Value = openapi::Paths;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a swagger 2.0 paths object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut paths = BTreeMap::new();
+        let mut extensions = BTreeMap::new();
+
+        while let Some(key) = map.next_key::<String>()? {
+            if key.starts_with("x-") {
+                let value = map.next_value()?;
+                extensions.insert(key, value);
+                continue;
+            }
+            let path_item = diagnostics::scope(&key, || {
+                let value = map.next_value::<serde_json::Value>()?;
+                let mut pointer = format!("/paths/{}", diagnostics::escape(&key));
+                spec::local_refs(&value, &mut pointer, &mut self.0.refs);
+                let mut path_item: PathItem =
+                    serde_json::from_value(value).map_err(<A::Error as de::Error>::custom)?;
+                media_type::check_path_item(&key, &mut path_item);
+                rewrite_operation_ids(&key, &mut path_item);
+                Ok::<_, A::Error>(openapi::PathItem::from(path_item))
+            })?;
+            paths.insert(key, path_item);
+        }
+        spec::ambiguous_paths(paths.keys().map(String::as_str));
+
+        let mut openapi_paths = openapi::PathsBuilder::new()
+            .extensions(Extensions(extensions).into_openapi_extensions())
+            .build();
+        openapi_paths.paths = paths;
+        Ok(openapi_paths)
+    }
+}
+
+struct DefinitionsSeed<'a>(&'a mut Outline);
+
+impl<'de> DeserializeSeed<'de> for DefinitionsSeed<'_> {
+    type Value = BTreeMap<String, openapi::RefOr<openapi::Schema>>;
+
+    fn deserialize<D: de::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for DefinitionsSeed<'_> {
+    type Value = BTreeMap<String, openapi::RefOr<openapi::Schema>>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a swagger 2.0 definitions object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut definitions = BTreeMap::new();
+
+        while let Some(key) = map.next_key::<String>()? {
+            let schema = diagnostics::scope(&key, || {
+                let value = map.next_value::<serde_json::Value>()?;
+                let mut pointer = format!("/definitions/{}", diagnostics::escape(&key));
+                spec::local_refs(&value, &mut pointer, &mut self.0.refs);
+                let outline = discriminator::outline(&value);
+                self.0.discriminators.insert(key.clone(), outline);
+                let schema: RefOr<Schema> =
+                    serde_json::from_value(value).map_err(<A::Error as de::Error>::custom)?;
+                if let Some(all_of) = spec::all_of_outline(&schema) {
+                    self.0.all_of.insert(key.clone(), all_of);
+                }
+                Ok::<_, A::Error>(schema.into_openapi_ref())
+            })?;
+            definitions.insert(key, schema);
+        }
+        spec::report_circular_all_of(&self.0.all_of);
+
+        Ok(definitions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn convert_reader_matches_convert() {
+        let swagger = json!({
+            "swagger": "2.0",
+            "info": {"title": "test", "version": "1.0"},
+            "paths": {
+                "/users": {
+                    "get": {
+                        "parameters": [{"name": "ids", "in": "query", "type": "array", "items": {"type": "string"}, "collectionFormat": "csv"}],
+                        "responses": {"200": {"description": "ok", "schema": {"$ref": "#/definitions/User"}}}
+                    }
+                }
+            },
+            "definitions": {
+                "User": {"type": "object", "properties": {"name": {"type": "string"}}}
+            }
+        });
+
+        let streamed =
+            convert_reader(swagger.to_string().as_bytes(), &ConvertOptions::default()).unwrap();
+        let converted = crate::convert(serde_json::from_value(swagger).unwrap());
+
+        assert_eq!(
+            serde_json::to_value(&streamed.openapi).unwrap(),
+            serde_json::to_value(&converted.openapi).unwrap()
+        );
+        assert_eq!(streamed.diagnostics, converted.diagnostics);
+    }
+
+    #[test]
+    fn convert_reader_finishes() {
+        let swagger = json!({
+            "swagger": "2.0",
+            "info": {"title": "test", "version": "1.0"},
+            "securityDefinitions": {
+                "key": {"type": "apiKey", "name": "key", "in": "header"},
+                "basic": {"type": "basic"}
+            },
+            "paths": {
+                "/users": {"get": {"responses": {"200": {"description": "ok"}}}}
+            }
+        });
+        let options = ConvertOptions {
+            required_security: vec!["key".parse().unwrap()],
+            prune: crate::prune::PruneOptions {
+                security_schemes: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let streamed = convert_reader(swagger.to_string().as_bytes(), &options).unwrap();
+        let converted = crate::convert_with(serde_json::from_value(swagger).unwrap(), &options);
+
+        let document = streamed.to_value();
+        assert_eq!(document["security"], json!([{"key": []}]));
+        assert_eq!(
+            document["components"]["securitySchemes"],
+            json!({"key": {"type": "apiKey", "name": "key", "in": "header"}})
+        );
+        assert_eq!(document, converted.to_value());
+        assert_eq!(streamed.diagnostics, converted.diagnostics);
+    }
+
+    #[test]
+    fn convert_reader_checks_document() {
+        let ok = json!({"200": {"description": "ok"}});
+        let swagger = json!({
+            "swagger": "2.0",
+            "info": {"title": "test", "version": "1.0"},
+            "produces": ["application/JSON"],
+            "responses": {"NotFound": {"description": "not found"}},
+            "securityDefinitions": {
+                "key": {"type": "apiKey", "name": "key", "in": "header"},
+                "tls": {"type": "mutualTLS"}
+            },
+            "paths": {
+                "/users/{id}": {"get": {
+                    "produces": ["json"],
+                    "security": [{"tls": []}, {"key": []}],
+                    "responses": ok.clone()
+                }},
+                "/users/{userId}": {"get": {"responses": ok}}
+            },
+            "definitions": {
+                "User": {"allOf": [{"$ref": "#/definitions/Admin"}]},
+                "Admin": {"allOf": [{"$ref": "#/definitions/User"}]},
+                "Pet": {"type": "object", "discriminator": "petType"},
+                "Owner": {"$ref": "#/definitions/Person"}
+            }
+        });
+        let sorted = |conversion: Conversion| {
+            let mut diagnostics: Vec<_> = conversion
+                .diagnostics
+                .into_iter()
+                .map(|diagnostic| diagnostic.to_string())
+                .collect();
+            diagnostics.sort();
+            diagnostics
+        };
+
+        let streamed =
+            convert_reader(swagger.to_string().as_bytes(), &ConvertOptions::default()).unwrap();
+        let converted = crate::convert(serde_json::from_value(swagger).unwrap());
+
+        assert_eq!(streamed.to_value(), converted.to_value());
+        let streamed = sorted(streamed);
+        assert!(streamed
+            .iter()
+            .any(|d| d.contains("dangling reference `#/definitions/Person`")));
+        assert!(streamed
+            .iter()
+            .any(|d| d.contains("naming the dropped scheme `tls`")));
+        assert_eq!(streamed, sorted(converted));
+    }
+
+    #[test]
+    fn convert_reader_unsupported_options() {
+        let options = ConvertOptions {
+            anonymize: true,
+            ..Default::default()
+        };
+
+        let result = convert_reader(&b"{}"[..], &options);
+
+        assert!(matches!(result, Err(Error::Unsupported("anonymize"))));
+    }
+}
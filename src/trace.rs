@@ -0,0 +1,25 @@
+//! Spans and counters of the conversion phases, emitted with `tracing` if the feature of the
+//! same name is enabled and compiled away otherwise.
+//!
+//! Every phase runs in an `info` level span named `phase` with the phase as `name` field:
+//! `parse`, `refs`, `paths`, `definitions` and `serialize`. Sizes are reported as `debug`
+//! events with the counted items as `name` field and their number as `count` field.
+
+/// Runs `f` in the span of the conversion phase `name`.
+#[inline]
+pub(crate) fn phase<T>(name: &'static str, f: impl FnOnce() -> T) -> T {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::info_span!("phase", name).entered();
+    #[cfg(not(feature = "tracing"))]
+    let _ = name;
+    f()
+}
+
+/// Reports the number of `name` items of a document, e.g. converted paths.
+#[inline]
+pub(crate) fn count(name: &'static str, count: usize) {
+    #[cfg(feature = "tracing")]
+    tracing::debug!(name, count);
+    #[cfg(not(feature = "tracing"))]
+    let _ = (name, count);
+}
@@ -0,0 +1,107 @@
+//! Conversions into the OpenAPI models of released utoipa versions, for callers depending on
+//! one of them rather than the utoipa revision this crate is built with.
+//!
+//! Enable `utoipa4` or `utoipa5`, or both, for `TryFrom` impls of the `OpenApi` of that
+//! version. The written document, see [`Conversion::to_value`], is loaded into the
+//! [`ir::Document`] and deserialized in the OpenAPI version the model expects: 3.0 for utoipa
+//! 4 and 3.1 for utoipa 5.
+//!
+//! The impls are a JSON round trip: the document is written as JSON value and parsed into the
+//! model, failing with [`Error::Parse`] if the model rejects it. The conversion itself still
+//! builds on the utoipa revision this crate pins, which stays a dependency whichever of the
+//! features are enabled.
+
+use crate::{
+    convert,
+    ir::{self, Version},
+    spec::Swagger,
+    Conversion, Error,
+};
+
+macro_rules! utoipa_version {
+    ($utoipa:ident, $version:expr) => {
+        impl TryFrom<&Conversion> for $utoipa::openapi::OpenApi {
+            type Error = Error;
+
+            fn try_from(conversion: &Conversion) -> Result<Self, Error> {
+                let document = conversion.to_document().to_openapi($version);
+                serde_json::from_value(document).map_err(Error::Parse)
+            }
+        }
+
+        /// Converts like [`convert`], dropping the diagnostics.
+        impl TryFrom<Swagger> for $utoipa::openapi::OpenApi {
+            type Error = Error;
+
+            fn try_from(swagger: Swagger) -> Result<Self, Error> {
+                Self::try_from(&convert(swagger))
+            }
+        }
+
+        /// Writes the document like [`ir::Document::to_openapi`] does.
+        impl TryFrom<&ir::Document> for $utoipa::openapi::OpenApi {
+            type Error = Error;
+
+            fn try_from(document: &ir::Document) -> Result<Self, Error> {
+                serde_json::from_value(document.to_openapi($version)).map_err(Error::Parse)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "utoipa4")]
+utoipa_version!(utoipa4, Version::OpenApi3_0);
+#[cfg(feature = "utoipa5")]
+utoipa_version!(utoipa5, Version::OpenApi3_1);
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn swagger() -> Swagger {
+        serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "paths": {"/pets": {"get": {"responses": {"204": {"description": "none"}}}}},
+            "definitions": {
+                "Pet": {
+                    "type": "object",
+                    "properties": {"name": {"type": "string", "x-nullable": true}}
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[cfg(feature = "utoipa4")]
+    #[test]
+    fn convert_into_utoipa4() {
+        let openapi = utoipa4::openapi::OpenApi::try_from(swagger()).unwrap();
+
+        assert_eq!(openapi.info.title, "Pets");
+        assert!(openapi.paths.paths.contains_key("/pets"));
+        let document = serde_json::to_value(&openapi).unwrap();
+        assert_eq!(document["openapi"], "3.0.3");
+        assert_eq!(
+            document["components"]["schemas"]["Pet"]["properties"]["name"]["nullable"],
+            true
+        );
+    }
+
+    #[cfg(feature = "utoipa5")]
+    #[test]
+    fn convert_into_utoipa5() {
+        let openapi = utoipa5::openapi::OpenApi::try_from(swagger()).unwrap();
+
+        assert_eq!(openapi.info.title, "Pets");
+        assert!(openapi.paths.paths.contains_key("/pets"));
+        let document = serde_json::to_value(&openapi).unwrap();
+        assert_eq!(document["openapi"], "3.1.0");
+        assert_eq!(
+            document["components"]["schemas"]["Pet"]["properties"]["name"]["type"],
+            json!(["string", "null"])
+        );
+    }
+}
@@ -0,0 +1,521 @@
+//! Validation of embedded examples and default values against the schemas they accompany,
+//! see [`ConvertOptions::validate_examples`](crate::ConvertOptions::validate_examples) and
+//! [`ConvertOptions::validate_defaults`](crate::ConvertOptions::validate_defaults).
+//!
+//! The `example` of a schema, the `x-example` of a parameter and the `examples` of a
+//! response are checked against the keywords of Swagger 2.0 schemas, following `$ref`s into
+//! the document, as is the `default` of every schema, parameter and header. `pattern` is
+//! not checked.
+
+use serde_json::{Map, Number, Value};
+
+use crate::{cycle, diagnostics, formats, options, walk};
+
+/// Reports the examples of `document` which do not match their schema.
+pub(crate) fn examples(document: &Value) {
+    walk::objects(document, &mut String::new(), &mut |object, pointer| {
+        for key in ["example", "x-example"] {
+            if let Some(example) = object.get(key) {
+                report(&format!("{pointer}/{key}"), example, object, document);
+            }
+        }
+        if let (Some(Value::Object(examples)), Some(schema)) =
+            (object.get("examples"), object.get("schema"))
+        {
+            for (media_type, example) in examples {
+                let pointer = format!("{pointer}/examples/{}", diagnostics::escape(media_type));
+                report(&pointer, example, schema, document);
+            }
+        }
+    });
+}
+
+/// Reports the defaults of `document` which do not match the schema or parameter declaring
+/// them. When converting leniently, defaults of the wrong type are coerced where that is
+/// obvious, like `"1"` for `type: integer`.
+pub(crate) fn defaults(document: &mut Value) {
+    let root = document.clone();
+    let lenient = options::current().lenient;
+    walk::enter_objects(document, &mut String::new(), &mut |object, pointer| {
+        let Some(default) = object.get("default") else {
+            return;
+        };
+        let schema = Value::Object(object.clone());
+        let fixed = diagnostics::at(&format!("{pointer}/default"), || {
+            checked_default(default, &schema, &root, lenient)
+        });
+        if let Some(fixed) = fixed {
+            object.insert("default".to_string(), fixed);
+        }
+    });
+}
+
+/// Reports the problems of `default`, returning the coerced value replacing it if there is
+/// one and converting leniently.
+fn checked_default(default: &Value, schema: &Value, root: &Value, lenient: bool) -> Option<Value> {
+    let problems = |value: &Value| {
+        let mut problems = Vec::new();
+        check(value, schema, root, &mut String::new(), &mut problems, 0);
+        problems
+    };
+    let found = problems(default);
+    if found.is_empty() {
+        return None;
+    }
+    let coerced = schema["type"]
+        .as_str()
+        .and_then(|type_name| coerce(default, type_name))
+        .filter(|coerced| problems(coerced).is_empty());
+    if lenient && coerced.is_some() {
+        return coerced;
+    }
+    let hint = match coerced {
+        Some(_) => ", convert leniently to coerce it",
+        None => "",
+    };
+    for problem in found {
+        diagnostics::warn(format!(
+            "default does not match its schema: {problem}{hint}"
+        ));
+    }
+    None
+}
+
+/// `value` converted to `type_name` where that is obvious, like `"1"` for `integer`.
+fn coerce(value: &Value, type_name: &str) -> Option<Value> {
+    match (type_name, value) {
+        ("string", Value::Number(_) | Value::Bool(_)) => Some(Value::String(value.to_string())),
+        ("integer" | "number" | "boolean", Value::String(text)) => {
+            serde_json::from_str(text.trim()).ok()
+        }
+        _ => None,
+    }
+}
+
+fn report(pointer: &str, example: &Value, schema: &Value, root: &Value) {
+    diagnostics::at(pointer, || {
+        let mut problems = Vec::new();
+        check(example, schema, root, &mut String::new(), &mut problems, 0);
+        for problem in problems {
+            diagnostics::warn(format!("example does not match its schema: {problem}"));
+        }
+    });
+}
+
+/// Pushes the reasons `value` does not match `schema` onto `problems`, each prefixed with
+/// the pointer of the offending part of `value` unless that is `value` itself.
+pub(crate) fn check(
+    value: &Value,
+    schema: &Value,
+    root: &Value,
+    pointer: &mut String,
+    problems: &mut Vec<String>,
+    depth: usize,
+) {
+    let Value::Object(schema) = schema else {
+        return;
+    };
+    if let Some(Value::String(location)) = schema.get("$ref") {
+        let target = location
+            .strip_prefix('#')
+            .and_then(|location| root.pointer(location));
+        // the value being checked decides how deep recursive schemas are followed, so this
+        // only counts references instead of stopping at cycles like a `RefStack` would
+        let max_depth = cycle::max_depth();
+        match target {
+            Some(target) if depth < max_depth => {
+                check(value, target, root, pointer, problems, depth + 1);
+            }
+            Some(_) => diagnostics::warn(format!(
+                "did not follow `{location}`, {}",
+                cycle::Recursion::TooDeep(max_depth)
+            )),
+            None => {}
+        }
+        return;
+    }
+    if value.is_null() && schema.get("x-nullable") == Some(&Value::Bool(true)) {
+        return;
+    }
+
+    let mut problem = |message: String| problems.push(located(pointer, message));
+    if let Some(Value::String(expected)) = schema.get("type") {
+        if !has_type(value, expected) {
+            problem(format!("is {value}, expected {}", article(expected)));
+            return;
+        }
+    }
+    if let Some(Value::String(format)) = schema.get("format") {
+        if !formats::matches_format(value, format) {
+            problem(format!("{value} does not match format `{format}`"));
+        }
+    }
+    if let Some(Value::Array(values)) = schema.get("enum") {
+        if !values.contains(value) {
+            problem(format!("{value} is not one of the `enum` values"));
+        }
+    }
+
+    match value {
+        Value::Number(number) => bounds(number, schema, &mut problem),
+        Value::String(s) => {
+            let len = s.chars().count() as u64;
+            if schema
+                .get("minLength")
+                .and_then(Value::as_u64)
+                .is_some_and(|min| len < min)
+            {
+                problem(format!("{value} is shorter than `minLength`"));
+            }
+            if schema
+                .get("maxLength")
+                .and_then(Value::as_u64)
+                .is_some_and(|max| len > max)
+            {
+                problem(format!("{value} is longer than `maxLength`"));
+            }
+        }
+        Value::Array(items) => {
+            let len = items.len() as u64;
+            if schema
+                .get("minItems")
+                .and_then(Value::as_u64)
+                .is_some_and(|min| len < min)
+            {
+                problem("has fewer items than `minItems`".to_string());
+            }
+            if schema
+                .get("maxItems")
+                .and_then(Value::as_u64)
+                .is_some_and(|max| len > max)
+            {
+                problem("has more items than `maxItems`".to_string());
+            }
+            let unique = schema.get("uniqueItems") == Some(&Value::Bool(true));
+            if unique && (1..items.len()).any(|i| items[..i].contains(&items[i])) {
+                problem("has duplicate items despite `uniqueItems`".to_string());
+            }
+        }
+        _ => {}
+    }
+
+    if let Some(Value::Array(members)) = schema.get("allOf") {
+        for member in members {
+            check(value, member, root, pointer, problems, depth);
+        }
+    }
+    match value {
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    let len = pointer.len();
+                    pointer.push_str(&format!("/{i}"));
+                    check(item, item_schema, root, pointer, problems, depth);
+                    pointer.truncate(len);
+                }
+            }
+        }
+        Value::Object(members) => properties(members, schema, root, pointer, problems, depth),
+        _ => {}
+    }
+}
+
+fn properties(
+    members: &Map<String, Value>,
+    schema: &Map<String, Value>,
+    root: &Value,
+    pointer: &mut String,
+    problems: &mut Vec<String>,
+    depth: usize,
+) {
+    if let Some(Value::Array(required)) = schema.get("required") {
+        for name in required.iter().filter_map(Value::as_str) {
+            if !members.contains_key(name) {
+                problems.push(located(
+                    pointer,
+                    format!("misses required property `{name}`"),
+                ));
+            }
+        }
+    }
+
+    let declared = schema.get("properties").and_then(Value::as_object);
+    for (name, member) in members {
+        let property = declared.and_then(|declared| declared.get(name));
+        let additional = schema.get("additionalProperties");
+        let member_schema = match (property, additional) {
+            (Some(property), _) => property,
+            (None, Some(Value::Bool(false))) => {
+                let message = format!("has undeclared property `{name}`");
+                problems.push(located(pointer, message));
+                continue;
+            }
+            (None, Some(additional)) => additional,
+            (None, None) => continue,
+        };
+        let len = pointer.len();
+        pointer.push('/');
+        pointer.push_str(&diagnostics::escape(name));
+        check(member, member_schema, root, pointer, problems, depth);
+        pointer.truncate(len);
+    }
+}
+
+fn located(pointer: &str, message: String) -> String {
+    if pointer.is_empty() {
+        message
+    } else {
+        format!("`{pointer}` {message}")
+    }
+}
+
+fn bounds(number: &Number, schema: &Map<String, Value>, problem: &mut impl FnMut(String)) {
+    let Some(n) = number.as_f64() else {
+        return;
+    };
+    let exclusive = |key: &str| schema.get(key) == Some(&Value::Bool(true));
+    if let Some(min) = schema.get("minimum").and_then(Value::as_f64) {
+        if n < min || (n == min && exclusive("exclusiveMinimum")) {
+            problem(format!("{number} is below the `minimum` {min}"));
+        }
+    }
+    if let Some(max) = schema.get("maximum").and_then(Value::as_f64) {
+        if n > max || (n == max && exclusive("exclusiveMaximum")) {
+            problem(format!("{number} is above the `maximum` {max}"));
+        }
+    }
+    if let Some(factor) = schema.get("multipleOf").and_then(Value::as_f64) {
+        if factor > 0.0 && (n / factor).fract() != 0.0 {
+            problem(format!("{number} is no multiple of {factor}"));
+        }
+    }
+}
+
+fn has_type(value: &Value, expected: &str) -> bool {
+    match (expected, value) {
+        ("string", Value::String(_))
+        | ("boolean", Value::Bool(_))
+        | ("array", Value::Array(_))
+        | ("object", Value::Object(_))
+        | ("number", Value::Number(_)) => true,
+        ("integer", Value::Number(n)) => n.is_i64() || n.is_u64(),
+        // file uploads have no example value to check
+        ("file", _) => true,
+        ("string" | "boolean" | "array" | "object" | "number" | "integer", _) => false,
+        _ => true,
+    }
+}
+
+fn article(type_name: &str) -> String {
+    match type_name {
+        "array" | "object" | "integer" => format!("an {type_name}"),
+        _ => format!("a {type_name}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::ConvertOptions;
+
+    #[test]
+    fn validate_examples() {
+        let swagger = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "parameters": [
+                            {"name": "limit", "in": "query", "type": "integer", "maximum": 100,
+                             "x-example": 500}
+                        ],
+                        "responses": {
+                            "200": {
+                                "description": "The pets",
+                                "schema": {"type": "array", "items": {"$ref": "#/definitions/Pet"}},
+                                "examples": {
+                                    "application/json": [{"name": "Rex", "age": "three"}]
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "definitions": {
+                "Pet": {
+                    "type": "object",
+                    "required": ["name"],
+                    "properties": {
+                        "name": {"type": "string"},
+                        "age": {"type": "integer", "minimum": 0}
+                    },
+                    "example": {"age": 3}
+                }
+            }
+        }))
+        .unwrap();
+        let options = ConvertOptions {
+            validate_examples: true,
+            ..Default::default()
+        };
+
+        let conversion = crate::convert_with(swagger, &options);
+
+        assert_eq!(
+            conversion
+                .diagnostics
+                .iter()
+                .map(|d| (d.pointer.as_str(), d.message.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "/definitions/Pet/example",
+                    "example does not match its schema: misses required property `name`"
+                ),
+                (
+                    "/paths/~1pets/get/parameters/0/x-example",
+                    "example does not match its schema: 500 is above the `maximum` 100"
+                ),
+                (
+                    "/paths/~1pets/get/responses/200/examples/application~1json",
+                    "example does not match its schema: `/0/age` is \"three\", expected an integer"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_examples_of_named_members() {
+        let swagger = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "paths": {},
+            "definitions": {
+                "example": {"type": "integer", "example": "one"},
+                "Pet": {
+                    "type": "object",
+                    "properties": {
+                        "examples": {"type": "array", "items": {"type": "string"}, "example": [1]}
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        let options = ConvertOptions {
+            validate_examples: true,
+            ..Default::default()
+        };
+
+        let conversion = crate::convert_with(swagger, &options);
+
+        assert_eq!(
+            conversion
+                .diagnostics
+                .iter()
+                .map(|d| d.pointer.as_str())
+                .collect::<Vec<_>>(),
+            [
+                "/definitions/Pet/properties/examples/example",
+                "/definitions/example/example"
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_defaults() {
+        let swagger = json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "parameters": [
+                            {"name": "limit", "in": "query", "type": "integer", "default": "20"}
+                        ],
+                        "responses": {"204": {"description": "none"}}
+                    }
+                }
+            },
+            "definitions": {
+                "Pet": {
+                    "type": "object",
+                    "properties": {
+                        "kind": {"type": "string", "enum": ["cat", "dog"], "default": "bird"}
+                    }
+                }
+            }
+        });
+        let convert = |lenient| {
+            let options = ConvertOptions {
+                validate_defaults: true,
+                lenient,
+                ..Default::default()
+            };
+            let conversion =
+                crate::convert_with(serde_json::from_value(swagger.clone()).unwrap(), &options);
+            let messages = conversion
+                .diagnostics
+                .iter()
+                .map(|d| format!("{}: {}", d.pointer, d.message))
+                .collect::<Vec<_>>();
+            (conversion.to_value(), messages)
+        };
+
+        let (_, strict) = convert(false);
+        assert_eq!(
+            strict,
+            [
+                "/definitions/Pet/properties/kind/default: default does not match its schema: \
+                 \"bird\" is not one of the `enum` values",
+                "/paths/~1pets/get/parameters/0/default: default does not match its schema: is \
+                 \"20\", expected an integer, convert leniently to coerce it",
+            ]
+        );
+
+        let (lenient, reported) = convert(true);
+        assert_eq!(
+            lenient["paths"]["/pets"]["get"]["parameters"][0]["schema"]["default"],
+            20
+        );
+        assert_eq!(reported.len(), 1);
+    }
+
+    #[test]
+    fn validate_defaults_of_named_members() {
+        let swagger = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "paths": {},
+            "definitions": {
+                "Pet": {
+                    "type": "object",
+                    "properties": {
+                        "default": {"type": "integer", "default": "x"},
+                        "enum": {"type": "string", "enum": ["cat"], "default": "cat"}
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        let options = ConvertOptions {
+            validate_defaults: true,
+            ..Default::default()
+        };
+
+        let conversion = crate::convert_with(swagger, &options);
+
+        assert_eq!(
+            conversion
+                .diagnostics
+                .iter()
+                .map(|d| format!("{}: {}", d.pointer, d.message))
+                .collect::<Vec<_>>(),
+            [
+                "/definitions/Pet/properties/default/default: default does not match its schema: \
+                 is \"x\", expected an integer"
+            ]
+        );
+    }
+}
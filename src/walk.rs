@@ -0,0 +1,211 @@
+//! Walking the objects of serialized documents.
+//!
+//! Besides the objects describing the API, documents hold values of the API, like examples,
+//! defaults and most extensions, which may look like schemas or contain `$ref`s. Walks skip
+//! them. The members of maps keyed by names the document chooses, like `properties` or
+//! `paths`, are walked whatever their names, so a property named `example` is a schema.
+
+use serde_json::{Map, Value};
+
+use crate::diagnostics;
+
+/// Members of objects whose members are named by the document rather than keywords.
+pub(crate) const NAMED: [&str; 23] = [
+    "callbacks",
+    "content",
+    "definitions",
+    "encoding",
+    "headers",
+    "links",
+    "mapping",
+    "parameters",
+    "paths",
+    "patternProperties",
+    "properties",
+    "requestBodies",
+    "responses",
+    "schemas",
+    "scopes",
+    "securityDefinitions",
+    "securitySchemes",
+    "variables",
+    "webhooks",
+    // extensions the conversion reads or writes like the keywords
+    "x-callbacks",
+    "x-links",
+    "x-patternProperties",
+    "x-webhooks",
+];
+
+/// [`NAMED`] maps which hold extensions next to their named members.
+const EXTENSIBLE: [&str; 4] = ["callbacks", "paths", "responses", "x-callbacks"];
+
+/// Extensions holding schemas, which the conversion turns into the keywords.
+const SCHEMA_EXTENSIONS: [&str; 3] = ["x-anyOf", "x-not", "x-oneOf"];
+
+/// Keywords holding values of the API.
+const VALUES: [&str; 4] = ["default", "enum", "example", "examples"];
+
+/// Whether the member `key` of an object holds values of the API rather than objects
+/// describing it.
+pub(crate) fn is_value(key: &str) -> bool {
+    VALUES.contains(&key)
+        || (key.starts_with("x-") && !NAMED.contains(&key) && !SCHEMA_EXTENSIONS.contains(&key))
+}
+
+/// Calls `visit` with every object describing the API within `value` and its pointer, which
+/// is `pointer` followed by the pointer relative to `value`, before the objects within it.
+pub(crate) fn objects(value: &Value, pointer: &mut String, visit: &mut impl FnMut(&Value, &str)) {
+    match value {
+        Value::Object(object) => {
+            visit(value, pointer);
+            for (key, value) in object.iter().filter(|(key, _)| !is_value(key)) {
+                let len = pointer.len();
+                push(pointer, key);
+                match value {
+                    Value::Object(members) if NAMED.contains(&key.as_str()) => {
+                        for (name, member) in named(key, members) {
+                            let len = pointer.len();
+                            push(pointer, name);
+                            objects(member, pointer, visit);
+                            pointer.truncate(len);
+                        }
+                    }
+                    value => objects(value, pointer, visit),
+                }
+                pointer.truncate(len);
+            }
+        }
+        Value::Array(values) => {
+            for (i, value) in values.iter().enumerate() {
+                let len = pointer.len();
+                pointer.push_str(&format!("/{i}"));
+                objects(value, pointer, visit);
+                pointer.truncate(len);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Like [`objects`], calling `enter` with every object before and `leave` after walking the
+/// objects within it.
+pub(crate) fn objects_mut(
+    value: &mut Value,
+    pointer: &mut String,
+    enter: &mut impl FnMut(&mut Map<String, Value>, &str),
+    leave: &mut impl FnMut(&mut Map<String, Value>, &str),
+) {
+    match value {
+        Value::Object(object) => {
+            enter(object, pointer);
+            for (key, value) in object.iter_mut().filter(|(key, _)| !is_value(key)) {
+                let len = pointer.len();
+                push(pointer, key);
+                match value {
+                    Value::Object(members) if NAMED.contains(&key.as_str()) => {
+                        for (name, member) in named_mut(key, members) {
+                            let len = pointer.len();
+                            push(pointer, name);
+                            objects_mut(member, pointer, enter, leave);
+                            pointer.truncate(len);
+                        }
+                    }
+                    value => objects_mut(value, pointer, enter, leave),
+                }
+                pointer.truncate(len);
+            }
+            leave(object, pointer);
+        }
+        Value::Array(values) => {
+            for (i, value) in values.iter_mut().enumerate() {
+                let len = pointer.len();
+                pointer.push_str(&format!("/{i}"));
+                objects_mut(value, pointer, enter, leave);
+                pointer.truncate(len);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Like [`objects_mut`] without a `leave`.
+pub(crate) fn enter_objects(
+    value: &mut Value,
+    pointer: &mut String,
+    enter: &mut impl FnMut(&mut Map<String, Value>, &str),
+) {
+    objects_mut(value, pointer, enter, &mut |_, _| {});
+}
+
+/// The members of the [`NAMED`] map `key` named by the document, without the extensions it
+/// may hold.
+pub(crate) fn named<'a>(
+    key: &str,
+    members: &'a Map<String, Value>,
+) -> impl Iterator<Item = (&'a String, &'a Value)> {
+    let extensible = EXTENSIBLE.contains(&key);
+    members
+        .iter()
+        .filter(move |(name, _)| !(extensible && name.starts_with("x-")))
+}
+
+/// Like [`named`], with mutable members.
+pub(crate) fn named_mut<'a>(
+    key: &str,
+    members: &'a mut Map<String, Value>,
+) -> impl Iterator<Item = (&'a String, &'a mut Value)> {
+    let extensible = EXTENSIBLE.contains(&key);
+    members
+        .iter_mut()
+        .filter(move |(name, _)| !(extensible && name.starts_with("x-")))
+}
+
+fn push(pointer: &mut String, key: &str) {
+    pointer.push('/');
+    pointer.push_str(&diagnostics::escape(key));
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn walk_named_members() {
+        let document = json!({
+            "paths": {
+                "/pets": {"get": {"responses": {"200": {"description": "ok"}}}},
+                "x-internal": {"type": "object"}
+            },
+            "definitions": {
+                "example": {
+                    "type": "object",
+                    "example": {"type": "string"},
+                    "properties": {"default": {"type": "string", "default": {"a": 1}}},
+                    "x-patternProperties": {"^x-": {"type": "integer"}},
+                    "x-go-name": {"type": "string"}
+                }
+            }
+        });
+
+        let mut pointers = Vec::new();
+        objects(&document, &mut String::new(), &mut |_, pointer| {
+            pointers.push(pointer.to_string())
+        });
+
+        assert_eq!(
+            pointers,
+            [
+                "",
+                "/definitions/example",
+                "/definitions/example/properties/default",
+                "/definitions/example/x-patternProperties/^x-",
+                "/paths/~1pets",
+                "/paths/~1pets/get",
+                "/paths/~1pets/get/responses/200",
+            ]
+        );
+    }
+}